@@ -7,47 +7,23 @@ in vec4 FragPosLightSpace;
 
 out vec4 FragColor;
 
-uniform vec3 lightPos;
-uniform vec3 viewPos;
+#include "shaders/camera.glsl"
+#include "shaders/light.glsl"
 uniform vec3 baseColor;
 uniform float metallic;
 uniform float roughness;
-uniform sampler2DShadow shadowMap;
 uniform sampler2D baseColorTexture;
 uniform int useBaseColorTexture;
 
-float ShadowCalculation(vec4 fragPosLightSpace, vec3 normal, vec3 lightDir)
-{
-    vec3 projCoords = fragPosLightSpace.xyz / fragPosLightSpace.w;
-    projCoords = projCoords * 0.5 + 0.5;
-
-    if (projCoords.z > 1.0)
-        return 0.0;
-
-    //float bias = max(0.01 * (1.0 - dot(normal, lightDir)), 0.001);
-    float shadow = 0.0;
-
-    vec2 texelSize = 1.0 / textureSize(shadowMap, 0);
-
-    float cnt = 0.0;
-    for (int x = -1; x <= 1; ++x)
-    {
-        for (int y = -1; y <= 1; ++y)
-        {
-            cnt = cnt + 1.0;
-            vec2 offset = vec2(x, y) * texelSize;
-            shadow += texture(shadowMap, vec3(projCoords.xy + offset, projCoords.z));
-        }
-    }
+#ifdef SHADOWS
+#include "shaders/shadow.glsl"
+#endif
 
-    shadow /= cnt;
-    return mix(0.3, 1.0, shadow); // soft shadow
-}
 void main()
 {
     vec3 norm = normalize(Normal);
-    vec3 lightDir = normalize(lightPos - FragPos);
-    vec3 viewDir  = normalize(viewPos - FragPos);
+    vec3 lightDir = normalize(lightPos.xyz - FragPos);
+    vec3 viewDir  = normalize(cameraPos.xyz - FragPos);
     vec3 halfwayDir = normalize(lightDir + viewDir);
     float diff = max(dot(norm, lightDir), 0.0);
     float spec = pow(max(dot(norm, halfwayDir), 0.0), 32.0 * (1.0 - roughness));
@@ -62,7 +38,11 @@ void main()
     vec3 specular = spec * mix(vec3(0.04), baseColorFinal, metallic);
     vec3 ambient = 0.3 * baseColorFinal;
 
+#ifdef SHADOWS
     float shadow = ShadowCalculation(FragPosLightSpace, norm, lightDir);
+#else
+    float shadow = 1.0;
+#endif
     vec3 lighting = ambient + (diffuse + specular) * shadow;
     FragColor = vec4(lighting, 1.0);
     //FragColor = vec4(vec3(shadow), 1.0);
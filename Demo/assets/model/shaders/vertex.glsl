@@ -4,10 +4,8 @@ layout(location = 0) in vec3 aPos;
 layout(location = 1) in vec3 aNormal;
 layout(location = 2) in vec2 aTexCoords;
 
-uniform mat4 model;
-uniform mat4 view;
-uniform mat4 projection;
-uniform mat4 lightSpaceMatrix;
+#include "shaders/mvp.glsl"
+#include "shaders/light.glsl"
 
 out vec3 FragPos;
 out vec3 Normal;
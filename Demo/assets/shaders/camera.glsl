@@ -0,0 +1,7 @@
+// comun tuturor programelor, scris o data pe frame (uniforms::CameraBlock)
+layout(std140) uniform Camera
+{
+    mat4 projection;
+    mat4 view;
+    vec4 cameraPos; // w nefolosit
+};
@@ -0,0 +1,6 @@
+// scris de Light::add_light (uniforms::LightBlock)
+layout(std140) uniform Light
+{
+    mat4 lightSpaceMatrix;
+    vec4 lightPos; // w nefolosit
+};
@@ -0,0 +1,3 @@
+// obiect -> lume -> camera -> ecran; view si projection vin din blocul Camera
+uniform mat4 model;
+#include "shaders/camera.glsl"
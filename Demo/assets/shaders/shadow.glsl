@@ -0,0 +1,30 @@
+// umbra cu PCF 3x3 din shadow map-ul luminii: 0.3 in umbra completa, 1.0 in lumina
+uniform sampler2DShadow shadowMap;
+
+float ShadowCalculation(vec4 fragPosLightSpace, vec3 normal, vec3 lightDir)
+{
+    vec3 projCoords = fragPosLightSpace.xyz / fragPosLightSpace.w;
+    projCoords = projCoords * 0.5 + 0.5;
+
+    if (projCoords.z > 1.0)
+        return 0.0;
+
+    //float bias = max(0.01 * (1.0 - dot(normal, lightDir)), 0.001);
+    float shadow = 0.0;
+
+    vec2 texelSize = 1.0 / textureSize(shadowMap, 0);
+
+    float cnt = 0.0;
+    for (int x = -1; x <= 1; ++x)
+    {
+        for (int y = -1; y <= 1; ++y)
+        {
+            cnt = cnt + 1.0;
+            vec2 offset = vec2(x, y) * texelSize;
+            shadow += texture(shadowMap, vec3(projCoords.xy + offset, projCoords.z));
+        }
+    }
+
+    shadow /= cnt;
+    return mix(0.3, 1.0, shadow); // soft shadow
+}
@@ -6,7 +6,7 @@ use crate::seb::gui::panel::{self, Panel, PanelRenderer};
 use crate::seb::gui::text;
 use crate::seb::gui::window::{GuiBuilder, GuiRenderer};
 use crate::seb::planet::Planet;
-use crate::seb::player::Player;
+use crate::seb::player::{Player, PlayerMode};
 use crate::seb::primitives::{
     Line, LineRenderer, Rectangle, RectangleRenderer, Sphere, SphereRenderer, Vector,
     VectorRenderer,
@@ -15,6 +15,18 @@ use crate::seb::skybox::Skybox;
 use gl::{BLEND, NONE};
 use nalgebra_glm as glm;
 use seb::collision::{Collider, collide};
+use seb::debug_draw::{DebugCategory, DebugDraw};
+use seb::gravity::{Falloff, GravityField};
+use seb::heightfield::Heightfield;
+use seb::input::InputMap;
+use seb::joints::Joint;
+use seb::orbit::{Integrator, OrbitalElements, SolarSystem};
+use seb::timestep::{FixedTimestep, interpolate};
+use seb::physics::{PhysicsWorld, RigidBody, TERRAIN, TriggerPhase};
+use seb::bvh::MeshBvh;
+use seb::raycast::{Target, raycast};
+use seb::shader_registry::ShaderRegistry;
+use seb::uniforms::{self, CameraBlock, UniformBuffer};
 use seb::gui::text::{TextBoxD, TextBoxRenderer, TextFont};
 use seb::gui::window;
 use seb::model::*;
@@ -29,17 +41,25 @@ fn demo1() {
     println!("╠════════════════════════════════════════════════════════════════╣");
     println!("║  [TAB]     - Intrare/Ieșire din modul player                   ║");
     println!("║  [ESC]     - Închidere program                                 ║");
-    println!("║  [SPACE]   - Resetare obiecte în cădere                        ║");
+    println!("║  [R]       - Resetare obiecte în cădere                        ║");
     println!("║                                                                ║");
     println!("║  Modul Player:                                                 ║");
     println!("║    W/A/S/D - Mișcare (înainte/stânga/înapoi/dreapta)           ║");
     println!("║    Mouse   - Rotire cameră                                     ║");
+    println!("║                                                                ║");
+    println!("║  Debug fizică:                                                 ║");
+    println!("║    F1      - Contururi collidere                               ║");
+    println!("║    F2      - Arborele din broadphase                           ║");
+    println!("║    F3      - Puncte de contact și normale                      ║");
+    println!("║    F4      - Viteze                                            ║");
+    println!("║    F5      - Articulații                                       ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
 
     let mut window = seb::window::Window::new();
-    window.create(1000, 800, "Window");
+    window.create_from_args(1000, 800, "Window");
 
     let mut player = Player::new(0.1);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
     let skybox = Skybox::new([
         "./assets/spaceskybox/right.png",  // +X
@@ -48,7 +68,7 @@ fn demo1() {
         "./assets/spaceskybox/bottom.png", // -Y
         "./assets/spaceskybox/front.png",  // +Z
         "./assets/spaceskybox/back.png",   // -Z
-    ]);
+    ]).unwrap();
 
     let mut rc1 = Rectangle::new();
     rc1.color = glm::vec4(1.0, 0.0, 0.0, 1.0);
@@ -73,7 +93,7 @@ fn demo1() {
     rc4.y_angle = 12.0;
     rc4.z_angle = 124.0;
 
-    let rcr = RectangleRenderer::new();
+    let rcr = RectangleRenderer::new().unwrap();
 
     let mut sc1 = Sphere::new();
     sc1.scale = 0.4;
@@ -85,20 +105,81 @@ fn demo1() {
     sc2.position = glm::vec3(-0.5, 1.5, 0.0);
     sc2.color = glm::vec4(0.0, 1.0, 1.0, 1.0);
 
-    let scr = SphereRenderer::new();
+    let scr = SphereRenderer::new().unwrap();
+
+    // rc1, rc2 și sc1 sunt mișcate de mână, rc3 și sc2 sunt lăsate fizicii
+    let mut world = PhysicsWorld::new();
+    let rc1_body = world.add_body(RigidBody::fixed(Collider::Rectangle(rc1)));
+    let rc2_body = world.add_body(RigidBody::fixed(Collider::Rectangle(rc2)));
+    let sc1_body = world.add_body(RigidBody::fixed(Collider::Sphere(sc1)));
+    let rc3_start = rc3;
+    let sc2_start = sc2;
+    let new_rc3_body = || {
+        let mut body = RigidBody::new(Collider::Rectangle(rc3_start), 1.0);
+        body.can_sleep = false;
+        body
+    };
+    let new_sc2_body = || {
+        let mut body = RigidBody::new(Collider::Sphere(sc2_start), 0.5);
+        body.restitution = 0.6;
+        body.can_sleep = false;
+        body.ccd = true;
+        body
+    };
+    let rc3_body = world.add_body(new_rc3_body());
+    let sc2_body = world.add_body(new_sc2_body());
+
+    // zonă nevăzută sub placă: ce cade de pe ea e pus înapoi sus
+    let mut fall_zone = Rectangle::new();
+    fall_zone.position = glm::vec3(0.0, -1.5, 0.0);
+    fall_zone.scale = glm::vec3(3.0, 0.5, 3.0);
+    let mut fall_zone_body = RigidBody::fixed(Collider::Rectangle(fall_zone));
+    fall_zone_body.trigger = true;
+    let fall_zone_body = world.add_body(fall_zone_body);
+
+    // o tăbliță prinsă cu o balama de lume, împinsă la Space
+    let mut sign = Rectangle::new();
+    sign.color = glm::vec4(1.0, 0.5, 0.0, 1.0);
+    sign.position = glm::vec3(0.0, 1.0, -0.8);
+    sign.scale = glm::vec3(0.3, 0.2, 0.02);
+    let sign_body = world.add_body(RigidBody::new(Collider::Rectangle(sign), 0.5));
+    world.add_joint(
+        Joint::hinge(sign_body, TERRAIN, glm::vec3(0.0, 1.2, -0.8), glm::vec3(1.0, 0.0, 0.0))
+            .with_limit(-1.2, 1.2),
+    );
+
+    let mut debug = DebugDraw::new();
 
+    // fizica merge cu 60 de pași pe secundă oricât de repede desenăm
+    let mut clock = FixedTimestep::new(60.0);
+    let mut previous: Vec<Collider> = world.bodies.iter().map(|b| b.collider.clone()).collect();
     let mut time = 0.0f32;
     while window.is_open() {
         window.set_color(0.0, 0.0, 0.0, 1.0);
         window.poll_events();
+        input.update(&window);
+        let steps = clock.advance(window.delta_time());
 
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        if input.pressed("quit") {
             window.close();
         }
 
-        player.bind(&mut window, 0.01);
+        player.bind(&mut window, &input, 0.6 * clock.frame_time());
         skybox.draw(player.projection, player.view);
 
+        let debug_actions = [
+            "debug_shapes",
+            "debug_broadphase",
+            "debug_contacts",
+            "debug_velocities",
+            "debug_joints",
+        ];
+        for (action, category) in debug_actions.into_iter().zip(DebugCategory::ALL) {
+            if input.pressed(action) {
+                debug.toggle(category);
+            }
+        }
+
         // if window.get_key(glfw::Key::Space) == glfw::Action::Press {
         //     rc2.position = glm::vec3(0.0, 1.5, 0.0);
         // }
@@ -125,49 +206,67 @@ fn demo1() {
         //     sc1.position += vec;
         // }
 
-        if window.get_key(glfw::Key::Space).unwrap() == glfw::Action::Press {
-            rc3.position = glm::vec3(0.5, 1.5, 0.0);
-            sc2.position = glm::vec3(-0.5, 1.5, 0.0);
+        if input.pressed("reset") {
+            *world.body_mut(rc3_body) = new_rc3_body();
+            *world.body_mut(sc2_body) = new_sc2_body();
+            world
+                .body_mut(sign_body)
+                .apply_impulse(glm::vec3(0.0, 0.0, 0.05), glm::vec3(0.0, 0.8, -0.8));
         }
 
-        rc3.position -= glm::vec3(0.0, 0.01, 0.0);
-        sc2.position -= glm::vec3(0.0, 0.02, 0.0);
+        for _ in 0..steps {
+            previous = world.bodies.iter().map(|b| b.collider.clone()).collect();
 
-        sc1.position.x += time.sin() * 0.005;
-        sc1.position.z += time.cos() * 0.005;
+            sc1.position.x += time.sin() * 0.005;
+            sc1.position.z += time.cos() * 0.005;
 
-        rc1.z_angle = time.sin() * 5.0;
-        rc1.x_angle = time.cos() * 5.0;
+            rc1.z_angle = time.sin() * 5.0;
+            rc1.x_angle = time.cos() * 5.0;
 
-        rc2.y_angle += time.sin();
+            rc2.y_angle += time.sin();
 
-        if let Some(mtv) = collide(Collider::Rectangle(rc3), Collider::Rectangle(rc1)) {
-            rc3.position += mtv;
-        }
-        if let Some(mtv) = collide(Collider::Rectangle(rc3), Collider::Rectangle(rc2)) {
-            rc3.position += mtv;
-        }
-        if let Some(mtv) = collide(Collider::Rectangle(rc3), Collider::Sphere(sc1)) {
-            rc3.position += mtv;
-        }
-        if let Some(mtv) = collide(Collider::Sphere(sc2), Collider::Rectangle(rc1)) {
-            sc2.position += mtv;
+            world.body_mut(rc1_body).collider = Collider::Rectangle(rc1);
+            world.body_mut(rc2_body).collider = Collider::Rectangle(rc2);
+            world.body_mut(sc1_body).collider = Collider::Sphere(sc1);
+
+            world.step(clock.step);
+
+            let fallen: Vec<usize> = world
+                .trigger_events()
+                .iter()
+                .filter(|e| e.trigger == fall_zone_body && e.phase == TriggerPhase::Enter)
+                .map(|e| e.other)
+                .collect();
+            for body in fallen {
+                if body == rc3_body {
+                    *world.body_mut(rc3_body) = new_rc3_body();
+                } else if body == sc2_body {
+                    *world.body_mut(sc2_body) = new_sc2_body();
+                }
+                println!("corpul {} a căzut de pe placă", body);
+            }
+
+            time += clock.step;
         }
-        if let Some(mtv) = collide(Collider::Sphere(sc2), Collider::Rectangle(rc2)) {
-            sc2.position += mtv;
+
+        // desenăm între ultimele două stări, ca mișcarea să nu sacadeze
+        let alpha = clock.alpha();
+        let drawn = |body: usize| interpolate(&previous[body], &world.body(body).collider, alpha);
+        if let Collider::Rectangle(r) = drawn(rc3_body) {
+            rc3 = r;
         }
-        if let Some(mtv) = collide(Collider::Sphere(sc2), Collider::Sphere(sc1)) {
-            sc2.position += mtv;
+        if let Collider::Sphere(s) = drawn(sc2_body) {
+            sc2 = s;
         }
-        if let Some(mtv) = collide(Collider::Sphere(sc2), Collider::Rectangle(rc3)) {
-            sc2.position += mtv / 2.0;
-            rc3.position -= mtv / 2.0;
+        if let Collider::Rectangle(r) = drawn(sign_body) {
+            sign = r;
         }
 
-        rcr.draw(player.projection, player.view, &mut [rc1, rc2, rc3]);
+        rcr.draw(player.projection, player.view, &mut [rc1, rc2, rc3, sign]);
         scr.draw(player.projection, player.view, &mut [sc1, sc2]);
 
-        time += 0.01;
+        debug.world(&world);
+        debug.draw(player.projection, player.view).unwrap();
 
         window.swap_buffers();
     }
@@ -186,9 +285,10 @@ fn demo2() {
     println!("║    Mouse   - Rotire cameră                                     ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = Window::new();
-    window.create(1000, 800, "Window");
+    window.create_from_args(1000, 800, "Window");
 
     let mut player = Player::new(0.0);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
     let skybox = Skybox::new([
         "./assets/spaceskybox/right.png",  // +X
@@ -197,9 +297,9 @@ fn demo2() {
         "./assets/spaceskybox/bottom.png", // -Y
         "./assets/spaceskybox/front.png",  // +Z
         "./assets/spaceskybox/back.png",   // -Z
-    ]);
+    ]).unwrap();
 
-    let mut lr = LineRenderer::new();
+    let mut lr = LineRenderer::new().unwrap();
 
     let mut line1 = Line::from(glm::vec3(0.0, 0.0, 0.0), glm::vec3(1.0, 1.0, 1.0));
     line1.color = glm::vec4(1.0, 0.0, 0.0, 1.0);
@@ -216,7 +316,7 @@ fn demo2() {
     let mut line5 = Line::from(glm::vec3(0.5, 0.5, 0.0), glm::vec3(1.5, 1.5, 1.0));
     line5.color = glm::vec4(1.0, 0.0, 1.0, 1.0);
 
-    let mut vr = VectorRenderer::new();
+    let mut vr = VectorRenderer::new().unwrap();
 
     let mut vector1 = Vector::from(glm::vec3(0.0, 0.0, 1.0), glm::vec3(1.0, 0.5, 1.0));
     vector1.color = glm::vec4(0.5, 0.0, 0.5, 1.0);
@@ -233,7 +333,7 @@ fn demo2() {
     let mut vector5 = Vector::from(glm::vec3(1.0, 2.0, 1.0), glm::vec3(2.0, 3.0, 1.5));
     vector5.color = glm::vec4(0.0, 1.0, 0.5, 1.0);
 
-    let mut rr = RectangleRenderer::new();
+    let mut rr = RectangleRenderer::new().unwrap();
 
     let mut rectangle1 = Rectangle::new();
     rectangle1.position = glm::vec3(1.0, 2.0, 0.0);
@@ -275,7 +375,7 @@ fn demo2() {
     rectangle5.y_angle = 20.0;
     rectangle5.z_angle = 45.0;
 
-    let mut sr = SphereRenderer::new();
+    let mut sr = SphereRenderer::new().unwrap();
 
     let mut sphere1 = Sphere::new();
     sphere1.position = glm::vec3(1.0, 2.0, 0.0);
@@ -302,14 +402,17 @@ fn demo2() {
     sphere5.scale = 1.3;
     sphere5.color = glm::vec4(1.0, 0.0, 1.0, 1.0);
 
+    let mut clock = FixedTimestep::new(60.0);
     while window.is_open() {
         window.set_color(0.0, 0.0, 0.2, 1.0);
         window.poll_events();
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        input.update(&window);
+        clock.advance(window.delta_time());
+        if input.pressed("quit") {
             window.close();
         }
 
-        player.bind(&mut window, 0.1);
+        player.bind(&mut window, &input, 6.0 * clock.frame_time());
 
         lr.draw(
             player.projection,
@@ -352,11 +455,14 @@ fn demo3() {
     println!("║                                                                ║");
     println!("║  Modul Gravitație Planetă:                                     ║");
     println!("║    [SPACE] - Săritură pe planetă                               ║");
+    println!("║                                                                ║");
+    println!("║  [B]       - Aruncă o sferă sau o cutie în fața camerei        ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = seb::window::Window::new();
-    window.create(1000, 800, "Window");
+    window.create_from_args(1000, 800, "Window");
 
     let mut player = Player::new(10.4);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
     let skybox = Skybox::new([
         "./assets/spaceskybox/right.png",  // +X
@@ -365,7 +471,7 @@ fn demo3() {
         "./assets/spaceskybox/bottom.png", // -Y
         "./assets/spaceskybox/front.png",  // +Z
         "./assets/spaceskybox/back.png",   // -Z
-    ]);
+    ]).unwrap();
 
     let mut planet = Planet::new();
     planet
@@ -375,60 +481,104 @@ fn demo3() {
     planet
         .load_texture("./assets/planet/worldgen1.jpg")
         .unwrap();
-    planet.init();
+    planet.init().unwrap();
+
+    // obiectele aruncate cad spre centrul planetei și se opresc pe relief
+    let mut world = PhysicsWorld::new();
+    world.gravity = glm::Vec3::zeros();
+    world
+        .fields
+        .push(GravityField::from_planet(&planet, 2.0, Falloff::InverseSquare));
+    world.terrains.push(Heightfield::from_planet(&planet));
+    let rcr = RectangleRenderer::new().unwrap();
+    let scr = SphereRenderer::new().unwrap();
 
     let mut on_planet: bool = false;
-    let mut h: f32 = 0.0;
 
-    let mut jump = false;
+    // pe planetă camera merge cu controllerul, la scara reliefului
+    player.eye_height = 0.05;
+    player.character.radius = 0.03;
+    player.character.half_height = 0.05;
+    player.character.step_height = 0.03;
+    player.character.snap_distance = 0.03;
+    player.character.skin = 0.001;
+    player.character.jump_speed = 0.9;
+
+    let mut clock = FixedTimestep::new(60.0);
     while window.is_open() {
         window.set_color(0.0, 0.0, 0.0, 1.0);
         window.poll_events();
+        input.update(&window);
+        let steps = clock.advance(window.delta_time());
 
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        if input.pressed("quit") {
             break;
         }
 
-        if let Some(key) = window.keyboard.find_key(glfw::Key::Enter)
-            && key.action == glfw::Action::Press
-        {
+        if input.pressed("toggle_planet") {
             on_planet = !on_planet;
+            if on_planet {
+                player.character.gravity = world.gravity_at(&player.cam_pos);
+                player.set_mode(PlayerMode::Walk);
+                // controllerul nu scoate capsula din planetă, așa că pornim de pe suprafață
+                let up = player.character.up();
+                player.character.position = planet.get_position_on_sphere(player.cam_pos, 0.0)
+                    + up * (player.character.half_height + player.character.radius);
+            } else {
+                player.set_mode(PlayerMode::Noclip);
+                player.cam_up = glm::vec3(0.0, 1.0, 0.0);
+            }
         }
 
         if on_planet {
-            if window.get_key(glfw::Key::Space).unwrap() == glfw::Action::Press {
-                if !jump {
-                    h += 0.02;
-                    jump = true;
-                }
-            }
-            h -= 0.001;
-
-            let ground_pos = planet.get_position_on_sphere(player.cam_pos, 0.1);
-
-            player.add_height(planet.position, h);
+            // gravitația camerei e aceeași cu a obiectelor aruncate
+            player.character.gravity = world.gravity_at(&player.character.position);
+            player.bind_walk(
+                &mut window,
+                &input,
+                &[Target::Planet(&planet)],
+                0.6,
+                clock.frame_time(),
+            );
+        } else {
+            player.bind(&mut window, &input, 6.0 * clock.frame_time());
+        }
 
-            let d1 = glm::length(&(ground_pos - planet.position));
-            let d2 = glm::length(&(player.cam_pos - planet.position));
+        if input.pressed("spawn") {
+            let forward = glm::normalize(&(player.cam_center - player.cam_pos));
+            let position = player.cam_pos + forward * 0.5;
+            let collider = if world.bodies.len().is_multiple_of(2) {
+                let mut sphere = Sphere::new();
+                sphere.position = position;
+                sphere.scale = 0.05;
+                sphere.color = glm::vec4(0.0, 1.0, 1.0, 1.0);
+                Collider::Sphere(sphere)
+            } else {
+                let mut rect = Rectangle::new();
+                rect.position = position;
+                rect.scale = glm::vec3(0.05, 0.05, 0.05);
+                rect.color = glm::vec4(1.0, 0.5, 0.0, 1.0);
+                Collider::Rectangle(rect)
+            };
+            world.add_body(RigidBody::new(collider, 1.0));
+        }
+        for _ in 0..steps {
+            world.step(clock.step);
+        }
 
-            if d2 < d1 {
-                h = 0.0;
-                jump = false;
-                player.set_height(ground_pos, planet.position);
-            }
+        planet.draw(player.projection, player.view, player.cam_pos);
 
-            player.bind_sphere(&mut window, 0.01, planet.position, 1.0);
-        } else {
-            let dist = glm::length(&(player.cam_pos - planet.position));
-            if dist < planet.scale {
-                let ground_pos = planet.get_position_on_sphere(player.cam_pos, 0.1);
-                player.set_height(ground_pos, planet.position);
+        let mut spheres: Vec<Sphere> = Vec::new();
+        let mut rects: Vec<Rectangle> = Vec::new();
+        for body in &world.bodies {
+            match &body.collider {
+                Collider::Sphere(s) => spheres.push(*s),
+                Collider::Rectangle(r) => rects.push(*r),
+                _ => {}
             }
-            player.cam_up = glm::vec3(0.0, 1.0, 0.0);
-            player.bind(&mut window, 0.1);
         }
-
-        planet.draw(player.projection, player.view, player.cam_pos);
+        rcr.draw(player.projection, player.view, &mut rects);
+        scr.draw(player.projection, player.view, &mut spheres);
 
         skybox.draw(player.projection, player.view);
         window.swap_buffers();
@@ -449,15 +599,27 @@ fn demo4() {
     println!("║                                                                ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = seb::window::Window::new();
-    window.create(1000, 800, "Window");
-
+    window.create_from_args(1000, 800, "Window");
+
+    // shaderele modelului se reîncarcă singure când sunt salvate
+    let mut shaders = ShaderRegistry::new();
+    let model_shader = shaders
+        .load(
+            seb::model::VERTEX_SHADER,
+            seb::model::FRAGMENT_SHADER,
+            seb::model::DEFINES,
+        )
+        .unwrap();
     let mut model = Model::new("./assets/model/casa.glb");
-    model.init();
+    model.init_with_shader(shaders.program(model_shader));
 
     let mut player = Player::new(0.1);
+    // projection, view și poziția camerei, comune tuturor shaderelor care le cer
+    let mut camera = UniformBuffer::new(uniforms::CAMERA_BINDING);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
     let mut light = Light::new();
-    light.init_shadow(4096, 4096);
+    light.init_shadow(4096, 4096).unwrap();
     light.add_light(glm::vec3(1.0, 1.0, 1.0), glm::vec3(-1.0, -1.0, -1.0));
 
     let skybox = Skybox::new([
@@ -467,10 +629,20 @@ fn demo4() {
         "./assets/spaceskybox/bottom.png", // -Y
         "./assets/spaceskybox/front.png",  // +Z
         "./assets/spaceskybox/back.png",   // -Z
-    ]);
+    ]).unwrap();
 
     let mut time: f32 = 0f32;
+    let mut clock = FixedTimestep::new(60.0);
     while window.is_open() {
+        window.poll_events();
+        input.update(&window);
+        clock.advance(window.delta_time());
+        if shaders.update() {
+            model.shader_id = shaders.program(model_shader);
+        }
+        for error in shaders.take_errors() {
+            println!("{}", error);
+        }
         light.add_light(
             glm::vec3(time.sin() * 2.0, 1.0, time.cos() * 2.0),
             glm::vec3(0.0, 0.0, 0.0),
@@ -482,16 +654,16 @@ fn demo4() {
         light.unbind_shadow();
 
         window.set_color(0.0, 0.0, 0.0, 1.0);
-        window.poll_events();
 
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        if input.pressed("quit") {
             break;
         }
 
-        player.bind(&mut window, 0.1);
+        player.bind(&mut window, &input, 6.0 * clock.frame_time());
+        camera.update(&CameraBlock::new(&player.projection, &player.view, &player.cam_pos));
 
-        model.draw(player.projection, player.view, player.cam_pos, light);
-        time += 0.01;
+        model.draw(&light);
+        time += 0.6 * clock.frame_time();
         skybox.draw(player.projection, player.view);
         window.swap_buffers();
     }
@@ -503,26 +675,50 @@ fn demo5() {
     println!("║                      CONTROALE JOC                             ║");
     println!("╠════════════════════════════════════════════════════════════════╣");
     println!("║  [TAB]     - Intrare/Ieșire din modul player                   ║");
+    println!("║  [F]       - Mers (gravitație, coliziuni) / zbor liber         ║");
     println!("║  [ESC]     - Închidere program                                 ║");
     println!("║                                                                ║");
     println!("║  Modul Player:                                                 ║");
     println!("║    W/A/S/D     - Mișcare (înainte/stânga/înapoi/dreapta)       ║");
     println!("║    Mouse       - Rotire cameră                                 ║");
+    println!("║    Space       - Săritură (în modul mers)                      ║");
     println!("║    Click stânga - Tragere pistol                               ║");
     println!("║                                                                ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = seb::window::Window::new();
-    window.create(1000, 800, "Window");
-
+    window.create_from_args(1000, 800, "Window");
+
+    // shaderele modelelor se reîncarcă singure când sunt salvate
+    let mut shaders = ShaderRegistry::new();
+    let model_shader = shaders
+        .load(
+            seb::model::VERTEX_SHADER,
+            seb::model::FRAGMENT_SHADER,
+            seb::model::DEFINES,
+        )
+        .unwrap();
     let mut model = Model::new("./assets/model/1911.glb");
-    model.init();
+    model.init_with_shader(shaders.program(model_shader));
     let mut model2 = Model::new("./assets/model/map.glb");
-    model2.init();
+    model2.init_with_shader(shaders.program(model_shader));
+    // geometria hărții pentru coliziunea camerei și tras
+    let level = MeshBvh::from_model(&model2.gltf_model);
 
     let mut player = Player::new(0.1);
+    let mut camera = UniformBuffer::new(uniforms::CAMERA_BINDING);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
+    // caracterul pe scara hărții, care are doar 2 unități
+    player.character.radius = 0.02;
+    player.character.half_height = 0.03;
+    player.character.step_height = 0.02;
+    player.character.snap_distance = 0.02;
+    player.character.skin = 0.001;
+    player.character.gravity = glm::vec3(0.0, -2.0, 0.0);
+    player.character.jump_speed = 0.6;
+    player.eye_height = 0.025;
 
     let mut light = Light::new();
-    light.init_shadow(4096, 4096);
+    light.init_shadow(4096, 4096).unwrap();
     light.add_light(glm::vec3(1.0, 1.0, 1.0), glm::vec3(-1.0, -1.0, -1.0));
 
     let skybox = Skybox::new([
@@ -532,11 +728,22 @@ fn demo5() {
         "./assets/spaceskybox/bottom.png", // -Y
         "./assets/spaceskybox/front.png",  // +Z
         "./assets/spaceskybox/back.png",   // -Z
-    ]);
+    ]).unwrap();
 
     let mut time = 0.0;
     let mut anim = false;
+    let mut clock = FixedTimestep::new(60.0);
     while window.is_open() {
+        window.poll_events();
+        input.update(&window);
+        clock.advance(window.delta_time());
+        if shaders.update() {
+            model.shader_id = shaders.program(model_shader);
+            model2.shader_id = shaders.program(model_shader);
+        }
+        for error in shaders.take_errors() {
+            println!("{}", error);
+        }
         light.bind_shadow();
 
         model.draw_for_shadow(&light);
@@ -545,24 +752,48 @@ fn demo5() {
         light.unbind_shadow();
 
         window.set_color(0.0, 0.0, 0.0, 1.0);
-        window.poll_events();
 
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        if input.pressed("quit") {
             break;
         }
 
-        player.bind(&mut window, 0.1);
+        if input.pressed("toggle_walk") {
+            player.set_mode(match player.mode {
+                PlayerMode::Noclip => PlayerMode::Walk,
+                PlayerMode::Walk => PlayerMode::Noclip,
+            });
+        }
+        match player.mode {
+            PlayerMode::Noclip => {
+                player.bind(&mut window, &input, 6.0 * clock.frame_time());
+                if let Some(mtv) = level.sphere_mtv(&player.cam_pos, 0.05) {
+                    player.cam_pos += mtv;
+                    player.cam_center += mtv;
+                    player.view =
+                        glm::look_at(&player.cam_pos, &player.cam_center, &player.cam_up);
+                }
+            }
+            PlayerMode::Walk => {
+                let dt = clock.frame_time();
+                player.bind_walk(&mut window, &input, &[Target::Mesh(&level)], 0.3, dt);
+            }
+        }
 
-        if window.get_key(glfw::Key::Space).unwrap() == glfw::Action::Press {
+        if input.pressed("reset") {
             time = 0.0;
         }
-        if let Some(action) = window.get_mouse_button(glfw::MouseButton::Button1) {
-            if action == glfw::Action::Press {
-                anim = true;
-            } else if action == glfw::Action::Release {
-                anim = false;
+        // tragem doar pe frontul apăsării, nu cât timp ținem apăsat
+        if input.pressed("shoot") {
+            let dir = player.cam_center - player.cam_pos;
+            let targets = [Target::Mesh(&level)];
+            if let Some(hit) = raycast(&targets, player.cam_pos, dir, 100.0) {
+                println!(
+                    "Lovit {:?} la {:.2} in ({:.2}, {:.2}, {:.2})",
+                    hit.part, hit.distance, hit.point.x, hit.point.y, hit.point.z
+                );
             }
         }
+        anim = input.down("shoot");
         if anim {
             if time > 1.0 {
                 time = 0.0;
@@ -572,10 +803,11 @@ fn demo5() {
         for i in 0..8 {
             model.apply_animation(i, time);
         }
-        model.draw(player.projection, player.view, player.cam_pos, light);
-        model2.draw(player.projection, player.view, player.cam_pos, light);
+        camera.update(&CameraBlock::new(&player.projection, &player.view, &player.cam_pos));
+        model.draw(&light);
+        model2.draw(&light);
 
-        time += 0.1;
+        time += 6.0 * clock.frame_time();
 
         skybox.draw(player.projection, player.view);
         window.swap_buffers();
@@ -593,9 +825,10 @@ fn demo6() {
     println!("║        stil ImGui. Work in progress!                           ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = Window::new();
-    window.create(1000, 800, "Window");
+    window.create_from_args(1000, 800, "Window");
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
-    let mut gr = GuiRenderer::new("./assets/Roboto-VariableFont_wdth,wght.ttf", 20f32);
+    let mut gr = GuiRenderer::new("./assets/Roboto-VariableFont_wdth,wght.ttf", 20f32).unwrap();
     let mut gb = GuiBuilder::new(
         glm::vec2(0.0, 0.0),
         glm::vec2(window.width as f32, window.height as f32),
@@ -656,7 +889,8 @@ fn demo6() {
     while window.is_open() {
         window.set_color(0.0, 0.0, 0.0, 1.0);
         window.poll_events();
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        input.update(&window);
+        if input.pressed("quit") {
             window.close();
         }
 
@@ -675,7 +909,8 @@ fn demo7() {
     println!("║                                                                ║");
     println!("╚════════════════════════════════════════════════════════════════╝");
     let mut window = Window::new();
-    window.create(1000, 800, "Window");
+    window.create_from_args(1000, 800, "Window");
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
 
     
 
@@ -726,14 +961,15 @@ fn demo7() {
     text4.z_index = 0.1;
 
 
-    let mut pr = PanelRenderer::new();
-    let mut tr = TextBoxRenderer::new();
+    let mut pr = PanelRenderer::new().unwrap();
+    let mut tr = TextBoxRenderer::new().unwrap();
 
 
     while window.is_open() {
         window.set_color(0.0, 0.0, 0.0, 1.0);
         window.poll_events();
-        if window.get_key(glfw::Key::Escape).unwrap() == glfw::Action::Press {
+        input.update(&window);
+        if input.pressed("quit") {
             window.close();
         }
         tr.set_size(window.width, window.height);
@@ -743,7 +979,7 @@ fn demo7() {
         for c in &window.keyboard.char_keys {
             text4.text.push(*c);
         }
-        if let Some(key) = window.keyboard.find_key(glfw::Key::Backspace) && (key.action == glfw::Action::Press || key.action == glfw::Action::Repeat) {
+        if input.repeated("erase") {
             text4.text.pop();
         }
 
@@ -763,6 +999,160 @@ fn demo7() {
     }
 }
 
+#[cfg(feature = "demo8")]
+fn demo8() {
+    println!("╔════════════════════════════════════════════════════════════════╗");
+    println!("║                      SISTEM SOLAR                              ║");
+    println!("╠════════════════════════════════════════════════════════════════╣");
+    println!("║  [TAB]     - Intrare/Ieșire din modul player                   ║");
+    println!("║  [1]-[4]   - Zbor la soare, planete, lună (camera le urmează)  ║");
+    println!("║  [0]       - Cameră liberă                                     ║");
+    println!("║  [SUS/JOS] - Timpul de 2 ori mai repede/încet                  ║");
+    println!("║  [K]       - Orbite Kepler / N-body                            ║");
+    println!("║  [ESC]     - Închidere program                                 ║");
+    println!("╚════════════════════════════════════════════════════════════════╝");
+    let mut window = seb::window::Window::new();
+    window.create_from_args(1000, 800, "Window");
+
+    let mut player = Player::new(25.0);
+    let mut input = InputMap::load_or_default("./assets/input.cfg");
+    player.cam_pos.z = -45.0;
+    player.cam_center.z = -44.0;
+
+    let skybox = Skybox::new([
+        "./assets/spaceskybox/right.png",  // +X
+        "./assets/spaceskybox/left.png",   // -X
+        "./assets/spaceskybox/top.png",    // +Y
+        "./assets/spaceskybox/bottom.png", // -Y
+        "./assets/spaceskybox/front.png",  // +Z
+        "./assets/spaceskybox/back.png",   // -Z
+    ]).unwrap();
+
+    let new_planet = |path: &str, scale: f32| {
+        let mut planet = Planet::new();
+        planet.from_map(path, 0.05, scale).unwrap();
+        planet.generate_terrain();
+        planet.load_texture(path).unwrap();
+        planet.init().unwrap();
+        planet
+    };
+    let mut planets = [
+        new_planet("./assets/planet/worldgen3.jpg", 3.0),
+        new_planet("./assets/planet/worldgen1.jpg", 1.0),
+        new_planet("./assets/planet/worldgen2.jpg", 1.5),
+        new_planet("./assets/planet/worldgen1.jpg", 0.3),
+    ];
+    // reliefurile, ca să nu intrăm cu camera în planete
+    let mut terrains: Vec<Heightfield> = planets.iter().map(Heightfield::from_planet).collect();
+
+    let mut system = SolarSystem::new();
+    let sun = system.add_body(1000.0, glm::Vec3::zeros(), glm::Vec3::zeros());
+    let inner = system.add_orbit(sun, 1.0, OrbitalElements::circular(12.0));
+    let mut elements = OrbitalElements::circular(24.0);
+    elements.eccentricity = 0.1;
+    elements.inclination = 5.0;
+    elements.mean_anomaly = 180.0;
+    let outer = system.add_orbit(sun, 20.0, elements);
+    let moon = system.add_orbit(outer, 0.01, OrbitalElements::circular(3.0));
+    system.bodies[sun].spin = 5.0;
+    system.bodies[inner].spin = 40.0;
+    system.bodies[inner].tilt = 23.0;
+    system.bodies[outer].spin = 25.0;
+    system.bodies[outer].tilt = 10.0;
+    system.bodies[moon].spin = 10.0;
+
+    let focus_actions = ["focus_1", "focus_2", "focus_3", "focus_4"];
+    let mut focus: Option<usize> = None;
+    let mut clock = FixedTimestep::new(60.0);
+    while window.is_open() {
+        window.set_color(0.0, 0.0, 0.0, 1.0);
+        window.poll_events();
+        input.update(&window);
+        clock.advance(window.delta_time());
+
+        if input.pressed("quit") {
+            break;
+        }
+        for (i, action) in focus_actions.into_iter().enumerate() {
+            if input.pressed(action) {
+                // ne punem în spatele corpului, privind spre el
+                let target = system.bodies[i].position;
+                let distance = planets[i].scale * 4.0;
+                player.cam_pos = target + glm::vec3(0.0, distance * 0.25, -distance);
+                player.look_at(target);
+                focus = Some(i);
+            }
+        }
+        if input.pressed("free_camera") {
+            focus = None;
+        }
+        if input.pressed("time_faster") {
+            system.time_scale *= 2.0;
+            println!("timp x{}", system.time_scale);
+        }
+        if input.pressed("time_slower") {
+            system.time_scale *= 0.5;
+            println!("timp x{}", system.time_scale);
+        }
+        if input.pressed("toggle_integrator") {
+            system.integrator = match system.integrator {
+                Integrator::Kepler => Integrator::NBody,
+                Integrator::NBody => Integrator::Kepler,
+            };
+            println!("{:?}", system.integrator);
+        }
+
+        let previous = focus.map(|i| system.bodies[i].position);
+        system.update(clock.frame_time());
+        // camera se mișcă odată cu corpul urmărit
+        if let (Some(i), Some(previous)) = (focus, previous) {
+            let delta = system.bodies[i].position - previous;
+            player.cam_pos += delta;
+            player.cam_center += delta;
+        }
+        for (i, planet) in planets.iter_mut().enumerate() {
+            system.apply(i, planet);
+            terrains[i].follow(planet);
+        }
+
+        player.bind(&mut window, &input, 6.0 * clock.frame_time());
+        // și când vine planeta peste cameră, o împinge afară pe rază
+        for (planet, terrain) in planets.iter().zip(&terrains) {
+            if let Some((height, _)) = terrain.ground(&player.cam_pos)
+                && height < 0.05
+            {
+                let push = glm::normalize(&(player.cam_pos - planet.position)) * (0.05 - height);
+                player.cam_pos += push;
+                player.cam_center += push;
+            }
+        }
+        skybox.draw(player.projection, player.view);
+        for planet in &mut planets {
+            planet.draw(player.projection, player.view, player.cam_pos);
+        }
+        window.swap_buffers();
+    }
+}
+
+#[cfg(feature = "golden")]
+fn golden() {
+    let mut window = Window::new();
+    if let Err(e) = window.create_headless(640, 480) {
+        println!("{}", e);
+        std::process::exit(2);
+    }
+    let failed = seb::golden::run(
+        &mut window,
+        seb::golden::SCENES,
+        "./assets/golden",
+        &seb::golden::Tolerance::default(),
+    );
+    if failed > 0 {
+        println!("{} scene au picat, vezi ./assets/golden/failed", failed);
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     #[cfg(feature = "demo1")]
     demo1();
@@ -784,4 +1174,10 @@ fn main() {
 
     #[cfg(feature = "demo7")]
     demo7();
+
+    #[cfg(feature = "demo8")]
+    demo8();
+
+    #[cfg(feature = "golden")]
+    golden();
 }
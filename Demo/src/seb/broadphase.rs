@@ -0,0 +1,516 @@
+use crate::seb::collision::Collider;
+use nalgebra_glm as glm;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+impl Aabb {
+    pub fn new(min: glm::Vec3, max: glm::Vec3) -> Self {
+        Self { min, max }
+    }
+    pub fn from_center(center: glm::Vec3, half_extents: glm::Vec3) -> Self {
+        Self {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+    pub fn center(&self) -> glm::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+    pub fn half_extents(&self) -> glm::Vec3 {
+        (self.max - self.min) * 0.5
+    }
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.min.z <= other.min.z
+            && self.max.x >= other.max.x
+            && self.max.y >= other.max.y
+            && self.max.z >= other.max.z
+    }
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: glm::min2(&self.min, &other.min),
+            max: glm::max2(&self.max, &other.max),
+        }
+    }
+    pub fn expanded(&self, margin: f32) -> Aabb {
+        let m = glm::vec3(margin, margin, margin);
+        Aabb {
+            min: self.min - m,
+            max: self.max + m,
+        }
+    }
+    // intervalul [intrare, ieșire] al razei prin cutie, dir nu trebuie să fie normalizat
+    pub fn ray_interval(&self, origin: &glm::Vec3, dir: &glm::Vec3) -> Option<(f32, f32)> {
+        let mut t_min = f32::NEG_INFINITY;
+        let mut t_max = f32::INFINITY;
+        for k in 0..3 {
+            if dir[k].abs() < 1e-12 {
+                if origin[k] < self.min[k] || origin[k] > self.max[k] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.min[k] - origin[k]) / dir[k];
+            let t2 = (self.max[k] - origin[k]) / dir[k];
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+        }
+        if t_min > t_max {
+            None
+        } else {
+            Some((t_min, t_max))
+        }
+    }
+    // folosită ca și cost la inserarea în arbore
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    aabb: Aabb,
+    parent: Option<usize>,
+    children: Option<[usize; 2]>,
+    // indexul dat la insert (ex: indexul colliderului), doar pentru frunze
+    item: usize,
+    // -1 pentru nodurile libere
+    height: i32,
+}
+
+/*
+Arbore dinamic de AABB-uri (ca în Box2D).
+Frunzele țin AABB-ul "gras" (mărit cu `margin`), așa că un obiect care se mișcă puțin
+nu trebuie reinserat în fiecare frame.
+*/
+#[derive(Clone, Debug)]
+pub struct DynamicTree {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    pub margin: f32,
+}
+impl DynamicTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            margin: 0.1,
+        }
+    }
+    fn allocate(&mut self, aabb: Aabb, item: usize) -> usize {
+        let node = Node {
+            aabb,
+            parent: None,
+            children: None,
+            item,
+            height: 0,
+        };
+        if let Some(id) = self.free.pop() {
+            self.nodes[id] = node;
+            id
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+    fn release(&mut self, id: usize) {
+        self.nodes[id].height = -1;
+        self.nodes[id].children = None;
+        self.free.push(id);
+    }
+    // întoarce id-ul frunzei (proxy), folosit la update/remove
+    pub fn insert(&mut self, aabb: Aabb, item: usize) -> usize {
+        let leaf = self.allocate(aabb.expanded(self.margin), item);
+        self.insert_leaf(leaf);
+        leaf
+    }
+    pub fn remove(&mut self, proxy: usize) {
+        self.remove_leaf(proxy);
+        self.release(proxy);
+    }
+    // reinserează frunza doar dacă AABB-ul a ieșit din cel gras
+    pub fn update(&mut self, proxy: usize, aabb: Aabb) -> bool {
+        if self.nodes[proxy].aabb.contains(&aabb) {
+            return false;
+        }
+        self.remove_leaf(proxy);
+        self.nodes[proxy].aabb = aabb.expanded(self.margin);
+        self.insert_leaf(proxy);
+        true
+    }
+    pub fn item(&self, proxy: usize) -> usize {
+        self.nodes[proxy].item
+    }
+    pub fn fat_aabb(&self, proxy: usize) -> Aabb {
+        self.nodes[proxy].aabb
+    }
+    pub fn height(&self) -> i32 {
+        self.root.map_or(0, |r| self.nodes[r].height)
+    }
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+    }
+
+    // adaugă în `out` itemii ale căror AABB-uri se intersectează cu `aabb`
+    pub fn query(&self, aabb: &Aabb, out: &mut Vec<usize>) {
+        let Some(root) = self.root else {
+            return;
+        };
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !node.aabb.overlaps(aabb) {
+                continue;
+            }
+            match node.children {
+                Some([l, r]) => {
+                    stack.push(l);
+                    stack.push(r);
+                }
+                None => out.push(node.item),
+            }
+        }
+    }
+
+    // toate perechile de itemi care se pot atinge, fiecare o singură dată, cu (mic, mare)
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        let Some(root) = self.root else {
+            return pairs;
+        };
+        let mut stack = Vec::new();
+        for (leaf, node) in self.nodes.iter().enumerate() {
+            if node.children.is_some() || node.height < 0 {
+                continue;
+            }
+            stack.push(root);
+            while let Some(id) = stack.pop() {
+                let other = &self.nodes[id];
+                if !other.aabb.overlaps(&node.aabb) {
+                    continue;
+                }
+                match other.children {
+                    Some([l, r]) => {
+                        stack.push(l);
+                        stack.push(r);
+                    }
+                    // fiecare pereche e găsită din ambele frunze, o păstrăm doar pe una
+                    None if id > leaf => {
+                        pairs.push((node.item.min(other.item), node.item.max(other.item)))
+                    }
+                    None => {}
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    // (aabb, e_frunză) pentru fiecare nod, util pentru desenat arborele
+    pub fn nodes(&self) -> Vec<(Aabb, bool)> {
+        let mut out = Vec::new();
+        let Some(root) = self.root else {
+            return out;
+        };
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            out.push((node.aabb, node.children.is_none()));
+            if let Some([l, r]) = node.children {
+                stack.push(l);
+                stack.push(r);
+            }
+        }
+        out
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.root = Some(leaf);
+            self.nodes[leaf].parent = None;
+            return;
+        };
+
+        // coborâm spre fratele cel mai ieftin (euristica suprafeței)
+        let leaf_aabb = self.nodes[leaf].aabb;
+        let mut index = root;
+        while let Some([l, r]) = self.nodes[index].children {
+            let area = self.nodes[index].aabb.surface_area();
+            let combined = self.nodes[index].aabb.union(&leaf_aabb).surface_area();
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+
+            let child_cost = |child: usize| {
+                let union = leaf_aabb.union(&self.nodes[child].aabb).surface_area();
+                if self.nodes[child].children.is_none() {
+                    union + inheritance
+                } else {
+                    union - self.nodes[child].aabb.surface_area() + inheritance
+                }
+            };
+            let cost_l = child_cost(l);
+            let cost_r = child_cost(r);
+            if cost < cost_l && cost < cost_r {
+                break;
+            }
+            index = if cost_l < cost_r { l } else { r };
+        }
+        let sibling = index;
+
+        let old_parent = self.nodes[sibling].parent;
+        let aabb = leaf_aabb.union(&self.nodes[sibling].aabb);
+        let new_parent = self.allocate(aabb, usize::MAX);
+        self.nodes[new_parent].parent = old_parent;
+        self.nodes[new_parent].height = self.nodes[sibling].height + 1;
+        self.nodes[new_parent].children = Some([sibling, leaf]);
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+        match old_parent {
+            Some(p) => self.replace_child(p, sibling, new_parent),
+            None => self.root = Some(new_parent),
+        }
+
+        self.refit(self.nodes[leaf].parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+        let parent = self.nodes[leaf].parent.unwrap();
+        let [l, r] = self.nodes[parent].children.unwrap();
+        let sibling = if l == leaf { r } else { l };
+        let grand_parent = self.nodes[parent].parent;
+
+        self.nodes[sibling].parent = grand_parent;
+        match grand_parent {
+            Some(g) => {
+                self.replace_child(g, parent, sibling);
+                self.release(parent);
+                self.refit(Some(g));
+            }
+            None => {
+                self.root = Some(sibling);
+                self.release(parent);
+            }
+        }
+        self.nodes[leaf].parent = None;
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let Some(children) = &mut self.nodes[parent].children {
+            if children[0] == old {
+                children[0] = new;
+            } else {
+                children[1] = new;
+            }
+        }
+    }
+
+    // urcă până la rădăcină, echilibrând și recalculând AABB-urile
+    fn refit(&mut self, mut index: Option<usize>) {
+        while let Some(id) = index {
+            let id = self.balance(id);
+            let [l, r] = self.nodes[id].children.unwrap();
+            self.nodes[id].height = 1 + self.nodes[l].height.max(self.nodes[r].height);
+            self.nodes[id].aabb = self.nodes[l].aabb.union(&self.nodes[r].aabb);
+            index = self.nodes[id].parent;
+        }
+    }
+
+    // rotație AVL dacă un copil e cu mai mult de 1 nivel mai înalt decât celălalt
+    fn balance(&mut self, a: usize) -> usize {
+        let Some([b, c]) = self.nodes[a].children else {
+            return a;
+        };
+        if self.nodes[a].height < 2 {
+            return a;
+        }
+        let diff = self.nodes[c].height - self.nodes[b].height;
+        if diff > 1 {
+            self.rotate(a, c, b, 1)
+        } else if diff < -1 {
+            self.rotate(a, b, c, 0)
+        } else {
+            a
+        }
+    }
+
+    // `up` (copilul prea înalt al lui `a`) îi ia locul lui `a`;
+    // `slot` e poziția lui `up` în copiii lui `a`
+    fn rotate(&mut self, a: usize, up: usize, other: usize, slot: usize) -> usize {
+        let [f, g] = self.nodes[up].children.unwrap();
+
+        self.nodes[up].parent = self.nodes[a].parent;
+        self.nodes[a].parent = Some(up);
+        match self.nodes[up].parent {
+            Some(p) => self.replace_child(p, a, up),
+            None => self.root = Some(up),
+        }
+
+        // nepotul mai înalt rămâne sub `up`, celălalt trece la `a`
+        let (keep, give) = if self.nodes[f].height > self.nodes[g].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+        self.nodes[up].children = Some([a, keep]);
+        let mut children = [other, other];
+        children[slot] = give;
+        self.nodes[a].children = Some(children);
+        self.nodes[give].parent = Some(a);
+
+        self.nodes[a].aabb = self.nodes[other].aabb.union(&self.nodes[give].aabb);
+        self.nodes[a].height = 1 + self.nodes[other].height.max(self.nodes[give].height);
+        self.nodes[up].aabb = self.nodes[a].aabb.union(&self.nodes[keep].aabb);
+        self.nodes[up].height = 1 + self.nodes[a].height.max(self.nodes[keep].height);
+        up
+    }
+}
+
+// perechile (mic, mare) de collidere ale căror AABB-uri se ating, fără să le încercăm pe toate cu toate
+pub fn candidate_pairs(colliders: &[Collider]) -> Vec<(usize, usize)> {
+    let mut tree = DynamicTree::new();
+    tree.margin = 0.0;
+    for (i, collider) in colliders.iter().enumerate() {
+        tree.insert(collider.aabb(), i);
+    }
+    tree.pairs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::primitives::{Rectangle, Sphere};
+
+    // generator simplu și determinist, ca testele să nu depindă de noroc
+    struct Lcg(u64);
+    impl Lcg {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+        fn vec3(&mut self, range: f32) -> glm::Vec3 {
+            glm::vec3(self.next(), self.next(), self.next()) * range
+        }
+    }
+
+    // sfere și cutii rotite amestecate, destul de dese cât să se atingă des
+    fn scene(rng: &mut Lcg, count: usize) -> Vec<Collider> {
+        (0..count)
+            .map(|i| {
+                if i % 2 == 0 {
+                    let mut s = Sphere::new();
+                    s.position = rng.vec3(10.0);
+                    s.scale = 0.2 + rng.next();
+                    Collider::Sphere(s)
+                } else {
+                    let mut r = Rectangle::new();
+                    r.position = rng.vec3(10.0);
+                    r.scale = glm::vec3(0.1, 0.1, 0.1) + rng.vec3(0.8);
+                    r.y_angle = rng.next() * 360.0;
+                    Collider::Rectangle(r)
+                }
+            })
+            .collect()
+    }
+
+    fn brute_force(aabbs: &[(usize, Aabb)]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (k, (i, a)) in aabbs.iter().enumerate() {
+            for (j, b) in &aabbs[k + 1..] {
+                if a.overlaps(b) {
+                    pairs.push((*i.min(j), *i.max(j)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn candidate_pairs_match_brute_force() {
+        let mut rng = Lcg(7);
+        let colliders = scene(&mut rng, 200);
+        let aabbs: Vec<(usize, Aabb)> = colliders.iter().map(|c| c.aabb()).enumerate().collect();
+
+        let pairs = candidate_pairs(&colliders);
+        assert!(!pairs.is_empty());
+        assert_eq!(pairs, brute_force(&aabbs));
+    }
+
+    #[test]
+    fn candidate_pairs_of_few_colliders() {
+        assert!(candidate_pairs(&[]).is_empty());
+        let mut rng = Lcg(3);
+        assert!(candidate_pairs(&scene(&mut rng, 1)).is_empty());
+    }
+
+    #[test]
+    fn pairs_follow_moved_and_removed_proxies() {
+        let mut rng = Lcg(11);
+        let colliders = scene(&mut rng, 120);
+        let mut tree = DynamicTree::new();
+        let mut proxies: Vec<Option<usize>> = colliders
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Some(tree.insert(c.aabb(), i)))
+            .collect();
+
+        // mutăm o parte puțin (rămân în AABB-ul gras) și o parte departe, apoi ștergem câțiva
+        let mut current: Vec<Aabb> = colliders.iter().map(|c| c.aabb()).collect();
+        for i in 0..colliders.len() {
+            let offset = match i % 3 {
+                0 => rng.vec3(0.05),
+                1 => rng.vec3(6.0) - glm::vec3(3.0, 3.0, 3.0),
+                _ => continue,
+            };
+            current[i] = Aabb::new(current[i].min + offset, current[i].max + offset);
+            tree.update(proxies[i].unwrap(), current[i]);
+        }
+        for i in (0..colliders.len()).step_by(7) {
+            tree.remove(proxies[i].take().unwrap());
+        }
+
+        // arborele lucrează cu AABB-urile grase, deci comparăm cu ele
+        let fat: Vec<(usize, Aabb)> = proxies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, proxy)| proxy.map(|p| (i, tree.fat_aabb(p))))
+            .collect();
+        let pairs = tree.pairs();
+        assert_eq!(pairs, brute_force(&fat));
+
+        // și nu pierde nicio pereche care se atinge cu adevărat
+        let exact: Vec<(usize, Aabb)> = fat.iter().map(|&(i, _)| (i, current[i])).collect();
+        for pair in brute_force(&exact) {
+            assert!(pairs.binary_search(&pair).is_ok(), "lipsește {:?}", pair);
+        }
+        assert!(
+            pairs
+                .iter()
+                .all(|&(i, j)| proxies[i].is_some() && proxies[j].is_some())
+        );
+    }
+}
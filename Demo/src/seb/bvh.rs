@@ -0,0 +1,501 @@
+use crate::seb::broadphase::Aabb;
+use crate::seb::collision::closest_points_segments;
+use crate::seb::gltfmodel::{GLTFModel, Mesh};
+use crate::seb::raycast::{closest_point_triangle, ray_triangle, sphere_triangle};
+use nalgebra_glm as glm;
+
+const LEAF_SIZE: usize = 4;
+// de câte ori împingem forma afară din triunghiuri la calculul MTV-ului
+const MTV_ITERATIONS: usize = 4;
+
+// nodurile interioare au count 0 și copiii la first și first + 1
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    aabb: Aabb,
+    first: u32,
+    count: u32,
+}
+
+/*
+BVH peste triunghiurile unui model, în coordonatele lumii (cu translation * rotation * scale
+ale fiecărui mesh). Dacă mesh-urile se mișcă trebuie reconstruit.
+*/
+#[derive(Clone, Debug)]
+pub struct MeshBvh {
+    pub triangles: Vec<[glm::Vec3; 3]>,
+    // pentru fiecare triunghi: indexul mesh-ului în model și al triunghiului în mesh
+    pub sources: Vec<(usize, usize)>,
+    nodes: Vec<Node>,
+    order: Vec<u32>,
+}
+
+impl MeshBvh {
+    pub fn new(triangles: Vec<[glm::Vec3; 3]>, sources: Vec<(usize, usize)>) -> Self {
+        let mut bvh = Self {
+            order: (0..triangles.len() as u32).collect(),
+            triangles,
+            sources,
+            nodes: Vec::new(),
+        };
+        bvh.build();
+        bvh
+    }
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let mut triangles = Vec::new();
+        let mut sources = Vec::new();
+        append_mesh(mesh, 0, &mut triangles, &mut sources);
+        Self::new(triangles, sources)
+    }
+    pub fn from_model(model: &GLTFModel) -> Self {
+        let mut triangles = Vec::new();
+        let mut sources = Vec::new();
+        for (i, mesh) in model.meshes.iter().enumerate() {
+            append_mesh(mesh, i, &mut triangles, &mut sources);
+        }
+        Self::new(triangles, sources)
+    }
+    pub fn aabb(&self) -> Option<Aabb> {
+        self.nodes.first().map(|n| n.aabb)
+    }
+
+    fn build(&mut self) {
+        self.nodes.clear();
+        if self.triangles.is_empty() {
+            return;
+        }
+        self.nodes.push(Node {
+            aabb: self.range_aabb(0, self.order.len()),
+            first: 0,
+            count: self.order.len() as u32,
+        });
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let Node { first, count, .. } = self.nodes[id];
+            let (first, count) = (first as usize, count as usize);
+            if count <= LEAF_SIZE {
+                continue;
+            }
+            // împărțim la mediana centrelor pe axa cea mai lungă
+            let centroid = |t: &[glm::Vec3; 3]| (t[0] + t[1] + t[2]) / 3.0;
+            let (mut lo, mut hi) = (glm::Vec3::repeat(f32::MAX), glm::Vec3::repeat(f32::MIN));
+            for &t in &self.order[first..first + count] {
+                let c = centroid(&self.triangles[t as usize]);
+                lo = glm::min2(&lo, &c);
+                hi = glm::max2(&hi, &c);
+            }
+            let extent = hi - lo;
+            let axis = if extent.x >= extent.y && extent.x >= extent.z {
+                0
+            } else if extent.y >= extent.z {
+                1
+            } else {
+                2
+            };
+            let mid = count / 2;
+            let triangles = &self.triangles;
+            self.order[first..first + count].select_nth_unstable_by(mid, |&a, &b| {
+                centroid(&triangles[a as usize])[axis]
+                    .total_cmp(&centroid(&triangles[b as usize])[axis])
+            });
+
+            let left = self.nodes.len();
+            for (start, len) in [(first, mid), (first + mid, count - mid)] {
+                self.nodes.push(Node {
+                    aabb: self.range_aabb(start, len),
+                    first: start as u32,
+                    count: len as u32,
+                });
+            }
+            self.nodes[id].first = left as u32;
+            self.nodes[id].count = 0;
+            stack.push(left);
+            stack.push(left + 1);
+        }
+    }
+    fn range_aabb(&self, first: usize, count: usize) -> Aabb {
+        let mut min = glm::Vec3::repeat(f32::MAX);
+        let mut max = glm::Vec3::repeat(f32::MIN);
+        for &t in &self.order[first..first + count] {
+            for p in &self.triangles[t as usize] {
+                min = glm::min2(&min, p);
+                max = glm::max2(&max, p);
+            }
+        }
+        Aabb::new(min, max)
+    }
+
+    // adaugă în `out` triunghiurile ale căror AABB-uri ating `aabb`
+    pub fn query(&self, aabb: &Aabb, out: &mut Vec<usize>) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !node.aabb.overlaps(aabb) {
+                continue;
+            }
+            let first = node.first as usize;
+            if node.count == 0 {
+                stack.push(first);
+                stack.push(first + 1);
+                continue;
+            }
+            for &t in &self.order[first..first + node.count as usize] {
+                let [a, b, c] = &self.triangles[t as usize];
+                let tri = Aabb::new(
+                    glm::min2(&glm::min2(a, b), c),
+                    glm::max2(&glm::max2(a, b), c),
+                );
+                if tri.overlaps(aabb) {
+                    out.push(t as usize);
+                }
+            }
+        }
+    }
+
+    /*
+    Cea mai apropiată lovitură a unei sfere de rază `radius` (0 pentru rază simplă) mutată pe
+    origin + dir * t, dir normalizat. Întoarce distanța, normala și indexul triunghiului.
+    */
+    pub fn cast(
+        &self,
+        origin: &glm::Vec3,
+        dir: &glm::Vec3,
+        radius: f32,
+        max_distance: f32,
+    ) -> Option<(f32, glm::Vec3, usize)> {
+        let mut best: Option<(f32, glm::Vec3, usize)> = None;
+        let enter = |node: &Node, limit: f32| {
+            node.aabb
+                .expanded(radius)
+                .ray_interval(origin, dir)
+                .filter(|&(t0, t1)| t1 >= 0.0 && t0 <= limit)
+                .map(|(t0, _)| t0)
+        };
+        enter(self.nodes.first()?, max_distance)?;
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let limit = best.map_or(max_distance, |b| b.0);
+            let node = &self.nodes[id];
+            let first = node.first as usize;
+            if node.count > 0 {
+                for &t in &self.order[first..first + node.count as usize] {
+                    let triangle = &self.triangles[t as usize];
+                    let limit = best.map_or(max_distance, |b| b.0);
+                    if let Some((d, n)) = sphere_triangle(origin, dir, radius, triangle, limit)
+                        && best.is_none_or(|b| d < b.0)
+                    {
+                        best = Some((d, n, t as usize));
+                    }
+                }
+                continue;
+            }
+            // copilul mai apropiat ultimul pe stivă, ca să fie vizitat primul
+            let left = enter(&self.nodes[first], limit);
+            let right = enter(&self.nodes[first + 1], limit);
+            match (left, right) {
+                (Some(l), Some(r)) if l <= r => stack.extend([first + 1, first]),
+                (Some(_), Some(_)) => stack.extend([first, first + 1]),
+                (Some(_), None) => stack.push(first),
+                (None, Some(_)) => stack.push(first + 1),
+                (None, None) => {}
+            }
+        }
+        best
+    }
+
+    /*
+    Vectorul care scoate sfera din mesh (de adunat la sferă), sau None dacă nu atinge nimic.
+    Împingem de câteva ori după cel mai adânc triunghi, așa că merge și în colțuri.
+    */
+    pub fn sphere_mtv(&self, center: &glm::Vec3, radius: f32) -> Option<glm::Vec3> {
+        self.resolve(radius, center, center, |a, _, triangle| {
+            let p = closest_point_triangle(a, triangle);
+            (*a, p)
+        })
+    }
+
+    // la fel ca sphere_mtv, pentru capsula cu axa de la `a` la `b`
+    pub fn capsule_mtv(&self, a: &glm::Vec3, b: &glm::Vec3, radius: f32) -> Option<glm::Vec3> {
+        self.resolve(radius, a, b, closest_segment_triangle)
+    }
+
+    fn resolve(
+        &self,
+        radius: f32,
+        a: &glm::Vec3,
+        b: &glm::Vec3,
+        closest: impl Fn(&glm::Vec3, &glm::Vec3, &[glm::Vec3; 3]) -> (glm::Vec3, glm::Vec3),
+    ) -> Option<glm::Vec3> {
+        let mut offset = glm::Vec3::zeros();
+        let mut candidates = Vec::new();
+        for _ in 0..MTV_ITERATIONS {
+            let (a, b) = (a + offset, b + offset);
+            candidates.clear();
+            self.query(
+                &Aabb::new(glm::min2(&a, &b), glm::max2(&a, &b)).expanded(radius),
+                &mut candidates,
+            );
+            let mut deepest: Option<(f32, glm::Vec3)> = None;
+            for &t in &candidates {
+                let triangle = &self.triangles[t];
+                let (on_shape, on_triangle) = closest(&a, &b, triangle);
+                let delta = on_shape - on_triangle;
+                let distance = glm::length(&delta);
+                let depth = radius - distance;
+                if depth <= 1e-6 || deepest.is_some_and(|(d, _)| d >= depth) {
+                    continue;
+                }
+                let normal = if distance > 1e-6 {
+                    delta / distance
+                } else {
+                    // axa trece prin triunghi: ieșim pe partea centrului formei
+                    let n = glm::normalize(&glm::cross(
+                        &(triangle[1] - triangle[0]),
+                        &(triangle[2] - triangle[0]),
+                    ));
+                    if glm::dot(&n, &((a + b) * 0.5 - triangle[0])) < 0.0 {
+                        -n
+                    } else {
+                        n
+                    }
+                };
+                deepest = Some((depth, normal));
+            }
+            let Some((depth, normal)) = deepest else {
+                break;
+            };
+            offset += normal * depth;
+        }
+        (glm::length2(&offset) > 0.0).then_some(offset)
+    }
+}
+
+fn append_mesh(
+    mesh: &Mesh,
+    mesh_index: usize,
+    triangles: &mut Vec<[glm::Vec3; 3]>,
+    sources: &mut Vec<(usize, usize)>,
+) {
+    let transform = mesh.translation * mesh.rotation * mesh.scale;
+    let points: Vec<glm::Vec3> = mesh
+        .position_coords
+        .chunks_exact(3)
+        .map(|p| {
+            let w = transform * glm::vec4(p[0], p[1], p[2], 1.0);
+            glm::vec3(w.x, w.y, w.z)
+        })
+        .collect();
+    for (index, face) in mesh.indices.chunks_exact(3).enumerate() {
+        let (Some(a), Some(b), Some(c)) = (
+            points.get(face[0] as usize),
+            points.get(face[1] as usize),
+            points.get(face[2] as usize),
+        ) else {
+            continue;
+        };
+        triangles.push([*a, *b, *c]);
+        sources.push((mesh_index, index));
+    }
+}
+
+// cele mai apropiate puncte dintre segmentul ab și triunghi: (pe segment, pe triunghi)
+pub fn closest_segment_triangle(
+    a: &glm::Vec3,
+    b: &glm::Vec3,
+    triangle: &[glm::Vec3; 3],
+) -> (glm::Vec3, glm::Vec3) {
+    let ab = b - a;
+    let length = glm::length(&ab);
+    if length > 1e-9
+        && let Some((t, _)) = ray_triangle(a, &(ab / length), triangle, length)
+    {
+        let p = a + ab * (t / length);
+        return (p, p);
+    }
+    let mut best = (*a, closest_point_triangle(a, triangle));
+    let mut consider = |(p, q): (glm::Vec3, glm::Vec3)| {
+        if glm::distance2(&p, &q) < glm::distance2(&best.0, &best.1) {
+            best = (p, q);
+        }
+    };
+    consider((*b, closest_point_triangle(b, triangle)));
+    for k in 0..3 {
+        consider(closest_points_segments(
+            *a,
+            *b,
+            triangle[k],
+            triangle[(k + 1) % 3],
+        ));
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // generator simplu și determinist, ca testele să nu depindă de noroc
+    struct Lcg(u64);
+    impl Lcg {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+        fn vec3(&mut self, range: f32) -> glm::Vec3 {
+            (glm::vec3(self.next(), self.next(), self.next()) * 2.0).add_scalar(-1.0) * range
+        }
+    }
+
+    // triunghiuri mici și mari amestecate într-un cub de latură 20
+    fn soup(rng: &mut Lcg, count: usize) -> MeshBvh {
+        let triangles: Vec<[glm::Vec3; 3]> = (0..count)
+            .map(|_| {
+                let center = rng.vec3(10.0);
+                let size = 0.2 + 2.0 * rng.next();
+                [
+                    center + rng.vec3(size),
+                    center + rng.vec3(size),
+                    center + rng.vec3(size),
+                ]
+            })
+            .collect();
+        let sources = (0..count).map(|i| (0, i)).collect();
+        MeshBvh::new(triangles, sources)
+    }
+
+    fn triangle_aabb([a, b, c]: &[glm::Vec3; 3]) -> Aabb {
+        Aabb::new(
+            glm::min2(&glm::min2(a, b), c),
+            glm::max2(&glm::max2(a, b), c),
+        )
+    }
+
+    fn brute_cast(
+        bvh: &MeshBvh,
+        origin: &glm::Vec3,
+        dir: &glm::Vec3,
+        radius: f32,
+        max_distance: f32,
+    ) -> Option<f32> {
+        bvh.triangles
+            .iter()
+            .filter_map(|t| sphere_triangle(origin, dir, radius, t, max_distance))
+            .map(|(d, _)| d)
+            .min_by(f32::total_cmp)
+    }
+
+    #[test]
+    fn query_matches_brute_force() {
+        let mut rng = Lcg(5);
+        let bvh = soup(&mut rng, 400);
+        for _ in 0..100 {
+            let center = rng.vec3(11.0);
+            let aabb = Aabb::from_center(center, rng.vec3(3.0).abs());
+            let mut found = Vec::new();
+            bvh.query(&aabb, &mut found);
+            found.sort_unstable();
+            let expected: Vec<usize> = (0..bvh.triangles.len())
+                .filter(|&t| triangle_aabb(&bvh.triangles[t]).overlaps(&aabb))
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn cast_matches_brute_force() {
+        let mut rng = Lcg(9);
+        let bvh = soup(&mut rng, 400);
+        let mut hits = 0;
+        for k in 0..300 {
+            let origin = rng.vec3(15.0);
+            // jumătate din raze țintesc mesh-ul, restul pleacă oriunde
+            let dir = if k % 2 == 0 {
+                glm::normalize(&(rng.vec3(5.0) - origin))
+            } else {
+                glm::normalize(&rng.vec3(1.0))
+            };
+            let radius = if k % 3 == 0 { 0.0 } else { 0.3 * rng.next() };
+            let max_distance = 5.0 + 30.0 * rng.next();
+
+            let expected = brute_cast(&bvh, &origin, &dir, radius, max_distance);
+            let found = bvh.cast(&origin, &dir, radius, max_distance);
+            match (found, expected) {
+                (Some((d, _, t)), Some(e)) => {
+                    assert!((d - e).abs() < 1e-5, "raza {}: {} în loc de {}", k, d, e);
+                    // triunghiul întors chiar e lovit la distanța aceea
+                    let own =
+                        sphere_triangle(&origin, &dir, radius, &bvh.triangles[t], max_distance);
+                    assert!(own.is_some_and(|(o, _)| (o - d).abs() < 1e-5));
+                    hits += 1;
+                }
+                (None, None) => {}
+                _ => panic!("raza {}: {:?} în loc de {:?}", k, found, expected),
+            }
+        }
+        // destule lovituri cât testul să conteze
+        assert!(hits > 50, "doar {} lovituri", hits);
+    }
+
+    #[test]
+    fn empty_mesh() {
+        let bvh = MeshBvh::new(Vec::new(), Vec::new());
+        assert!(bvh.aabb().is_none());
+        let mut found = Vec::new();
+        bvh.query(
+            &Aabb::from_center(glm::Vec3::zeros(), glm::vec3(1e3, 1e3, 1e3)),
+            &mut found,
+        );
+        assert!(found.is_empty());
+        let down = glm::vec3(0.0, -1.0, 0.0);
+        assert!(
+            bvh.cast(&glm::vec3(0.0, 1.0, 0.0), &down, 0.5, 100.0)
+                .is_none()
+        );
+        assert!(bvh.sphere_mtv(&glm::Vec3::zeros(), 1.0).is_none());
+    }
+
+    #[test]
+    fn single_triangle() {
+        let triangle = [
+            glm::vec3(-1.0, 0.0, -1.0),
+            glm::vec3(1.0, 0.0, -1.0),
+            glm::vec3(0.0, 0.0, 1.0),
+        ];
+        let bvh = MeshBvh::new(vec![triangle], vec![(0, 0)]);
+        assert!(bvh.aabb().is_some());
+
+        let mut found = Vec::new();
+        bvh.query(
+            &Aabb::from_center(glm::Vec3::zeros(), glm::vec3(0.1, 0.1, 0.1)),
+            &mut found,
+        );
+        assert_eq!(found, vec![0]);
+        found.clear();
+        bvh.query(
+            &Aabb::from_center(glm::vec3(0.0, 2.0, 0.0), glm::vec3(0.1, 0.1, 0.1)),
+            &mut found,
+        );
+        assert!(found.is_empty());
+
+        let down = glm::vec3(0.0, -1.0, 0.0);
+        let (distance, normal, index) = bvh
+            .cast(&glm::vec3(0.0, 3.0, 0.0), &down, 0.0, 10.0)
+            .unwrap();
+        assert!((distance - 3.0).abs() < 1e-5);
+        assert!(normal.y > 0.99);
+        assert_eq!(index, 0);
+        assert!(
+            bvh.cast(&glm::vec3(3.0, 3.0, 0.0), &down, 0.0, 10.0)
+                .is_none()
+        );
+
+        // sfera scufundată pe jumătate iese în sus
+        let mtv = bvh.sphere_mtv(&glm::vec3(0.0, 0.25, 0.0), 0.5).unwrap();
+        assert!(glm::distance(&mtv, &glm::vec3(0.0, 0.25, 0.0)) < 1e-4);
+    }
+}
@@ -0,0 +1,445 @@
+use crate::seb::collision::{Collider, collide_manifold};
+use crate::seb::gravity;
+use crate::seb::primitives::Capsule;
+use crate::seb::raycast::{RayHit, Target, raycast, spherecast};
+use nalgebra as na;
+use nalgebra_glm as glm;
+
+// de câte ori alunecăm pe suprafețe într-o singură mișcare
+const MAX_SLIDES: usize = 4;
+
+/*
+Controller cinematic cu o capsulă pe direcția sus: nu e corp în PhysicsWorld, se mișcă
+doar prin cast-uri în scenă și alunecă pe ce atinge. Gravitația dă și direcția sus,
+așa că merge și pe planete dacă o actualizăm în fiecare frame.
+*/
+#[derive(Clone, Debug)]
+pub struct CharacterController {
+    pub position: glm::Vec3, // centrul capsulei
+    pub radius: f32,
+    pub half_height: f32,
+    pub velocity: glm::Vec3,
+    pub gravity: glm::Vec3,
+    pub jump_speed: f32,
+    // cât de înaltă poate fi o treaptă urcată fără să sărim
+    pub step_height: f32,
+    // cea mai abruptă pantă pe care putem sta, în grade
+    pub max_slope: f32,
+    // cât coborâm după sol la pante și trepte în jos, ca să nu zburăm de pe ele
+    pub snap_distance: f32,
+    // distanța ținută față de suprafețe, ca următorul cast să nu pornească din ele
+    pub skin: f32,
+    grounded: bool,
+    ground_normal: glm::Vec3,
+}
+
+impl CharacterController {
+    pub fn new() -> Self {
+        Self {
+            position: glm::Vec3::zeros(),
+            radius: 0.3,
+            half_height: 0.6,
+            velocity: glm::Vec3::zeros(),
+            gravity: glm::vec3(0.0, -9.81, 0.0),
+            jump_speed: 5.0,
+            step_height: 0.3,
+            max_slope: 45.0,
+            snap_distance: 0.3,
+            skin: 0.01,
+            grounded: false,
+            ground_normal: glm::vec3(0.0, 1.0, 0.0),
+        }
+    }
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+    pub fn ground_normal(&self) -> Option<glm::Vec3> {
+        self.grounded.then_some(self.ground_normal)
+    }
+    pub fn up(&self) -> glm::Vec3 {
+        gravity::up_from(&self.gravity)
+    }
+    pub fn collider(&self) -> Collider {
+        let mut capsule = Capsule::new();
+        capsule.position = self.position;
+        capsule.radius = self.radius;
+        capsule.half_height = self.half_height;
+        let mut collider = Collider::Capsule(capsule);
+        let rotation = na::Rotation3::rotation_between(&glm::vec3(0.0, 1.0, 0.0), &self.up())
+            .unwrap_or_else(|| na::Rotation3::from_axis_angle(&glm::Vec3::x_axis(), glm::pi()));
+        collider.set_rotation(rotation.matrix());
+        collider
+    }
+    pub fn jump(&mut self) {
+        if !self.grounded {
+            return;
+        }
+        let up = self.up();
+        self.velocity += up * (self.jump_speed - glm::dot(&self.velocity, &up));
+        self.grounded = false;
+    }
+
+    // `wish` e viteza dorită în plan (din input), componenta pe sus e ignorată
+    pub fn update(&mut self, scene: &[Target], wish: glm::Vec3, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        let up = self.up();
+        self.depenetrate(scene);
+
+        let mut vertical = glm::dot(&self.velocity, &up);
+        if self.grounded && vertical <= 0.0 {
+            vertical = 0.0;
+        } else {
+            vertical += glm::dot(&self.gravity, &up) * dt;
+        }
+        let horizontal = wish - up * glm::dot(&wish, &up);
+
+        // pe sol mergem de-a lungul pantei, cu aceeași viteză
+        let mut walk = horizontal;
+        if self.grounded {
+            let along =
+                horizontal - self.ground_normal * glm::dot(&horizontal, &self.ground_normal);
+            if glm::length(&along) > 1e-6 {
+                walk = along * (glm::length(&horizontal) / glm::length(&along));
+            }
+        }
+        let start = self.position;
+        let motion = walk * dt;
+        let (mut position, hits) = self.slide(scene, start, motion, true);
+        if self.grounded && self.step_height > 0.0 && hits.iter().any(|n| !self.walkable(n)) {
+            let flat = |p: &glm::Vec3| {
+                let d = p - start;
+                glm::length(&(d - up * glm::dot(&d, &up)))
+            };
+            if let Some(stepped) = self.step_up(scene, start, motion)
+                && flat(&stepped) > flat(&position) + 1e-4
+            {
+                position = stepped;
+            }
+        }
+
+        let was_grounded = self.grounded;
+        self.grounded = false;
+        if vertical.abs() > 1e-6 {
+            let (moved, hits) = self.slide(scene, position, up * (vertical * dt), false);
+            position = moved;
+            for n in hits {
+                if vertical <= 0.0 && self.walkable(&n) {
+                    self.grounded = true;
+                    self.ground_normal = n;
+                    vertical = 0.0;
+                } else if vertical > 0.0 && glm::dot(&n, &up) < -0.5 {
+                    // tavan
+                    vertical = 0.0;
+                }
+            }
+        }
+        // căutăm solul sub noi, mai departe dacă stăteam deja pe ceva
+        if !self.grounded && vertical <= 0.0 {
+            let probe = if was_grounded {
+                self.snap_distance
+            } else {
+                self.skin * 2.0
+            };
+            if let Some(hit) = self.cast(scene, &position, &(-up * probe))
+                && let Some(n) = self.support(scene, &hit, &position)
+            {
+                position -= up * (hit.distance - self.skin).max(0.0);
+                self.grounded = true;
+                self.ground_normal = n;
+                vertical = 0.0;
+            }
+        }
+
+        self.position = position;
+        self.velocity = horizontal + up * vertical;
+    }
+
+    fn walkable(&self, normal: &glm::Vec3) -> bool {
+        glm::dot(normal, &self.up()) >= self.max_slope.to_radians().cos()
+    }
+    /*
+    Normala solului pe care ne sprijinim la lovitura `hit` a capsulei cu centrul în `center`.
+    Pe muchia unei trepte normala din cast e rotunjită, așa că tragem o rază în jos chiar
+    lângă punctul atins, dinspre axa capsulei spre treaptă, și ne uităm la suprafața de acolo.
+    */
+    fn support(&self, scene: &[Target], hit: &RayHit, center: &glm::Vec3) -> Option<glm::Vec3> {
+        if self.walkable(&hit.normal) {
+            return Some(hit.normal);
+        }
+        let up = self.up();
+        let away = hit.point - center;
+        let outward = away - up * glm::dot(&away, &up);
+        if glm::length(&outward) < 1e-6 {
+            return None;
+        }
+        // pornim de sus, ca pe o pantă abruptă să nu pornim din interiorul ei
+        let origin = hit.point + glm::normalize(&outward) * self.skin + up * self.radius;
+        raycast(scene, origin, -up, self.radius + self.skin)
+            .filter(|ground| ground.distance > 0.0 && self.walkable(&ground.normal))
+            .map(|ground| ground.normal)
+    }
+
+    /*
+    Cea mai apropiată lovitură a capsulei mutate cu `motion`. Capsula e luată ca un șir de sfere
+    pe axă, la cel mult o rază una de alta; ce scapă printre ele rezolvă depenetrate.
+    */
+    fn cast(&self, scene: &[Target], position: &glm::Vec3, motion: &glm::Vec3) -> Option<RayHit> {
+        let distance = glm::length(motion);
+        if distance < 1e-7 {
+            return None;
+        }
+        let dir = motion / distance;
+        let up = self.up();
+        let samples = (2.0 * self.half_height / self.radius).ceil().max(1.0) as usize;
+        (0..=samples)
+            .filter_map(|k| {
+                let offset = up * self.half_height * (2.0 * k as f32 / samples as f32 - 1.0);
+                spherecast(scene, position + offset, dir, self.radius, distance)
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /*
+    Collide and slide: înaintăm până la prima suprafață, tăiem din restul mișcării
+    componenta spre ea și continuăm. Cu `walls`, pantele prea abrupte sunt tratate ca pereți
+    verticali, ca să nu le urcăm mergând. Întoarce poziția și normalele atinse.
+    */
+    fn slide(
+        &self,
+        scene: &[Target],
+        mut position: glm::Vec3,
+        motion: glm::Vec3,
+        walls: bool,
+    ) -> (glm::Vec3, Vec<glm::Vec3>) {
+        let up = self.up();
+        let mut remaining = motion;
+        let mut hits: Vec<glm::Vec3> = Vec::new();
+        for _ in 0..MAX_SLIDES {
+            let Some(RayHit {
+                distance, normal, ..
+            }) = self.cast(scene, &position, &remaining)
+            else {
+                position += remaining;
+                break;
+            };
+            let length = glm::length(&remaining);
+            let dir = remaining / length;
+            let advance = (distance - self.skin).max(0.0);
+            position += dir * advance;
+            hits.push(normal);
+
+            let mut plane = normal;
+            if walls && !self.walkable(&normal) {
+                let flat = normal - up * glm::dot(&normal, &up);
+                if glm::length(&flat) > 1e-6 {
+                    plane = glm::normalize(&flat);
+                }
+            }
+            remaining = dir * (length - advance);
+            remaining -= plane * glm::dot(&remaining, &plane).min(0.0);
+            // între două suprafețe mergem doar pe muchia dintre ele
+            if let [.., previous, _] = hits.as_slice()
+                && glm::dot(&remaining, previous) < 0.0
+            {
+                let crease = glm::cross(previous, &plane);
+                if glm::length2(&crease) < 1e-12 {
+                    break;
+                }
+                let crease = glm::normalize(&crease);
+                remaining = crease * glm::dot(&remaining, &crease);
+            }
+            if glm::length2(&remaining) < 1e-12 {
+                break;
+            }
+        }
+        (position, hits)
+    }
+
+    // urcăm cu step_height, mergem, apoi coborâm pe treaptă; None dacă nu aterizăm pe ceva bun
+    fn step_up(&self, scene: &[Target], start: glm::Vec3, motion: glm::Vec3) -> Option<glm::Vec3> {
+        let up = self.up();
+        let (raised, _) = self.slide(scene, start, up * self.step_height, false);
+        let (moved, _) = self.slide(scene, raised, motion, true);
+        let drop = glm::dot(&(raised - start), &up) + self.skin * 2.0;
+        let hit = self.cast(scene, &moved, &(-up * drop))?;
+        // rotunjimea capsulei ar urca și muchii mai înalte, așa că măsurăm de la tălpi
+        let feet = start - up * (self.half_height + self.radius);
+        if glm::dot(&(hit.point - feet), &up) > self.step_height {
+            return None;
+        }
+        self.support(scene, &hit, &moved)?;
+        Some(moved - up * (hit.distance - self.skin).max(0.0))
+    }
+
+    // scoatem capsula din collidere și mesh-uri (restul țintelor se bazează doar pe cast-uri)
+    fn depenetrate(&mut self, scene: &[Target]) {
+        for target in scene {
+            let mtv = match target {
+                Target::Collider(collider) => {
+                    collide_manifold(&self.collider(), collider).map(|m| m.mtv())
+                }
+                Target::Mesh(bvh) => {
+                    let up = self.up();
+                    let (a, b) = (
+                        self.position - up * self.half_height,
+                        self.position + up * self.half_height,
+                    );
+                    bvh.capsule_mtv(&a, &b, self.radius)
+                }
+                _ => None,
+            };
+            if let Some(mtv) = mtv {
+                self.position += mtv;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::bvh::MeshBvh;
+    use crate::seb::primitives::Rectangle;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn block(position: glm::Vec3, half: glm::Vec3, z_angle: f32) -> Collider {
+        let mut r = Rectangle::new();
+        r.position = position;
+        r.scale = half;
+        r.z_angle = z_angle;
+        Collider::Rectangle(r)
+    }
+
+    // podea cu fața de sus la y = 0
+    fn floor() -> Collider {
+        block(glm::vec3(0.0, -0.5, 0.0), glm::vec3(20.0, 0.5, 20.0), 0.0)
+    }
+
+    // capsula stă pe podea, cu tălpile la y = 0
+    fn standing(x: f32) -> CharacterController {
+        let mut character = CharacterController::new();
+        character.position = glm::vec3(x, character.half_height + character.radius + 0.01, 0.0);
+        character
+    }
+
+    fn walk(
+        character: &mut CharacterController,
+        colliders: &[Collider],
+        wish: glm::Vec3,
+        seconds: f32,
+    ) {
+        let scene: Vec<Target> = colliders.iter().map(Target::from).collect();
+        for _ in 0..(seconds / DT) as usize {
+            character.update(&scene, wish, DT);
+        }
+    }
+
+    fn feet(character: &CharacterController) -> f32 {
+        character.position.y - character.half_height - character.radius
+    }
+
+    #[test]
+    fn slides_along_wall() {
+        // perete cu fața la x = 1, mergem în diagonală spre el
+        let wall = block(glm::vec3(1.5, 1.0, 0.0), glm::vec3(0.5, 1.0, 10.0), 0.0);
+        let mut character = standing(0.0);
+        walk(
+            &mut character,
+            &[floor(), wall],
+            glm::vec3(2.0, 0.0, 2.0),
+            2.0,
+        );
+
+        assert!(character.position.x <= 1.0 - character.radius + 1e-3);
+        assert!(character.position.x > 0.6);
+        // componenta de-a lungul peretelui nu se pierde
+        assert!(character.position.z > 3.5, "z = {}", character.position.z);
+        assert!(character.is_grounded());
+    }
+
+    #[test]
+    fn climbs_low_step() {
+        let step = block(glm::vec3(2.0, 0.1, 0.0), glm::vec3(1.0, 0.1, 2.0), 0.0);
+        let mut character = standing(0.0);
+        walk(
+            &mut character,
+            &[floor(), step],
+            glm::vec3(1.5, 0.0, 0.0),
+            1.5,
+        );
+
+        assert!(character.position.x > 1.5);
+        assert!(
+            (feet(&character) - 0.2).abs() < 0.03,
+            "tălpile la {}",
+            feet(&character)
+        );
+        assert!(character.is_grounded());
+    }
+
+    #[test]
+    fn stops_at_high_step() {
+        // mai înaltă decât step_height
+        let step = block(glm::vec3(2.0, 0.25, 0.0), glm::vec3(1.0, 0.25, 2.0), 0.0);
+        let mut character = standing(0.0);
+        walk(
+            &mut character,
+            &[floor(), step],
+            glm::vec3(1.5, 0.0, 0.0),
+            1.5,
+        );
+
+        assert!(character.position.x <= 1.0 - character.radius + 1e-3);
+        assert!(feet(&character).abs() < 0.03);
+        assert!(character.is_grounded());
+    }
+
+    #[test]
+    fn does_not_walk_up_steep_slope() {
+        // plăci rotite, cu fața de sus spre -x, care trec prin podea la x = 2
+        let ramp =
+            |degrees: f32| block(glm::vec3(2.0, 0.0, 0.0), glm::vec3(4.0, 0.05, 2.0), degrees);
+
+        let mut steep = standing(0.0);
+        walk(
+            &mut steep,
+            &[floor(), ramp(60.0)],
+            glm::vec3(1.5, 0.0, 0.0),
+            3.0,
+        );
+        assert!(feet(&steep) < 0.2, "a urcat până la {}", feet(&steep));
+        assert!(steep.position.x < 2.0);
+
+        // panta de 30 de grade e sub max_slope, deci urcăm pe ea
+        let mut gentle = standing(0.0);
+        walk(
+            &mut gentle,
+            &[floor(), ramp(30.0)],
+            glm::vec3(1.5, 0.0, 0.0),
+            3.0,
+        );
+        assert!(feet(&gentle) > 1.0, "a rămas la {}", feet(&gentle));
+        assert!(gentle.is_grounded());
+    }
+
+    #[test]
+    fn pushed_out_of_mesh() {
+        let a = glm::vec3(-5.0, 0.0, -5.0);
+        let b = glm::vec3(5.0, 0.0, -5.0);
+        let c = glm::vec3(5.0, 0.0, 5.0);
+        let d = glm::vec3(-5.0, 0.0, 5.0);
+        let mesh = MeshBvh::new(vec![[a, c, b], [a, d, c]], vec![(0, 0), (0, 1)]);
+
+        // capsula e pe jumătate în podea
+        let mut character = CharacterController::new();
+        character.position = glm::vec3(0.0, 0.5, 0.0);
+        character.update(&[Target::Mesh(&mesh)], glm::Vec3::zeros(), DT);
+
+        assert!(feet(&character) > -1e-3, "tălpile la {}", feet(&character));
+        assert!(feet(&character) < 0.05);
+        assert!(character.position.x.abs() < 1e-4 && character.position.z.abs() < 1e-4);
+    }
+}
@@ -1,13 +1,142 @@
 
 
 
-use crate::seb::primitives::{Rectangle, Sphere};
+use crate::seb::broadphase::Aabb;
+use crate::seb::gjk::{epa, gjk_distance};
+use crate::seb::primitives::{Capsule, ConvexHull, Cylinder, Plane, Rectangle, Sphere};
 use nalgebra_glm as glm;
 
 
+#[derive(Clone, Debug)]
 pub enum Collider {
     Rectangle(Rectangle),
     Sphere(Sphere),
+    Capsule(Capsule),
+    Cylinder(Cylinder),
+    Plane(Plane),
+    ConvexHull(ConvexHull),
+}
+impl Collider {
+    pub fn position(&self) -> glm::Vec3 {
+        match self {
+            Collider::Rectangle(r) => r.position,
+            Collider::Sphere(s) => s.position,
+            Collider::Capsule(c) => c.position,
+            Collider::Cylinder(c) => c.position,
+            Collider::Plane(p) => p.normal * p.distance,
+            Collider::ConvexHull(h) => h.position,
+        }
+    }
+    pub fn set_position(&mut self, position: glm::Vec3) {
+        match self {
+            Collider::Rectangle(r) => r.position = position,
+            Collider::Sphere(s) => s.position = position,
+            Collider::Capsule(c) => c.position = position,
+            Collider::Cylinder(c) => c.position = position,
+            Collider::Plane(p) => p.distance = glm::dot(&p.normal, &position),
+            Collider::ConvexHull(h) => h.position = position,
+        }
+    }
+    // sfera și planul nu au unghiuri, pentru ele e identitatea
+    pub fn rotation(&self) -> glm::Mat3 {
+        match self {
+            Collider::Rectangle(r) => euler_to_matrix(r.x_angle, r.y_angle, r.z_angle),
+            Collider::Capsule(c) => euler_to_matrix(c.x_angle, c.y_angle, c.z_angle),
+            Collider::Cylinder(c) => euler_to_matrix(c.x_angle, c.y_angle, c.z_angle),
+            Collider::ConvexHull(h) => euler_to_matrix(h.x_angle, h.y_angle, h.z_angle),
+            Collider::Sphere(_) | Collider::Plane(_) => glm::Mat3::identity(),
+        }
+    }
+    pub fn set_rotation(&mut self, m: &glm::Mat3) {
+        let angles = matrix_to_euler(m);
+        let (x, y, z) = match self {
+            Collider::Rectangle(r) => (&mut r.x_angle, &mut r.y_angle, &mut r.z_angle),
+            Collider::Capsule(c) => (&mut c.x_angle, &mut c.y_angle, &mut c.z_angle),
+            Collider::Cylinder(c) => (&mut c.x_angle, &mut c.y_angle, &mut c.z_angle),
+            Collider::ConvexHull(h) => (&mut h.x_angle, &mut h.y_angle, &mut h.z_angle),
+            Collider::Sphere(_) | Collider::Plane(_) => return,
+        };
+        *x = angles.x;
+        *y = angles.y;
+        *z = angles.z;
+    }
+    pub fn aabb(&self) -> Aabb {
+        match self {
+            Collider::Rectangle(r) => {
+                // vârfurile sunt între -1 și 1, deci jumătatea pe fiecare axă e suma |M_ij|
+                let m = r.get_model();
+                let half = glm::vec3(
+                    m[(0, 0)].abs() + m[(0, 1)].abs() + m[(0, 2)].abs(),
+                    m[(1, 0)].abs() + m[(1, 1)].abs() + m[(1, 2)].abs(),
+                    m[(2, 0)].abs() + m[(2, 1)].abs() + m[(2, 2)].abs(),
+                );
+                Aabb::from_center(r.position, half)
+            }
+            Collider::Sphere(s) => {
+                Aabb::from_center(s.position, glm::vec3(s.scale, s.scale, s.scale))
+            }
+            Collider::Capsule(c) => {
+                let (a, b) = c.segment();
+                let r = glm::vec3(c.radius, c.radius, c.radius);
+                Aabb::new(glm::min2(&a, &b) - r, glm::max2(&a, &b) + r)
+            }
+            Collider::Cylinder(c) => {
+                let axis = self.rotation() * glm::vec3(0.0, 1.0, 0.0);
+                // discul de rază r perpendicular pe axă are pe axa k jumătatea r * sqrt(1 - axis_k²)
+                let half = glm::vec3(
+                    axis.x.abs() * c.half_height
+                        + c.radius * (1.0 - axis.x * axis.x).max(0.0).sqrt(),
+                    axis.y.abs() * c.half_height
+                        + c.radius * (1.0 - axis.y * axis.y).max(0.0).sqrt(),
+                    axis.z.abs() * c.half_height
+                        + c.radius * (1.0 - axis.z * axis.z).max(0.0).sqrt(),
+                );
+                Aabb::from_center(c.position, half)
+            }
+            // planul e infinit, îi dăm o cutie foarte mare ca să intre în broadphase
+            Collider::Plane(_) => {
+                let e = glm::vec3(PLANE_EXTENT, PLANE_EXTENT, PLANE_EXTENT);
+                Aabb::new(-e, e)
+            }
+            Collider::ConvexHull(h) => {
+                let points = h.world_points();
+                let Some(first) = points.first() else {
+                    return Aabb::new(h.position, h.position);
+                };
+                let (min, max) = points.iter().fold((*first, *first), |(min, max), p| {
+                    (glm::min2(&min, p), glm::max2(&max, p))
+                });
+                Aabb::new(min, max)
+            }
+        }
+    }
+    // forma convexă din lume plus raza rotunjită peste ea (sfera = punct + rază, capsula = segment + rază)
+    pub fn convex_shape(&self) -> (ConvexShape, f32) {
+        match self {
+            Collider::Rectangle(r) => (ConvexShape::Box(Obb::new(r)), 0.0),
+            Collider::Sphere(s) => (ConvexShape::Point(s.position), s.scale),
+            Collider::Capsule(c) => {
+                let (a, b) = c.segment();
+                (ConvexShape::Segment(a, b), c.radius)
+            }
+            Collider::Cylinder(c) => (
+                ConvexShape::Cylinder {
+                    center: c.position,
+                    axis: self.rotation() * glm::vec3(0.0, 1.0, 0.0),
+                    half_height: c.half_height,
+                    radius: c.radius,
+                },
+                0.0,
+            ),
+            Collider::ConvexHull(h) => (ConvexShape::Hull(h.world_points()), 0.0),
+            Collider::Plane(p) => (ConvexShape::Point(p.normal * p.distance), 0.0),
+        }
+    }
+    // punctul colliderului cel mai departe pe direcția dir (planul nu are, e infinit)
+    pub fn support(&self, dir: &glm::Vec3) -> glm::Vec3 {
+        let (shape, margin) = self.convex_shape();
+        shape.support(dir) + safe_normalize(dir) * margin
+    }
 }
 impl From<Rectangle> for Collider {
     fn from(value: Rectangle) -> Self {
@@ -19,6 +148,143 @@ impl From<Sphere> for Collider {
         Collider::Sphere(value)
     }
 }
+impl From<Capsule> for Collider {
+    fn from(value: Capsule) -> Self {
+        Collider::Capsule(value)
+    }
+}
+impl From<Cylinder> for Collider {
+    fn from(value: Cylinder) -> Self {
+        Collider::Cylinder(value)
+    }
+}
+impl From<Plane> for Collider {
+    fn from(value: Plane) -> Self {
+        Collider::Plane(value)
+    }
+}
+impl From<ConvexHull> for Collider {
+    fn from(value: ConvexHull) -> Self {
+        Collider::ConvexHull(value)
+    }
+}
+
+/*
+Straturile din care face parte un collider (`layer`) și cele cu care se poate ciocni (`mask`),
+ca biți. Doi collideri se ating doar dacă fiecare are stratul celuilalt în mască, de exemplu
+gloanțele fără stratul jucătorului în mască trec prin cel care trage.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionFilter {
+    pub layer: u32,
+    pub mask: u32,
+}
+impl CollisionFilter {
+    pub fn new(layer: u32, mask: u32) -> Self {
+        Self { layer, mask }
+    }
+    pub fn can_collide(&self, other: &CollisionFilter) -> bool {
+        self.layer & other.mask != 0 && other.layer & self.mask != 0
+    }
+}
+// stratul 1, se ciocnește cu tot
+impl Default for CollisionFilter {
+    fn default() -> Self {
+        Self::new(1, u32::MAX)
+    }
+}
+
+const PLANE_EXTENT: f32 = 1e6;
+
+// aceeași ordine ca Rectangle::get_model: Z * Y * X
+pub fn euler_to_matrix(x_angle: f32, y_angle: f32, z_angle: f32) -> glm::Mat3 {
+    let m = glm::rotation(z_angle.to_radians(), &glm::vec3(0.0, 0.0, 1.0))
+        * glm::rotation(y_angle.to_radians(), &glm::vec3(0.0, 1.0, 0.0))
+        * glm::rotation(x_angle.to_radians(), &glm::vec3(1.0, 0.0, 0.0));
+    glm::mat4_to_mat3(&m)
+}
+
+// inversul lui euler_to_matrix, în grade
+pub fn matrix_to_euler(m: &glm::Mat3) -> glm::Vec3 {
+    let sy = (-m[(2, 0)]).clamp(-1.0, 1.0);
+    let y = sy.asin();
+    let (x, z) = if sy.abs() < 0.9999 {
+        (m[(2, 1)].atan2(m[(2, 2)]), m[(1, 0)].atan2(m[(0, 0)]))
+    } else {
+        // gimbal lock: punem tot unghiul pe X
+        ((-m[(1, 2)]).atan2(m[(1, 1)]), 0.0)
+    };
+    glm::vec3(x.to_degrees(), y.to_degrees(), z.to_degrees())
+}
+
+fn safe_normalize(v: &glm::Vec3) -> glm::Vec3 {
+    let len = glm::length(v);
+    if len > 1e-12 {
+        v / len
+    } else {
+        glm::vec3(1.0, 0.0, 0.0)
+    }
+}
+
+// forme convexe deja transformate în lume, folosite de GJK
+#[derive(Clone, Debug)]
+pub enum ConvexShape {
+    Point(glm::Vec3),
+    Segment(glm::Vec3, glm::Vec3),
+    Box(Obb),
+    Cylinder {
+        center: glm::Vec3,
+        axis: glm::Vec3,
+        half_height: f32,
+        radius: f32,
+    },
+    Hull(Vec<glm::Vec3>),
+}
+impl ConvexShape {
+    pub fn support(&self, dir: &glm::Vec3) -> glm::Vec3 {
+        match self {
+            ConvexShape::Point(p) => *p,
+            ConvexShape::Segment(a, b) => {
+                if glm::dot(dir, &(b - a)) > 0.0 {
+                    *b
+                } else {
+                    *a
+                }
+            }
+            ConvexShape::Box(obb) => {
+                let mut p = obb.center;
+                for k in 0..3 {
+                    let sign = if glm::dot(dir, &obb.axes[k]) >= 0.0 {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    p += obb.axes[k] * obb.half[k] * sign;
+                }
+                p
+            }
+            ConvexShape::Cylinder {
+                center,
+                axis,
+                half_height,
+                radius,
+            } => {
+                let along = glm::dot(dir, axis);
+                let radial = dir - axis * along;
+                let mut p = center + axis * half_height.copysign(along);
+                let len = glm::length(&radial);
+                if len > 1e-9 {
+                    p += radial * (radius / len);
+                }
+                p
+            }
+            ConvexShape::Hull(points) => *points
+                .iter()
+                .max_by(|a, b| glm::dot(a, dir).total_cmp(&glm::dot(b, dir)))
+                .unwrap_or(&glm::Vec3::zeros()),
+        }
+    }
+}
 
 
 /*
@@ -26,7 +292,12 @@ ATENTIE pt rezolvarea coliziunii:
 ADUNI LA PRIMUL SCAZI LA AL DOILEA LA MTV
 */
 pub fn collide(this: Collider, other: Collider) -> Option<glm::Vec3> {
-    match (&this, &other) {
+    collide_pair(&this, &other)
+}
+
+// La fel ca `collide`, dar nu consumă colliderele (folosit de physics)
+pub fn collide_pair(this: &Collider, other: &Collider) -> Option<glm::Vec3> {
+    match (this, other) {
         (Collider::Sphere(a), Collider::Sphere(b)) => {
             let delta = b.position - a.position;
             let dist = glm::length(&delta);
@@ -207,5 +478,993 @@ pub fn collide(this: Collider, other: Collider) -> Option<glm::Vec3> {
                 None
             }
         }
+        // formele noi trec prin manifold (GJK/EPA sau plan)
+        _ => collide_manifold(this, other).map(|m| m.mtv()),
+    }
+}
+
+/*
+Manifold de contact: pe lângă MTV avem punctele de contact și ce parte din fiecare corp atinge.
+Normala e de la primul collider spre al doilea (opusul direcției MTV-ului).
+Punctele sunt la mijloc între cele două suprafețe.
+*/
+
+// Indexarea pentru Rectangle:
+// vârf  = bitul k e 1 dacă vârful e pe partea + a axei k (0..8)
+// față  = axa * 2 + (0 pentru +, 1 pentru -) (0..6)
+// muchie = axa muchiei * 4 + bitul axei următoare + 2 * bitul celei de după (0..12)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Feature {
+    Vertex(u32),
+    Edge(u32),
+    Face(u32),
+    // suprafețe curbe (sfera) care nu au vârfuri/muchii/fețe
+    Surface,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ContactPoint {
+    pub position: glm::Vec3,
+    pub depth: f32,
+    pub feature_a: Feature,
+    pub feature_b: Feature,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContactManifold {
+    pub normal: glm::Vec3,
+    pub points: Vec<ContactPoint>,
+}
+impl ContactManifold {
+    pub fn depth(&self) -> f32 {
+        self.points.iter().fold(0.0, |d, p| d.max(p.depth))
+    }
+    // același MTV ca `collide`
+    pub fn mtv(&self) -> glm::Vec3 {
+        -self.normal * self.depth()
+    }
+    // manifoldul văzut din partea celuilalt collider
+    pub fn flipped(mut self) -> Self {
+        self.normal = -self.normal;
+        for p in &mut self.points {
+            std::mem::swap(&mut p.feature_a, &mut p.feature_b);
+        }
+        self
+    }
+}
+
+pub fn collide_manifold(this: &Collider, other: &Collider) -> Option<ContactManifold> {
+    match (this, other) {
+        (Collider::Sphere(a), Collider::Sphere(b)) => sphere_sphere_manifold(a, b),
+        (Collider::Rectangle(a), Collider::Rectangle(b)) => box_box_manifold(a, b),
+        (Collider::Sphere(s), Collider::Rectangle(r)) => sphere_box_manifold(s, r),
+        (Collider::Rectangle(r), Collider::Sphere(s)) => {
+            sphere_box_manifold(s, r).map(ContactManifold::flipped)
+        }
+        (Collider::Plane(_), Collider::Plane(_)) => None,
+        (Collider::Plane(p), _) => plane_manifold(p, other),
+        (_, Collider::Plane(p)) => plane_manifold(p, this).map(ContactManifold::flipped),
+        _ => convex_manifold(this, other),
+    }
+}
+
+fn sphere_sphere_manifold(a: &Sphere, b: &Sphere) -> Option<ContactManifold> {
+    let delta = b.position - a.position;
+    let dist = glm::length(&delta);
+    let depth = a.scale + b.scale - dist;
+    if depth <= 0.0 {
+        return None;
+    }
+    let normal = if dist > 1e-5 {
+        delta / dist
+    } else {
+        glm::vec3(1.0, 0.0, 0.0)
+    };
+    Some(ContactManifold {
+        normal,
+        points: vec![ContactPoint {
+            position: a.position + normal * (a.scale - depth * 0.5),
+            depth,
+            feature_a: Feature::Surface,
+            feature_b: Feature::Surface,
+        }],
+    })
+}
+
+// Rectangle ca OBB: centru, axe normalizate și jumătățile pe fiecare axă
+#[derive(Clone, Debug)]
+pub struct Obb {
+    pub center: glm::Vec3,
+    pub axes: [glm::Vec3; 3],
+    pub half: glm::Vec3,
+}
+impl Obb {
+    pub fn new(r: &Rectangle) -> Self {
+        let model = r.get_model();
+        Self {
+            center: r.position,
+            axes: [
+                glm::normalize(&glm::column(&model, 0).xyz()),
+                glm::normalize(&glm::column(&model, 1).xyz()),
+                glm::normalize(&glm::column(&model, 2).xyz()),
+            ],
+            half: r.scale.abs(),
+        }
+    }
+    pub fn corner(&self, index: u32) -> glm::Vec3 {
+        let mut p = self.center;
+        for k in 0..3 {
+            let sign = if index >> k & 1 == 1 { 1.0 } else { -1.0 };
+            p += self.axes[k] * self.half[k] * sign;
+        }
+        p
+    }
+    fn face_normal(&self, face: u32) -> glm::Vec3 {
+        let n = self.axes[face as usize / 2];
+        if face & 1 == 0 { n } else { -n }
+    }
+    // cele 4 vârfuri ale feței, în ordine în jurul ei
+    fn face_corners(face: u32) -> [u32; 4] {
+        let axis = face / 2;
+        let bit = if face & 1 == 0 { 1 << axis } else { 0 };
+        let u = 1 << ((axis + 1) % 3);
+        let v = 1 << ((axis + 2) % 3);
+        [bit, bit | u, bit | u | v, bit | v]
+    }
+    fn projected_radius(&self, axis: &glm::Vec3) -> f32 {
+        (0..3)
+            .map(|k| glm::dot(&self.axes[k], axis).abs() * self.half[k])
+            .sum()
+    }
+}
+
+fn edge_index(c1: u32, c2: u32) -> u32 {
+    let axis = (c1 ^ c2).trailing_zeros();
+    let u = c1 >> ((axis + 1) % 3) & 1;
+    let v = c1 >> ((axis + 2) % 3) & 1;
+    axis * 4 + u + 2 * v
+}
+
+// axele SAT: 0..3 fețele lui a, 3..6 fețele lui b, apoi muchie x muchie
+fn box_box_manifold(ra: &Rectangle, rb: &Rectangle) -> Option<ContactManifold> {
+    let a = Obb::new(ra);
+    let b = Obb::new(rb);
+    let center_diff = b.center - a.center;
+
+    // (overlap, index, axa orientată de la a spre b) pentru fețele lui a, ale lui b și muchii
+    let mut best = [(f32::MAX, 0usize, glm::Vec3::zeros()); 3];
+    for index in 0..15 {
+        let axis = if index < 3 {
+            a.axes[index]
+        } else if index < 6 {
+            b.axes[index - 3]
+        } else {
+            let cross = glm::cross(&a.axes[(index - 6) / 3], &b.axes[(index - 6) % 3]);
+            // muchii paralele, axa e deja acoperită de fețe
+            if glm::length(&cross) < 1e-5 {
+                continue;
+            }
+            glm::normalize(&cross)
+        };
+        let distance = glm::dot(&center_diff, &axis);
+        let overlap = a.projected_radius(&axis) + b.projected_radius(&axis) - distance.abs();
+        if overlap < 0.0 {
+            return None;
+        }
+        let group = (index / 3).min(2);
+        if overlap < best[group].0 {
+            let axis = if distance < 0.0 { -axis } else { axis };
+            best[group] = (overlap, index, axis);
+        }
+    }
+
+    // preferăm fețele lui a, apoi ale lui b, apoi muchiile, ca să nu sară referința între frame-uri
+    let clearly_better = |x: f32, y: f32| x < y * 0.95 - 1e-4;
+    let face = if clearly_better(best[1].0, best[0].0) {
+        best[1]
+    } else {
+        best[0]
+    };
+    if clearly_better(best[2].0, face.0) {
+        return Some(edge_edge_manifold(&a, &b, best[2]));
+    }
+    let (_, index, normal) = face;
+    if index < 3 {
+        box_face_manifold(&a, &b, index, normal)
+    } else {
+        box_face_manifold(&b, &a, index - 3, -normal).map(ContactManifold::flipped)
+    }
+}
+
+fn edge_edge_manifold(
+    a: &Obb,
+    b: &Obb,
+    (depth, index, normal): (f32, usize, glm::Vec3),
+) -> ContactManifold {
+    let axis_a = (index - 6) / 3;
+    let axis_b = (index - 6) % 3;
+    // muchia de pe fiecare cutie cea mai avansată spre cealaltă
+    let support = |obb: &Obb, axis: usize, dir: glm::Vec3| -> (u32, u32) {
+        let mut corner = 0;
+        for k in 0..3 {
+            if k != axis && glm::dot(&obb.axes[k], &dir) > 0.0 {
+                corner |= 1 << k;
+            }
+        }
+        (corner, corner | 1 << axis)
+    };
+    let (a1, a2) = support(a, axis_a, normal);
+    let (b1, b2) = support(b, axis_b, -normal);
+    let (pa, pb) = closest_points_segments(a.corner(a1), a.corner(a2), b.corner(b1), b.corner(b2));
+
+    ContactManifold {
+        normal,
+        points: vec![ContactPoint {
+            position: (pa + pb) * 0.5,
+            depth,
+            feature_a: Feature::Edge(edge_index(a1, a2)),
+            feature_b: Feature::Edge(edge_index(b1, b2)),
+        }],
+    }
+}
+
+pub fn closest_points_segments(
+    p1: glm::Vec3,
+    q1: glm::Vec3,
+    p2: glm::Vec3,
+    q2: glm::Vec3,
+) -> (glm::Vec3, glm::Vec3) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = glm::dot(&d1, &d1);
+    let e = glm::dot(&d2, &d2);
+    let f = glm::dot(&d2, &r);
+    if a <= 1e-9 && e <= 1e-9 {
+        return (p1, p2);
+    }
+    let (s, t) = if a <= 1e-9 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = glm::dot(&d1, &r);
+        if e <= 1e-9 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = glm::dot(&d1, &d2);
+            let denom = a * e - b * b;
+            let mut s = if denom > 1e-9 {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+#[derive(Clone, Copy)]
+enum Segment {
+    // muchie a feței incidente (index de muchie pe cutia incidentă)
+    Incident(u32),
+    // bucată tăiată de planul lateral k al feței de referință
+    Side(usize),
+}
+
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: glm::Vec3,
+    feature_a: Feature,
+    feature_b: Feature,
+    // segmentul de la acest vârf la următorul
+    next: Segment,
+}
+
+// `reference` are fața `axis` spre `incident`, `normal` iese din fața de referință
+fn box_face_manifold(
+    reference: &Obb,
+    incident: &Obb,
+    axis: usize,
+    normal: glm::Vec3,
+) -> Option<ContactManifold> {
+    let ref_face = axis as u32 * 2
+        + if glm::dot(&normal, &reference.axes[axis]) > 0.0 {
+            0
+        } else {
+            1
+        };
+
+    // fața incidentă e cea mai opusă normalei
+    let mut inc_face = 0;
+    let mut min_dot = f32::MAX;
+    for face in 0..6 {
+        let d = glm::dot(&incident.face_normal(face), &normal);
+        if d < min_dot {
+            min_dot = d;
+            inc_face = face;
+        }
+    }
+    let inc_corners = Obb::face_corners(inc_face);
+    let mut polygon: Vec<ClipVertex> = (0..4)
+        .map(|k| ClipVertex {
+            position: incident.corner(inc_corners[k]),
+            feature_a: Feature::Face(ref_face),
+            feature_b: Feature::Vertex(inc_corners[k]),
+            next: Segment::Incident(edge_index(inc_corners[k], inc_corners[(k + 1) % 4])),
+        })
+        .collect();
+
+    // planele laterale: fețele vecine ale cutiei de referință
+    let ref_bit = if ref_face & 1 == 0 { 1 << axis } else { 0 };
+    let side_axes = [(axis + 1) % 3, (axis + 2) % 3];
+    let mut sides = Vec::with_capacity(4);
+    for &side_axis in &side_axes {
+        for positive in [true, false] {
+            let n = if positive {
+                reference.axes[side_axis]
+            } else {
+                -reference.axes[side_axis]
+            };
+            let offset = glm::dot(&n, &reference.center) + reference.half[side_axis];
+            let other_axis = if side_axis == side_axes[0] {
+                side_axes[1]
+            } else {
+                side_axes[0]
+            };
+            let side_bit = if positive { 1 << side_axis } else { 0 };
+            let c1 = ref_bit | side_bit;
+            let edge = edge_index(c1, c1 | 1 << other_axis);
+            sides.push((n, offset, side_axis, side_bit, edge));
+        }
+    }
+
+    for (k, &(n, offset, _, _, edge)) in sides.iter().enumerate() {
+        if polygon.is_empty() {
+            break;
+        }
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let cur = polygon[i];
+            let nxt = polygon[(i + 1) % polygon.len()];
+            let dc = glm::dot(&n, &cur.position) - offset;
+            let dn = glm::dot(&n, &nxt.position) - offset;
+            if dc <= 0.0 {
+                clipped.push(cur);
+            }
+            if (dc <= 0.0) != (dn <= 0.0) {
+                let t = dc / (dc - dn);
+                let (feature_a, feature_b) = match cur.next {
+                    Segment::Incident(e) => (Feature::Edge(edge), Feature::Edge(e)),
+                    // colțul feței de referință a intrat în fața incidentă
+                    Segment::Side(j) => {
+                        let (_, _, _, bit_j, _) = sides[j];
+                        let (_, _, _, bit_k, _) = sides[k];
+                        (
+                            Feature::Vertex(ref_bit | bit_j | bit_k),
+                            Feature::Face(inc_face),
+                        )
+                    }
+                };
+                // la ieșire continuăm pe planul lateral, la intrare pe segmentul original
+                let next = if dc <= 0.0 {
+                    Segment::Side(k)
+                } else {
+                    cur.next
+                };
+                clipped.push(ClipVertex {
+                    position: cur.position + (nxt.position - cur.position) * t,
+                    feature_a,
+                    feature_b,
+                    next,
+                });
+            }
+        }
+        polygon = clipped;
+    }
+
+    let face_offset = glm::dot(&normal, &reference.center) + reference.half[axis];
+    let mut points: Vec<ContactPoint> = polygon
+        .iter()
+        .filter_map(|v| {
+            let depth = face_offset - glm::dot(&normal, &v.position);
+            (depth >= 0.0).then(|| ContactPoint {
+                position: v.position + normal * depth * 0.5,
+                depth,
+                feature_a: v.feature_a,
+                feature_b: v.feature_b,
+            })
+        })
+        .collect();
+
+    if points.is_empty() {
+        // numeric se poate întâmpla la atingeri pe muchie, luăm vârful cel mai adânc
+        let corner = (0..4)
+            .map(|k| inc_corners[k])
+            .max_by(|&x, &y| {
+                let dx = -glm::dot(&normal, &incident.corner(x));
+                let dy = -glm::dot(&normal, &incident.corner(y));
+                dx.total_cmp(&dy)
+            })
+            .unwrap();
+        let p = incident.corner(corner);
+        let depth = (face_offset - glm::dot(&normal, &p)).max(0.0);
+        points.push(ContactPoint {
+            position: p + normal * depth * 0.5,
+            depth,
+            feature_a: Feature::Face(ref_face),
+            feature_b: Feature::Vertex(corner),
+        });
+    }
+    if points.len() > 4 {
+        points = reduce_points(points, &normal);
+    }
+    Some(ContactManifold { normal, points })
+}
+
+// păstrează 4 puncte: cel mai adânc și apoi cele care acoperă cea mai mare arie
+fn reduce_points(points: Vec<ContactPoint>, normal: &glm::Vec3) -> Vec<ContactPoint> {
+    let pos = |i: usize| points[i].position;
+    let area = |p: glm::Vec3, a: glm::Vec3, b: glm::Vec3| {
+        glm::dot(&glm::cross(&(a - p), &(b - p)), normal)
+    };
+    let best = |score: &dyn Fn(usize) -> f32| -> usize {
+        (0..points.len())
+            .max_by(|&x, &y| score(x).total_cmp(&score(y)))
+            .unwrap()
+    };
+
+    let i0 = best(&|i| points[i].depth);
+    let i1 = best(&|i| glm::distance2(&pos(i), &pos(i0)));
+    let i2 = best(&|i| area(pos(i0), pos(i1), pos(i)).abs());
+    let (i1, i2) = if area(pos(i0), pos(i1), pos(i2)) < 0.0 {
+        (i2, i1)
+    } else {
+        (i1, i2)
+    };
+    // al patrulea e cel mai departe în afara triunghiului
+    let outside = |i: usize| {
+        let p = pos(i);
+        (-area(pos(i0), pos(i1), p))
+            .max(-area(pos(i1), pos(i2), p))
+            .max(-area(pos(i2), pos(i0), p))
+    };
+    let i3 = best(&outside);
+
+    let mut reduced = vec![points[i0], points[i1], points[i2]];
+    if outside(i3) > 0.0 {
+        reduced.push(points[i3]);
+    }
+    reduced
+}
+
+fn sphere_box_manifold(sphere: &Sphere, rect: &Rectangle) -> Option<ContactManifold> {
+    let obb = Obb::new(rect);
+    let difference = sphere.position - obb.center;
+
+    let mut closest = obb.center;
+    let mut clamped = [None; 3];
+    for (k, side) in clamped.iter_mut().enumerate() {
+        let distance = glm::dot(&difference, &obb.axes[k]);
+        if distance > obb.half[k] {
+            *side = Some(true);
+        } else if distance < -obb.half[k] {
+            *side = Some(false);
+        }
+        closest += obb.axes[k] * distance.clamp(-obb.half[k], obb.half[k]);
+    }
+
+    let count = clamped.iter().filter(|c| c.is_some()).count();
+    let (normal, distance, feature) = if count == 0 {
+        // centrul e în cutie: ieșim pe fața cea mai apropiată
+        let (axis, gap) = (0..3)
+            .map(|k| (k, obb.half[k] - glm::dot(&difference, &obb.axes[k]).abs()))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap();
+        let positive = glm::dot(&difference, &obb.axes[axis]) >= 0.0;
+        let out = if positive {
+            obb.axes[axis]
+        } else {
+            -obb.axes[axis]
+        };
+        let face = axis as u32 * 2 + if positive { 0 } else { 1 };
+        (-out, -gap, Feature::Face(face))
+    } else {
+        let to_box = closest - sphere.position;
+        let dist = glm::length(&to_box);
+        if dist >= sphere.scale {
+            return None;
+        }
+        let bits = (0..3).fold(0, |acc, k| {
+            if clamped[k] == Some(true) {
+                acc | 1 << k
+            } else {
+                acc
+            }
+        });
+        let feature = match count {
+            1 => {
+                let k = clamped.iter().position(|c| c.is_some()).unwrap() as u32;
+                Feature::Face(k * 2 + if bits != 0 { 0 } else { 1 })
+            }
+            2 => {
+                let k = clamped.iter().position(|c| c.is_none()).unwrap() as u32;
+                Feature::Edge(edge_index(bits & !(1 << k), bits | 1 << k))
+            }
+            _ => Feature::Vertex(bits),
+        };
+        (to_box / dist, dist, feature)
+    };
+
+    let depth = sphere.scale - distance;
+    if depth <= 0.0 {
+        return None;
+    }
+    Some(ContactManifold {
+        normal,
+        points: vec![ContactPoint {
+            position: sphere.position + normal * (distance + sphere.scale) * 0.5,
+            depth,
+            feature_a: Feature::Surface,
+            feature_b: feature,
+        }],
+    })
+}
+
+// punctele lui `other` care pot atinge planul: (trăsătura, punctul, raza rotunjită peste el)
+fn plane_candidates(plane: &Plane, other: &Collider) -> Vec<(Feature, glm::Vec3, f32)> {
+    match other {
+        Collider::Sphere(s) => vec![(Feature::Surface, s.position, s.scale)],
+        Collider::Rectangle(r) => {
+            let obb = Obb::new(r);
+            (0..8)
+                .map(|i| (Feature::Vertex(i), obb.corner(i), 0.0))
+                .collect()
+        }
+        Collider::Capsule(c) => {
+            let (a, b) = c.segment();
+            vec![
+                (Feature::Vertex(0), a, c.radius),
+                (Feature::Vertex(1), b, c.radius),
+            ]
+        }
+        Collider::Cylinder(c) => {
+            // câte 4 puncte pe fiecare margine, începând cu cel mai adânc
+            let axis = other.rotation() * glm::vec3(0.0, 1.0, 0.0);
+            let down = -plane.normal - axis * glm::dot(&-plane.normal, &axis);
+            let u = if glm::length(&down) > 1e-4 {
+                glm::normalize(&down)
+            } else {
+                glm::normalize(&glm::cross(&axis, &any_axis_not_parallel(&axis)))
+            };
+            let v = glm::cross(&axis, &u);
+            let mut points = Vec::with_capacity(8);
+            for (cap, sign) in [(0, -1.0), (1, 1.0)] {
+                let center = c.position + axis * c.half_height * sign;
+                for (k, dir) in [u, v, -u, -v].iter().enumerate() {
+                    points.push((
+                        Feature::Vertex(cap * 4 + k as u32),
+                        center + dir * c.radius,
+                        0.0,
+                    ));
+                }
+            }
+            points
+        }
+        Collider::ConvexHull(h) => h
+            .world_points()
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (Feature::Vertex(i as u32), p, 0.0))
+            .collect(),
+        Collider::Plane(_) => Vec::new(),
+    }
+}
+
+fn any_axis_not_parallel(v: &glm::Vec3) -> glm::Vec3 {
+    if v.x.abs() < 0.9 {
+        glm::vec3(1.0, 0.0, 0.0)
+    } else {
+        glm::vec3(0.0, 1.0, 0.0)
+    }
+}
+
+// normala e a planului, spre `other`
+fn plane_manifold(plane: &Plane, other: &Collider) -> Option<ContactManifold> {
+    let normal = plane.normal;
+    let mut points: Vec<ContactPoint> = plane_candidates(plane, other)
+        .into_iter()
+        .filter_map(|(feature, p, margin)| {
+            let depth = margin - plane.signed_distance(&p);
+            let deepest = p - normal * margin;
+            (depth > 0.0).then(|| ContactPoint {
+                position: deepest + normal * depth * 0.5,
+                depth,
+                feature_a: Feature::Face(0),
+                feature_b: feature,
+            })
+        })
+        .collect();
+    if points.is_empty() {
+        return None;
+    }
+    if points.len() > 4 {
+        points = reduce_points(points, &normal);
+    }
+    Some(ContactManifold { normal, points })
+}
+
+/*
+Caz general cu GJK. Formele rotunjite (sferă, capsulă) sunt tratate ca miez + rază:
+cât timp miezurile nu se ating folosim distanța dintre ele, care e exactă,
+și doar dacă se intersectează și miezurile trecem la EPA pe formele întregi.
+*/
+fn convex_manifold(a: &Collider, b: &Collider) -> Option<ContactManifold> {
+    let (shape_a, margin_a) = a.convex_shape();
+    let (shape_b, margin_b) = b.convex_shape();
+    let margin = margin_a + margin_b;
+    let core_a = |d: &glm::Vec3| shape_a.support(d);
+    let core_b = |d: &glm::Vec3| shape_b.support(d);
+
+    let core = gjk_distance(&core_a, &core_b, a.position() - b.position());
+    if !core.intersects() {
+        if core.distance >= margin {
+            return None;
+        }
+        let normal = (core.point_b - core.point_a) / core.distance;
+        let mut manifold = ContactManifold {
+            normal,
+            points: vec![ContactPoint {
+                position: (core.point_a + normal * margin_a + core.point_b - normal * margin_b)
+                    * 0.5,
+                depth: margin - core.distance,
+                feature_a: Feature::Surface,
+                feature_b: Feature::Surface,
+            }],
+        };
+        // o capsulă culcată pe ceva are nevoie de ambele capete, altfel se leagănă
+        if let ConvexShape::Segment(p0, p1) = shape_a {
+            let ends = segment_end_contacts(&[p0, p1], margin_a, &shape_b, margin_b);
+            if ends.len() == 2 {
+                manifold.points = ends;
+            }
+        } else if let ConvexShape::Segment(p0, p1) = shape_b {
+            let ends = segment_end_contacts(&[p0, p1], margin_b, &shape_a, margin_a);
+            if ends.len() == 2 {
+                manifold.points = ends
+                    .into_iter()
+                    .map(|p| ContactPoint {
+                        feature_a: p.feature_b,
+                        feature_b: p.feature_a,
+                        ..p
+                    })
+                    .collect();
+            }
+        }
+        return Some(manifold);
+    }
+
+    let full_a = |d: &glm::Vec3| shape_a.support(d) + safe_normalize(d) * margin_a;
+    let full_b = |d: &glm::Vec3| shape_b.support(d) + safe_normalize(d) * margin_b;
+    let full = gjk_distance(&full_a, &full_b, a.position() - b.position());
+    let penetration = epa(&full_a, &full_b, &full.simplex)?;
+    if penetration.depth <= 0.0 {
+        return None;
+    }
+    Some(ContactManifold {
+        normal: penetration.normal,
+        points: vec![ContactPoint {
+            position: (penetration.point_a + penetration.point_b) * 0.5,
+            depth: penetration.depth,
+            feature_a: Feature::Surface,
+            feature_b: Feature::Surface,
+        }],
+    })
+}
+
+// contactele capetelor unui segment rotunjit cu o altă formă (trăsătura segmentului e pe feature_a)
+fn segment_end_contacts(
+    ends: &[glm::Vec3; 2],
+    margin: f32,
+    other: &ConvexShape,
+    other_margin: f32,
+) -> Vec<ContactPoint> {
+    let mut points = Vec::new();
+    for (i, end) in ends.iter().enumerate() {
+        let result = gjk_distance(
+            &|_: &glm::Vec3| *end,
+            &|d: &glm::Vec3| other.support(d),
+            end - other.support(&glm::Vec3::zeros()),
+        );
+        if result.intersects() || result.distance >= margin + other_margin {
+            continue;
+        }
+        let n = (result.point_b - result.point_a) / result.distance;
+        points.push(ContactPoint {
+            position: (result.point_a + n * margin + result.point_b - n * other_margin) * 0.5,
+            depth: margin + other_margin - result.distance,
+            feature_a: Feature::Vertex(i as u32),
+            feature_b: Feature::Surface,
+        });
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere(position: glm::Vec3, radius: f32) -> Collider {
+        let mut s = Sphere::new();
+        s.position = position;
+        s.scale = radius;
+        Collider::Sphere(s)
+    }
+
+    fn cuboid(position: glm::Vec3, half: glm::Vec3, angles: glm::Vec3) -> Collider {
+        let mut r = Rectangle::new();
+        r.position = position;
+        r.scale = half;
+        r.x_angle = angles.x;
+        r.y_angle = angles.y;
+        r.z_angle = angles.z;
+        Collider::Rectangle(r)
+    }
+
+    fn near(a: &glm::Vec3, b: &glm::Vec3) -> bool {
+        glm::distance(a, b) < 1e-3
+    }
+
+    fn vertices(m: &ContactManifold) -> Vec<(Feature, Feature)> {
+        let mut features: Vec<(Feature, Feature)> = m
+            .points
+            .iter()
+            .map(|p| (p.feature_a, p.feature_b))
+            .collect();
+        features.sort_by_key(|f| format!("{:?}", f));
+        features
+    }
+
+    fn capsule(position: glm::Vec3, z_angle: f32) -> Collider {
+        let mut c = Capsule::new();
+        c.position = position;
+        c.z_angle = z_angle;
+        Collider::Capsule(c)
+    }
+
+    fn hull_cube(position: glm::Vec3) -> Collider {
+        let mut corners = Vec::new();
+        for i in 0..8 {
+            corners.push(Obb::new(&Rectangle::new()).corner(i));
+        }
+        let mut h = ConvexHull::from_points(&corners);
+        h.position = position;
+        Collider::ConvexHull(h)
+    }
+
+    #[test]
+    fn sphere_sphere() {
+        let a = sphere(glm::vec3(0.0, 0.0, 0.0), 1.0);
+        let b = sphere(glm::vec3(1.5, 0.0, 0.0), 1.0);
+        let m = collide_manifold(&a, &b).unwrap();
+        assert!(near(&m.normal, &glm::vec3(1.0, 0.0, 0.0)));
+        assert_eq!(m.points.len(), 1);
+        assert!((m.depth() - 0.5).abs() < 1e-5);
+        // la mijloc între suprafețe
+        assert!(near(&m.points[0].position, &glm::vec3(0.75, 0.0, 0.0)));
+        // același MTV ca vechiul collide
+        assert!(near(&m.mtv(), &collide_pair(&a, &b).unwrap()));
+
+        let far = sphere(glm::vec3(2.5, 0.0, 0.0), 1.0);
+        assert!(collide_manifold(&a, &far).is_none());
+    }
+
+    #[test]
+    fn box_resting_on_box() {
+        let zero = glm::Vec3::zeros();
+        let ground = cuboid(zero, glm::vec3(2.0, 0.5, 2.0), zero);
+        let top = cuboid(glm::vec3(0.3, 0.9, -0.2), glm::vec3(0.5, 0.5, 0.5), zero);
+        let m = collide_manifold(&ground, &top).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert!((p.depth - 0.1).abs() < 1e-4);
+            assert!((p.position.y - 0.45).abs() < 1e-4);
+        }
+        // fața de sus a podelei cu cele 4 vârfuri de jos ale cutiei
+        let expected: Vec<(Feature, Feature)> = [0, 1, 4, 5]
+            .iter()
+            .map(|&v| (Feature::Face(2), Feature::Vertex(v)))
+            .collect();
+        assert_eq!(vertices(&m), expected);
+
+        // trăsăturile rămân aceleași când cutia se mișcă puțin, de ele depinde warm start-ul
+        let moved = cuboid(glm::vec3(0.32, 0.89, -0.21), glm::vec3(0.5, 0.5, 0.5), zero);
+        let m2 = collide_manifold(&ground, &moved).unwrap();
+        assert_eq!(vertices(&m2), expected);
+
+        let reversed = collide_manifold(&top, &ground).unwrap();
+        assert!(near(&reversed.normal, &glm::vec3(0.0, -1.0, 0.0)));
+        assert!((reversed.depth() - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn clipped_face_is_reduced_to_four_points() {
+        // fața de jos, rotită cu 45°, iese din fața de referință: tăiată dă un octogon
+        let zero = glm::Vec3::zeros();
+        let ground = cuboid(zero, glm::vec3(0.5, 0.5, 0.5), zero);
+        let top = cuboid(
+            glm::vec3(0.0, 1.0, 0.0),
+            glm::vec3(0.6, 0.6, 0.6),
+            glm::vec3(0.0, 45.0, 0.0),
+        );
+        let m = collide_manifold(&ground, &top).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert!((p.depth - 0.1).abs() < 1e-4);
+            assert!(p.position.x.abs() <= 0.5 + 1e-4 && p.position.z.abs() <= 0.5 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn crossed_edges() {
+        let a = cuboid(
+            glm::Vec3::zeros(),
+            glm::vec3(0.5, 0.5, 0.5),
+            glm::vec3(45.0, 0.0, 0.0),
+        );
+        let b = cuboid(
+            glm::vec3(0.0, 1.35, 0.0),
+            glm::vec3(0.5, 0.5, 0.5),
+            glm::vec3(0.0, 0.0, 45.0),
+        );
+        let m = collide_manifold(&a, &b).unwrap();
+        let edge = 0.5 * std::f32::consts::SQRT_2;
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(m.points.len(), 1);
+        let p = m.points[0];
+        assert!((p.depth - (2.0 * edge - 1.35)).abs() < 1e-4);
+        assert!(near(&p.position, &glm::vec3(0.0, 1.35 * 0.5, 0.0)));
+        assert!(matches!(
+            (p.feature_a, p.feature_b),
+            (Feature::Edge(_), Feature::Edge(_))
+        ));
+    }
+
+    #[test]
+    fn sphere_box_features() {
+        let zero = glm::Vec3::zeros();
+        let cube = cuboid(zero, glm::vec3(1.0, 1.0, 1.0), zero);
+        let feature = |center: glm::Vec3, radius: f32| {
+            let m = collide_manifold(&sphere(center, radius), &cube).unwrap();
+            (m.points[0].feature_b, m)
+        };
+
+        let (face, m) = feature(glm::vec3(0.0, 1.5, 0.0), 1.0);
+        assert_eq!(face, Feature::Face(2));
+        assert!(near(&m.normal, &glm::vec3(0.0, -1.0, 0.0)));
+        assert!((m.depth() - 0.5).abs() < 1e-5);
+
+        assert_eq!(feature(glm::vec3(1.3, 1.3, 1.3), 1.0).0, Feature::Vertex(7));
+        assert_eq!(
+            feature(glm::vec3(1.3, -1.3, -1.3), 1.0).0,
+            Feature::Vertex(1)
+        );
+        assert_eq!(feature(glm::vec3(1.3, 1.3, 0.0), 1.0).0, Feature::Edge(11));
+
+        // centrul în cutie: iese pe fața cea mai apropiată
+        let (inside, m) = feature(glm::vec3(0.0, 0.8, 0.0), 0.5);
+        assert_eq!(inside, Feature::Face(2));
+        assert!((m.depth() - 0.7).abs() < 1e-5);
+
+        // invers, trăsăturile și normala se schimbă între ele
+        let m = collide_manifold(&cube, &sphere(glm::vec3(0.0, 1.5, 0.0), 1.0)).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(
+            (m.points[0].feature_a, m.points[0].feature_b),
+            (Feature::Face(2), Feature::Surface)
+        );
+        assert!(collide_manifold(&sphere(glm::vec3(0.0, 2.5, 0.0), 1.0), &cube).is_none());
+    }
+
+    #[test]
+    fn capsule_lying_on_plane_touches_with_both_ends() {
+        let ground = Collider::Plane(Plane::new());
+        let m = collide_manifold(&ground, &capsule(glm::vec3(0.0, 0.4, 0.0), 90.0)).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(
+            vertices(&m),
+            [
+                (Feature::Face(0), Feature::Vertex(0)),
+                (Feature::Face(0), Feature::Vertex(1)),
+            ]
+        );
+        for p in &m.points {
+            assert!((p.depth - 0.1).abs() < 1e-4);
+            assert!((p.position.y + 0.05).abs() < 1e-4);
+        }
+        // plan al doilea: normala se întoarce
+        let m = collide_manifold(&capsule(glm::vec3(0.0, 0.4, 0.0), 90.0), &ground).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, -1.0, 0.0)));
+    }
+
+    #[test]
+    fn capsule_lying_on_box_touches_with_both_ends() {
+        let zero = glm::Vec3::zeros();
+        let ground = cuboid(zero, glm::vec3(2.0, 0.5, 2.0), zero);
+        let m = collide_manifold(&ground, &capsule(glm::vec3(0.0, 0.9, 0.0), 90.0)).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert_eq!(
+            vertices(&m),
+            [
+                (Feature::Surface, Feature::Vertex(0)),
+                (Feature::Surface, Feature::Vertex(1)),
+            ]
+        );
+        for p in &m.points {
+            assert!((p.depth - 0.1).abs() < 1e-4);
+            assert!((p.position.x.abs() - 0.5).abs() < 1e-4);
+        }
+        // în picioare atinge doar cu un capăt
+        let m = collide_manifold(&ground, &capsule(glm::vec3(0.0, 1.4, 0.0), 0.0)).unwrap();
+        assert_eq!(m.points.len(), 1);
+        assert!((m.depth() - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sphere_on_cylinder() {
+        let cylinder = Collider::Cylinder(Cylinder::new());
+        let ball = sphere(glm::vec3(0.1, 0.9, 0.0), 0.5);
+        let m = collide_manifold(&cylinder, &ball).unwrap();
+        assert!(near(&m.normal, &glm::vec3(0.0, 1.0, 0.0)));
+        assert!((m.depth() - 0.1).abs() < 1e-4);
+        assert!(near(&m.points[0].position, &glm::vec3(0.1, 0.45, 0.0)));
+        assert!(collide_manifold(&cylinder, &sphere(glm::vec3(0.9, 0.0, 0.0), 0.3)).is_none());
+    }
+
+    #[test]
+    fn cylinder_standing_on_plane() {
+        let ground = Collider::Plane(Plane::new());
+        let mut c = Cylinder::new();
+        c.position = glm::vec3(0.0, 0.4, 0.0);
+        let m = collide_manifold(&ground, &Collider::Cylinder(c)).unwrap();
+        // marginea bazei de jos, cele 4 puncte
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert!((p.depth - 0.1).abs() < 1e-4);
+            assert!(matches!(p.feature_b, Feature::Vertex(0..4)));
+        }
+    }
+
+    #[test]
+    fn overlapping_hulls_go_through_epa() {
+        let a = hull_cube(glm::Vec3::zeros());
+        let b = hull_cube(glm::vec3(1.5, 0.2, -0.1));
+        let m = collide_manifold(&a, &b).unwrap();
+        assert!(near(&m.normal, &glm::vec3(1.0, 0.0, 0.0)));
+        assert!((m.depth() - 0.5).abs() < 1e-3);
+        assert!(near(
+            &collide_pair(&a, &b).unwrap(),
+            &glm::vec3(-0.5, 0.0, 0.0)
+        ));
+        assert!(collide_manifold(&a, &hull_cube(glm::vec3(2.1, 0.0, 0.0))).is_none());
+    }
+
+    #[test]
+    fn sphere_inside_capsule_core() {
+        // centrul sferei e pe segmentul capsulei: miezurile se ating și trece prin EPA
+        let c = capsule(glm::Vec3::zeros(), 0.0);
+        let m = collide_manifold(&c, &sphere(glm::vec3(0.0, 0.2, 0.0), 0.5)).unwrap();
+        // pe forme rotunjite EPA aproximează suprafața cu un politop
+        assert!((m.depth() - 1.0).abs() < 2e-2);
+        assert!(m.normal.y.abs() < 2e-2);
     }
 }
@@ -0,0 +1,450 @@
+use crate::seb::broadphase::Aabb;
+use crate::seb::collision::Collider;
+use crate::seb::physics::PhysicsWorld;
+use crate::seb::primitives::{Line, LineRenderer, Vector, VectorRenderer};
+use crate::seb::seb::ShaderError;
+use nalgebra_glm as glm;
+
+const CIRCLE_SEGMENTS: usize = 24;
+// cât de mare e crucea din punctul de contact
+const CONTACT_SIZE: f32 = 0.05;
+// planul e infinit, desenăm doar un pătrat în jurul punctului cel mai apropiat de origine
+const PLANE_SIZE: f32 = 10.0;
+
+const SHAPE_COLOR: glm::Vec4 = glm::Vec4::new(0.2, 1.0, 0.2, 1.0);
+const STATIC_COLOR: glm::Vec4 = glm::Vec4::new(0.6, 0.6, 0.6, 1.0);
+const SLEEPING_COLOR: glm::Vec4 = glm::Vec4::new(0.2, 0.4, 1.0, 1.0);
+const TRIGGER_COLOR: glm::Vec4 = glm::Vec4::new(1.0, 0.8, 0.0, 1.0);
+const LEAF_COLOR: glm::Vec4 = glm::Vec4::new(1.0, 0.5, 0.0, 1.0);
+const NODE_COLOR: glm::Vec4 = glm::Vec4::new(0.6, 0.3, 0.0, 1.0);
+const CONTACT_COLOR: glm::Vec4 = glm::Vec4::new(1.0, 0.1, 0.1, 1.0);
+const VELOCITY_COLOR: glm::Vec4 = glm::Vec4::new(0.0, 1.0, 1.0, 1.0);
+const JOINT_COLOR: glm::Vec4 = glm::Vec4::new(1.0, 0.2, 1.0, 1.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugCategory {
+    // conturul colliderelor: cutii orientate, cercuri pentru sfere, capsule, cilindri, înfășurători
+    Shapes,
+    // nodurile arborelui din broadphase, frunzele mai deschise la culoare
+    Broadphase,
+    // punctele de contact și normalele lor
+    Contacts,
+    Velocities,
+    // ancorele articulațiilor și linia dintre ele
+    Joints,
+}
+
+impl DebugCategory {
+    pub const ALL: [DebugCategory; 5] = [
+        DebugCategory::Shapes,
+        DebugCategory::Broadphase,
+        DebugCategory::Contacts,
+        DebugCategory::Velocities,
+        DebugCategory::Joints,
+    ];
+}
+
+/*
+Desenul de depanare pentru fizică. Liniile și săgețile se adună peste frame cu `world`
+sau cu funcțiile pentru câte o formă, apoi `draw` le trimite la LineRenderer și
+VectorRenderer și golește listele. Fiecare categorie se poate porni și opri separat.
+Rendererele se creează la primul `draw`, așa că listele se pot umple și fără context OpenGL.
+*/
+pub struct DebugDraw {
+    pub enabled: [bool; DebugCategory::ALL.len()],
+    // lungimea săgeții pentru o viteză de 1 m/s
+    pub velocity_scale: f32,
+    // lungimea normalei din punctul de contact
+    pub normal_length: f32,
+    lines: Vec<Line>,
+    vectors: Vec<Vector>,
+    renderers: Option<(LineRenderer, VectorRenderer)>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self {
+            enabled: [false; DebugCategory::ALL.len()],
+            velocity_scale: 0.25,
+            normal_length: 0.3,
+            lines: Vec::new(),
+            vectors: Vec::new(),
+            renderers: None,
+        }
+    }
+    pub fn is_enabled(&self, category: DebugCategory) -> bool {
+        self.enabled[category as usize]
+    }
+    pub fn set_enabled(&mut self, category: DebugCategory, enabled: bool) {
+        self.enabled[category as usize] = enabled;
+    }
+    pub fn toggle(&mut self, category: DebugCategory) {
+        self.enabled[category as usize] = !self.enabled[category as usize];
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.vectors.is_empty()
+    }
+
+    pub fn line(&mut self, start: glm::Vec3, end: glm::Vec3, color: glm::Vec4) {
+        let mut line = Line::from(start, end);
+        line.color = color;
+        self.lines.push(line);
+    }
+    pub fn arrow(&mut self, position: glm::Vec3, direction: glm::Vec3, color: glm::Vec4) {
+        let mut vector = Vector::from(position, direction);
+        vector.color = color;
+        self.vectors.push(vector);
+    }
+    // cerc în planul dat de axele u și v (unitare și perpendiculare)
+    pub fn circle(
+        &mut self,
+        center: glm::Vec3,
+        u: glm::Vec3,
+        v: glm::Vec3,
+        radius: f32,
+        color: glm::Vec4,
+    ) {
+        self.arc(center, u, v, radius, (0.0, std::f32::consts::TAU), color);
+    }
+    pub fn sphere(
+        &mut self,
+        center: glm::Vec3,
+        rotation: &glm::Mat3,
+        radius: f32,
+        color: glm::Vec4,
+    ) {
+        let axes = [rotation.column(0), rotation.column(1), rotation.column(2)];
+        for i in 0..3 {
+            let (u, v) = (axes[i].into(), axes[(i + 1) % 3].into());
+            self.circle(center, u, v, radius, color);
+        }
+    }
+    // cutie orientată: centrul, rotația și jumătățile laturilor
+    pub fn obb(
+        &mut self,
+        center: glm::Vec3,
+        rotation: &glm::Mat3,
+        half: glm::Vec3,
+        color: glm::Vec4,
+    ) {
+        let corner = |i: usize| {
+            let sign = glm::vec3(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            center + rotation * half.component_mul(&sign)
+        };
+        // colțurile care diferă printr-un singur bit sunt capetele unei muchii
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+    pub fn aabb(&mut self, aabb: &Aabb, color: glm::Vec4) {
+        let center = (aabb.min + aabb.max) * 0.5;
+        let half = (aabb.max - aabb.min) * 0.5;
+        self.obb(center, &glm::Mat3::identity(), half, color);
+    }
+    pub fn collider(&mut self, collider: &Collider, color: glm::Vec4) {
+        let rotation = collider.rotation();
+        match collider {
+            Collider::Rectangle(r) => self.obb(r.position, &rotation, r.scale, color),
+            Collider::Sphere(s) => self.sphere(s.position, &rotation, s.scale, color),
+            Collider::Capsule(c) => {
+                let (bottom, top) = c.segment();
+                let axis: glm::Vec3 = rotation.column(1).into();
+                let sides: [glm::Vec3; 2] = [rotation.column(0).into(), rotation.column(2).into()];
+                let pi = std::f32::consts::PI;
+                self.circle(top, sides[0], sides[1], c.radius, color);
+                self.circle(bottom, sides[0], sides[1], c.radius, color);
+                for side in sides {
+                    self.line(bottom + side * c.radius, top + side * c.radius, color);
+                    self.line(bottom - side * c.radius, top - side * c.radius, color);
+                    // capetele rotunde, câte o jumătate de cerc în fiecare plan lateral
+                    self.arc(top, side, axis, c.radius, (0.0, pi), color);
+                    self.arc(bottom, side, axis, c.radius, (pi, 2.0 * pi), color);
+                }
+            }
+            Collider::Cylinder(c) => {
+                let axis: glm::Vec3 = rotation.column(1).into();
+                let sides: [glm::Vec3; 2] = [rotation.column(0).into(), rotation.column(2).into()];
+                let (bottom, top) = (
+                    c.position - axis * c.half_height,
+                    c.position + axis * c.half_height,
+                );
+                self.circle(top, sides[0], sides[1], c.radius, color);
+                self.circle(bottom, sides[0], sides[1], c.radius, color);
+                for side in sides {
+                    self.line(bottom + side * c.radius, top + side * c.radius, color);
+                    self.line(bottom - side * c.radius, top - side * c.radius, color);
+                }
+            }
+            Collider::Plane(p) => {
+                let normal = glm::normalize(&p.normal);
+                let center = normal * p.distance;
+                let helper = if normal.y.abs() < 0.9 {
+                    glm::vec3(0.0, 1.0, 0.0)
+                } else {
+                    glm::vec3(1.0, 0.0, 0.0)
+                };
+                let u = glm::normalize(&glm::cross(&normal, &helper));
+                let v = glm::cross(&normal, &u);
+                let plane = glm::mat3(u.x, normal.x, v.x, u.y, normal.y, v.y, u.z, normal.z, v.z);
+                self.obb(
+                    center,
+                    &plane,
+                    glm::vec3(PLANE_SIZE, 0.0, PLANE_SIZE),
+                    color,
+                );
+                self.arrow(center, normal, color);
+            }
+            Collider::ConvexHull(h) => {
+                let points = h.world_points();
+                // muchiile comune a două triunghiuri apar o singură dată
+                let mut edges: Vec<(u32, u32)> = Vec::new();
+                for face in &h.faces {
+                    for k in 0..3 {
+                        let (a, b) = (face[k], face[(k + 1) % 3]);
+                        let edge = (a.min(b), a.max(b));
+                        if !edges.contains(&edge) {
+                            edges.push(edge);
+                        }
+                    }
+                }
+                for (a, b) in edges {
+                    self.line(points[a as usize], points[b as usize], color);
+                }
+            }
+        }
+    }
+    // adună tot ce e pornit din lume; desenul se face la `draw`
+    pub fn world(&mut self, world: &PhysicsWorld) {
+        if self.is_enabled(DebugCategory::Shapes) {
+            for body in &world.bodies {
+                let color = if body.trigger {
+                    TRIGGER_COLOR
+                } else if body.is_static() {
+                    STATIC_COLOR
+                } else if body.is_sleeping() {
+                    SLEEPING_COLOR
+                } else {
+                    SHAPE_COLOR
+                };
+                match &body.collider {
+                    // sfera nu-și ține rotația, o luăm de la corp ca să se vadă cum se rostogolește
+                    Collider::Sphere(s) => {
+                        self.sphere(s.position, &body.rotation(), s.scale, color)
+                    }
+                    collider => self.collider(collider, color),
+                }
+            }
+        }
+        if self.is_enabled(DebugCategory::Broadphase) {
+            for (aabb, leaf) in world.broadphase().nodes() {
+                self.aabb(&aabb, if leaf { LEAF_COLOR } else { NODE_COLOR });
+            }
+        }
+        if self.is_enabled(DebugCategory::Contacts) {
+            for contact in world.contacts() {
+                let p = contact.point;
+                for axis in [glm::Vec3::x(), glm::Vec3::y(), glm::Vec3::z()] {
+                    self.line(
+                        p - axis * CONTACT_SIZE,
+                        p + axis * CONTACT_SIZE,
+                        CONTACT_COLOR,
+                    );
+                }
+                self.arrow(p, contact.normal * self.normal_length, CONTACT_COLOR);
+            }
+        }
+        if self.is_enabled(DebugCategory::Velocities) {
+            for body in &world.bodies {
+                if body.is_static() || body.is_sleeping() || glm::length2(&body.velocity) < 1e-4 {
+                    continue;
+                }
+                self.arrow(
+                    body.position(),
+                    body.velocity * self.velocity_scale,
+                    VELOCITY_COLOR,
+                );
+            }
+        }
+        if self.is_enabled(DebugCategory::Joints) {
+            for i in 0..world.joints.len() {
+                let (a, b) = world.joint_anchors(i);
+                self.line(a, b, JOINT_COLOR);
+                for anchor in [a, b] {
+                    self.line(
+                        anchor - glm::Vec3::y() * CONTACT_SIZE,
+                        anchor + glm::Vec3::y() * CONTACT_SIZE,
+                        JOINT_COLOR,
+                    );
+                }
+            }
+        }
+    }
+    // listele se golesc și când shaderele nu se compilează, ca să nu crească la nesfârșit
+    pub fn draw(&mut self, projection: glm::Mat4, view: glm::Mat4) -> Result<(), ShaderError> {
+        let result = self.render(projection, view);
+        self.lines.clear();
+        self.vectors.clear();
+        result
+    }
+    fn render(&mut self, projection: glm::Mat4, view: glm::Mat4) -> Result<(), ShaderError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let (line_renderer, vector_renderer) = match &mut self.renderers {
+            Some(renderers) => renderers,
+            renderers => renderers.insert((LineRenderer::new()?, VectorRenderer::new()?)),
+        };
+        if !self.lines.is_empty() {
+            line_renderer.draw(projection, view, &mut self.lines);
+        }
+        if !self.vectors.is_empty() {
+            vector_renderer.draw(projection, view, &mut self.vectors);
+        }
+        Ok(())
+    }
+
+    fn arc(
+        &mut self,
+        center: glm::Vec3,
+        u: glm::Vec3,
+        v: glm::Vec3,
+        radius: f32,
+        (from, to): (f32, f32),
+        color: glm::Vec4,
+    ) {
+        let segments = ((to - from).abs() / std::f32::consts::TAU * CIRCLE_SEGMENTS as f32)
+            .ceil()
+            .max(1.0) as usize;
+        let point = |angle: f32| center + (u * angle.cos() + v * angle.sin()) * radius;
+        for i in 0..segments {
+            let a = from + (to - from) * i as f32 / segments as f32;
+            let b = from + (to - from) * (i + 1) as f32 / segments as f32;
+            self.line(point(a), point(b), color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::euler_to_matrix;
+    use crate::seb::joints::Joint;
+    use crate::seb::physics::{RigidBody, TERRAIN};
+    use crate::seb::primitives::{Rectangle, Sphere};
+
+    #[test]
+    fn obb_draws_twelve_edges_between_the_rotated_corners() {
+        let mut debug = DebugDraw::new();
+        let center = glm::vec3(1.0, 2.0, 3.0);
+        let rotation = euler_to_matrix(20.0, 35.0, -10.0);
+        let half = glm::vec3(0.5, 1.0, 2.0);
+        debug.obb(center, &rotation, half, SHAPE_COLOR);
+        assert_eq!(debug.lines.len(), 12);
+        assert!(debug.vectors.is_empty());
+
+        // colțul din coordonatele lui în spațiul cutiei, -1 sau 1 pe fiecare axă
+        let local = |p: &glm::Vec3| {
+            let l = rotation.transpose() * (p - center);
+            glm::vec3(l.x / half.x, l.y / half.y, l.z / half.z)
+        };
+        let mut edges = Vec::new();
+        for line in &debug.lines {
+            let (a, b) = (local(&line.start), local(&line.end));
+            for v in [a, b] {
+                assert!(v.iter().all(|c| (c.abs() - 1.0).abs() < 1e-4), "{:?}", v);
+            }
+            // capetele diferă pe o singură axă
+            let differ = (0..3).filter(|&k| (a[k] - b[k]).abs() > 1.0).count();
+            assert_eq!(differ, 1);
+            let key = |v: glm::Vec3| v.map(|c| c.round() as i32);
+            let edge = (key(a), key(b));
+            assert!(!edges.contains(&edge) && !edges.contains(&(edge.1, edge.0)));
+            edges.push(edge);
+        }
+    }
+
+    // un corp pe podea (deci cu contacte) și o balama prinsă de lume
+    fn busy_world() -> PhysicsWorld {
+        let mut world = PhysicsWorld::new();
+        let mut floor = Rectangle::new();
+        floor.position = glm::vec3(0.0, -0.5, 0.0);
+        floor.scale = glm::vec3(5.0, 0.5, 5.0);
+        world.add_body(RigidBody::fixed(Collider::Rectangle(floor)));
+        let mut ball = Sphere::new();
+        ball.position = glm::vec3(0.0, 0.24, 0.0);
+        ball.scale = 0.25;
+        let ball = world.add_body(RigidBody::new(Collider::Sphere(ball), 1.0));
+        world.body_mut(ball).velocity = glm::vec3(1.0, 0.0, 0.0);
+        world.add_joint(Joint::hinge(
+            ball,
+            TERRAIN,
+            glm::vec3(0.0, 1.0, 0.0),
+            glm::vec3(0.0, 0.0, 1.0),
+        ));
+        world.step(1.0 / 60.0);
+        assert!(!world.contacts().is_empty());
+        world
+    }
+
+    #[test]
+    fn disabled_categories_draw_nothing() {
+        let world = busy_world();
+        let mut debug = DebugDraw::new();
+        debug.world(&world);
+        assert!(debug.is_empty());
+
+        // doar formele: linii, dar nicio săgeată de viteză sau normală
+        debug.set_enabled(DebugCategory::Shapes, true);
+        debug.world(&world);
+        assert!(!debug.lines.is_empty());
+        assert!(debug.vectors.is_empty());
+        assert!(
+            debug
+                .lines
+                .iter()
+                .all(|l| l.color != CONTACT_COLOR && l.color != JOINT_COLOR)
+        );
+    }
+
+    #[test]
+    fn contacts_and_joints_follow_their_toggles() {
+        let world = busy_world();
+        let contacts = world.contacts().len();
+        let mut debug = DebugDraw::new();
+
+        // o cruce de trei linii și o normală pe contact
+        debug.set_enabled(DebugCategory::Contacts, true);
+        debug.world(&world);
+        assert_eq!(debug.lines.len(), 3 * contacts);
+        assert_eq!(debug.vectors.len(), contacts);
+        assert!(debug.lines.iter().all(|l| l.color == CONTACT_COLOR));
+        let normal = debug.vectors[0].direction;
+        assert!(glm::distance(&normal, &(world.contacts()[0].normal * debug.normal_length)) < 1e-6);
+
+        // linia dintre ancore și câte un semn la fiecare ancoră
+        debug.lines.clear();
+        debug.vectors.clear();
+        debug.toggle(DebugCategory::Contacts);
+        debug.toggle(DebugCategory::Joints);
+        debug.world(&world);
+        assert_eq!(debug.lines.len(), 3);
+        assert!(debug.vectors.is_empty());
+        let (a, b) = world.joint_anchors(0);
+        assert_eq!((debug.lines[0].start, debug.lines[0].end), (a, b));
+        assert!(debug.lines.iter().all(|l| l.color == JOINT_COLOR));
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// framebuffer-ul în care desenează toată lumea când nu are unul al ei; 0 e fereastra
+static DEFAULT_FRAMEBUFFER: AtomicU32 = AtomicU32::new(0);
+
+pub fn default_framebuffer() -> u32 {
+    DEFAULT_FRAMEBUFFER.load(Ordering::Relaxed)
+}
+pub fn set_default_framebuffer(framebuffer: u32) {
+    DEFAULT_FRAMEBUFFER.store(framebuffer, Ordering::Relaxed);
+}
+// în loc de BindFramebuffer(0), ca pass-urile intermediare (umbre) să meargă și headless
+pub fn bind_default() {
+    unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, default_framebuffer());
+    }
+}
+
+/*
+Culoare RGBA8 într-o textură plus depth/stencil într-un renderbuffer. Fără fereastră
+e ținta implicită a desenării, iar `read_pixels` scoate imaginea înapoi.
+*/
+pub struct FrameBuffer {
+    pub framebuffer: u32,
+    pub texture_colorbuffer: u32,
+    pub renderbuffer: u32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Drop for FrameBuffer {
+    fn drop(&mut self) {
+        if default_framebuffer() == self.framebuffer {
+            set_default_framebuffer(0);
+        }
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteTextures(1, &self.texture_colorbuffer);
+            gl::DeleteRenderbuffers(1, &self.renderbuffer);
+        }
+    }
+}
+
+impl FrameBuffer {
+    pub fn new(width: i32, height: i32) -> Result<Self, String> {
+        let mut framebuffer = 0;
+        let mut texture_colorbuffer = 0;
+        let mut renderbuffer = 0;
+        let status;
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+
+            gl::GenTextures(1, &mut texture_colorbuffer);
+            gl::BindTexture(gl::TEXTURE_2D, texture_colorbuffer);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                texture_colorbuffer,
+                0,
+            );
+
+            gl::GenRenderbuffers(1, &mut renderbuffer);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                renderbuffer,
+            );
+
+            status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, default_framebuffer());
+        }
+        let framebuffer = Self {
+            framebuffer,
+            texture_colorbuffer,
+            renderbuffer,
+            width,
+            height,
+        };
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!("framebuffer incomplet: 0x{:x}", status));
+        }
+        Ok(framebuffer)
+    }
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::Viewport(0, 0, self.width, self.height);
+        }
+    }
+    pub fn read_pixels(&self) -> Vec<u8> {
+        read_pixels(self.framebuffer, self.width, self.height)
+    }
+}
+
+// RGBA, rândurile de sus în jos ca într-o imagine obișnuită
+pub fn read_pixels(framebuffer: u32, width: i32, height: i32) -> Vec<u8> {
+    let row = width.max(0) as usize * 4;
+    let mut pixels = vec![0u8; row * height.max(0) as usize];
+    if pixels.is_empty() {
+        return pixels;
+    }
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            width,
+            height,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut _,
+        );
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, default_framebuffer());
+    }
+    // OpenGL începe de jos
+    let mut flipped = Vec::with_capacity(pixels.len());
+    for line in pixels.chunks_exact(row).rev() {
+        flipped.extend_from_slice(line);
+    }
+    flipped
+}
@@ -0,0 +1,504 @@
+use nalgebra_glm as glm;
+
+const GJK_ITERATIONS: usize = 64;
+const EPA_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-4;
+
+// un punct din diferența Minkowski A - B, cu punctele din A și B care l-au generat
+#[derive(Clone, Copy, Debug)]
+pub struct SupportPoint {
+    pub point: glm::Vec3,
+    pub a: glm::Vec3,
+    pub b: glm::Vec3,
+}
+
+fn support(
+    support_a: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    support_b: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    dir: &glm::Vec3,
+) -> SupportPoint {
+    let a = support_a(dir);
+    let b = support_b(&-dir);
+    SupportPoint { point: a - b, a, b }
+}
+
+#[derive(Clone, Debug)]
+pub struct GjkResult {
+    // 0 dacă formele se intersectează
+    pub distance: f32,
+    pub point_a: glm::Vec3,
+    pub point_b: glm::Vec3,
+    // la intersecție conține originea, de aici pornește EPA
+    pub simplex: Vec<SupportPoint>,
+}
+impl GjkResult {
+    pub fn intersects(&self) -> bool {
+        self.distance <= 0.0
+    }
+}
+
+/*
+GJK pe funcții support: `support_a(d)` e punctul lui A cel mai departe pe direcția d.
+Dă distanța și cele mai apropiate puncte dintre cele două forme convexe.
+*/
+pub fn gjk_distance(
+    support_a: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    support_b: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    initial_dir: glm::Vec3,
+) -> GjkResult {
+    let dir = if glm::length2(&initial_dir) > 1e-12 {
+        initial_dir
+    } else {
+        glm::vec3(1.0, 0.0, 0.0)
+    };
+    let first = support(support_a, support_b, &dir);
+    let mut simplex = vec![first];
+    let mut weights = vec![1.0];
+    let mut v = first.point;
+
+    for _ in 0..GJK_ITERATIONS {
+        let vv = glm::dot(&v, &v);
+        if vv < 1e-12 {
+            break;
+        }
+        let w = support(support_a, support_b, &-v);
+        // nu ne mai apropiem de origine
+        if vv - glm::dot(&v, &w.point) <= 1e-6 * vv.max(1e-6)
+            || simplex
+                .iter()
+                .any(|s| glm::distance2(&s.point, &w.point) < 1e-12)
+        {
+            break;
+        }
+        simplex.push(w);
+        let (closest, kept) = closest_on_simplex(&simplex);
+        simplex = kept.iter().map(|&(i, _)| simplex[i]).collect();
+        weights = kept.iter().map(|&(_, w)| w).collect();
+        v = closest;
+        if simplex.len() == 4 {
+            v = glm::Vec3::zeros();
+            break;
+        }
+    }
+
+    let distance = glm::length(&v);
+    let (point_a, point_b) = if distance < 1e-6 {
+        let p = simplex[0].a;
+        (p, p)
+    } else {
+        let mut pa = glm::Vec3::zeros();
+        let mut pb = glm::Vec3::zeros();
+        for (s, w) in simplex.iter().zip(&weights) {
+            pa += s.a * *w;
+            pb += s.b * *w;
+        }
+        (pa, pb)
+    };
+    GjkResult {
+        distance: if distance < 1e-6 { 0.0 } else { distance },
+        point_a,
+        point_b,
+        simplex,
+    }
+}
+
+// cel mai apropiat punct de origine și vârfurile (cu ponderi) din care se formează
+fn closest_on_simplex(simplex: &[SupportPoint]) -> (glm::Vec3, Vec<(usize, f32)>) {
+    let p: Vec<glm::Vec3> = simplex.iter().map(|s| s.point).collect();
+    match p.len() {
+        1 => (p[0], vec![(0, 1.0)]),
+        2 => closest_on_segment(&p, [0, 1]),
+        3 => closest_on_triangle(&p, [0, 1, 2]),
+        _ => closest_on_tetrahedron(&p),
+    }
+}
+
+fn closest_on_segment(p: &[glm::Vec3], [i, j]: [usize; 2]) -> (glm::Vec3, Vec<(usize, f32)>) {
+    let ab = p[j] - p[i];
+    let denom = glm::dot(&ab, &ab);
+    let t = if denom > 1e-12 {
+        (-glm::dot(&p[i], &ab) / denom).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    if t <= 0.0 {
+        (p[i], vec![(i, 1.0)])
+    } else if t >= 1.0 {
+        (p[j], vec![(j, 1.0)])
+    } else {
+        (p[i] + ab * t, vec![(i, 1.0 - t), (j, t)])
+    }
+}
+
+// Ericson, "Real-Time Collision Detection" 5.1.5, cu punctul în origine
+fn closest_on_triangle(p: &[glm::Vec3], [i, j, k]: [usize; 3]) -> (glm::Vec3, Vec<(usize, f32)>) {
+    let (a, b, c) = (p[i], p[j], p[k]);
+    let ab = b - a;
+    let ac = c - a;
+    let ap = -a;
+    let d1 = glm::dot(&ab, &ap);
+    let d2 = glm::dot(&ac, &ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (a, vec![(i, 1.0)]);
+    }
+    let bp = -b;
+    let d3 = glm::dot(&ab, &bp);
+    let d4 = glm::dot(&ac, &bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (b, vec![(j, 1.0)]);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let t = d1 / (d1 - d3);
+        return (a + ab * t, vec![(i, 1.0 - t), (j, t)]);
+    }
+    let cp = -c;
+    let d5 = glm::dot(&ab, &cp);
+    let d6 = glm::dot(&ac, &cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (c, vec![(k, 1.0)]);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let t = d2 / (d2 - d6);
+        return (a + ac * t, vec![(i, 1.0 - t), (k, t)]);
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * t, vec![(j, 1.0 - t), (k, t)]);
+    }
+    let denom = va + vb + vc;
+    if denom.abs() < 1e-20 {
+        // triunghi degenerat
+        return closest_on_segment(p, [i, j]);
+    }
+    let v = vb / denom;
+    let w = vc / denom;
+    (a + ab * v + ac * w, vec![(i, 1.0 - v - w), (j, v), (k, w)])
+}
+
+fn closest_on_tetrahedron(p: &[glm::Vec3]) -> (glm::Vec3, Vec<(usize, f32)>) {
+    let faces = [[0, 1, 2, 3], [0, 2, 3, 1], [0, 3, 1, 2], [1, 3, 2, 0]];
+    // tetraedru turtit: nu poate conține originea, luăm cea mai apropiată față
+    let volume = glm::dot(&glm::cross(&(p[1] - p[0]), &(p[2] - p[0])), &(p[3] - p[0]));
+    let size = (0..4)
+        .map(|i| glm::length2(&(p[i] - p[0])))
+        .fold(0.0, f32::max);
+    let flat = volume.abs() <= 1e-5 * size.powf(1.5);
+
+    let mut best: Option<(glm::Vec3, Vec<(usize, f32)>)> = None;
+    let mut inside = !flat;
+    for [i, j, k, opposite] in faces {
+        let n = glm::cross(&(p[j] - p[i]), &(p[k] - p[i]));
+        let side_origin = glm::dot(&n, &-p[i]);
+        let side_opposite = glm::dot(&n, &(p[opposite] - p[i]));
+        // originea e de cealaltă parte a feței decât al patrulea vârf
+        if flat || side_origin * side_opposite < 0.0 {
+            inside = false;
+            let candidate = closest_on_triangle(p, [i, j, k]);
+            if best
+                .as_ref()
+                .is_none_or(|b| glm::length2(&candidate.0) < glm::length2(&b.0))
+            {
+                best = Some(candidate);
+            }
+        }
+    }
+    if inside {
+        return (glm::Vec3::zeros(), (0..4).map(|i| (i, 0.25)).collect());
+    }
+    best.unwrap()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Penetration {
+    // de la A spre B
+    pub normal: glm::Vec3,
+    pub depth: f32,
+    pub point_a: glm::Vec3,
+    pub point_b: glm::Vec3,
+}
+
+/*
+EPA: pornind de la simplexul GJK care conține originea, crește politopul spre fața
+diferenței Minkowski cea mai apropiată de origine. Aceea dă normala și adâncimea.
+*/
+pub fn epa(
+    support_a: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    support_b: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    simplex: &[SupportPoint],
+) -> Option<Penetration> {
+    let mut vertices = simplex.to_vec();
+    if !complete_tetrahedron(support_a, support_b, &mut vertices) {
+        return None;
+    }
+
+    // fețe cu normala în afară (originea e înăuntru)
+    let mut faces: Vec<[usize; 3]> = Vec::new();
+    let center = vertices.iter().map(|v| v.point).sum::<glm::Vec3>() / 4.0;
+    for [a, b, c] in [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]] {
+        let n = glm::cross(
+            &(vertices[b].point - vertices[a].point),
+            &(vertices[c].point - vertices[a].point),
+        );
+        if glm::dot(&n, &(vertices[a].point - center)) < 0.0 {
+            faces.push([a, c, b]);
+        } else {
+            faces.push([a, b, c]);
+        }
+    }
+
+    let face_plane = |vertices: &[SupportPoint], f: &[usize; 3]| -> Option<(glm::Vec3, f32)> {
+        let n = glm::cross(
+            &(vertices[f[1]].point - vertices[f[0]].point),
+            &(vertices[f[2]].point - vertices[f[0]].point),
+        );
+        let len = glm::length(&n);
+        if len < 1e-12 {
+            return None;
+        }
+        let n = n / len;
+        Some((n, glm::dot(&n, &vertices[f[0]].point)))
+    };
+
+    let mut result = None;
+    for _ in 0..EPA_ITERATIONS {
+        let mut closest: Option<(usize, glm::Vec3, f32)> = None;
+        for (i, f) in faces.iter().enumerate() {
+            if let Some((n, d)) = face_plane(&vertices, f)
+                && closest.is_none_or(|(_, _, best)| d < best)
+            {
+                closest = Some((i, n, d));
+            }
+        }
+        let (index, normal, distance) = closest?;
+        result = Some((faces[index], normal, distance));
+
+        let w = support(support_a, support_b, &normal);
+        if glm::dot(&w.point, &normal) - distance < EPA_TOLERANCE {
+            break;
+        }
+
+        // scoatem fețele văzute din w și le închidem cu triunghiuri spre w
+        let new_index = vertices.len();
+        vertices.push(w);
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|f| {
+            let visible =
+                face_plane(&vertices, f).is_none_or(|(n, d)| glm::dot(&n, &w.point) - d > 0.0);
+            if visible {
+                for (u, v) in [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])] {
+                    // o muchie comună a două fețe vizibile nu e pe orizont
+                    if let Some(pos) = horizon.iter().position(|&e| e == (v, u)) {
+                        horizon.swap_remove(pos);
+                    } else {
+                        horizon.push((u, v));
+                    }
+                }
+            }
+            !visible
+        });
+        for (u, v) in horizon {
+            faces.push([u, v, new_index]);
+        }
+        if faces.is_empty() {
+            break;
+        }
+    }
+
+    let (face, normal, depth) = result?;
+    // coordonatele baricentrice ale proiecției originii pe față dau punctele din A și B
+    let (a, b, c) = (vertices[face[0]], vertices[face[1]], vertices[face[2]]);
+    let bary = barycentric(&(normal * depth), &a.point, &b.point, &c.point);
+    Some(Penetration {
+        normal,
+        depth,
+        point_a: a.a * bary.x + b.a * bary.y + c.a * bary.z,
+        point_b: a.b * bary.x + b.b * bary.y + c.b * bary.z,
+    })
+}
+
+fn barycentric(p: &glm::Vec3, a: &glm::Vec3, b: &glm::Vec3, c: &glm::Vec3) -> glm::Vec3 {
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+    let d00 = glm::dot(&v0, &v0);
+    let d01 = glm::dot(&v0, &v1);
+    let d11 = glm::dot(&v1, &v1);
+    let d20 = glm::dot(&v2, &v0);
+    let d21 = glm::dot(&v2, &v1);
+    let denom = d00 * d11 - d01 * d01;
+    if denom.abs() < 1e-20 {
+        return glm::vec3(1.0, 0.0, 0.0);
+    }
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    glm::vec3(1.0 - v - w, v, w)
+}
+
+// GJK se poate opri cu un simplex mai mic (la atingere), îl completăm până la tetraedru
+fn complete_tetrahedron(
+    support_a: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    support_b: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    vertices: &mut Vec<SupportPoint>,
+) -> bool {
+    let axes = [
+        glm::vec3(1.0, 0.0, 0.0),
+        glm::vec3(0.0, 1.0, 0.0),
+        glm::vec3(0.0, 0.0, 1.0),
+    ];
+    while vertices.len() < 4 {
+        let directions: Vec<glm::Vec3> = match vertices.len() {
+            1 => axes.iter().flat_map(|a| [*a, -a]).collect(),
+            2 => {
+                let d = vertices[1].point - vertices[0].point;
+                axes.iter()
+                    .map(|a| glm::cross(&d, a))
+                    .filter(|n| glm::length2(n) > 1e-12)
+                    .flat_map(|n| [n, -n])
+                    .collect()
+            }
+            _ => {
+                let n = glm::cross(
+                    &(vertices[1].point - vertices[0].point),
+                    &(vertices[2].point - vertices[0].point),
+                );
+                vec![n, -n]
+            }
+        };
+        let added = directions.iter().any(|dir| {
+            let w = support(support_a, support_b, dir);
+            if affine_distance(vertices, &w.point) > 1e-6 {
+                vertices.push(w);
+                true
+            } else {
+                false
+            }
+        });
+        if !added {
+            return false;
+        }
+    }
+    true
+}
+
+// distanța de la p la dreapta/planul prin vârfurile date
+fn affine_distance(vertices: &[SupportPoint], p: &glm::Vec3) -> f32 {
+    let a = vertices[0].point;
+    match vertices.len() {
+        1 => glm::distance(&a, p),
+        2 => {
+            let d = vertices[1].point - a;
+            let len = glm::length(&d);
+            if len < 1e-12 {
+                return glm::distance(&a, p);
+            }
+            glm::length(&glm::cross(&(p - a), &d)) / len
+        }
+        _ => {
+            let n = glm::cross(&(vertices[1].point - a), &(vertices[2].point - a));
+            let len = glm::length(&n);
+            if len < 1e-12 {
+                return glm::distance(&a, p);
+            }
+            (glm::dot(&n, &(p - a)) / len).abs()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cutie aliniată cu axele
+    fn cuboid(center: glm::Vec3, half: glm::Vec3) -> impl Fn(&glm::Vec3) -> glm::Vec3 {
+        move |d: &glm::Vec3| {
+            center
+                + glm::vec3(
+                    half.x.copysign(d.x),
+                    half.y.copysign(d.y),
+                    half.z.copysign(d.z),
+                )
+        }
+    }
+
+    fn ball(center: glm::Vec3, radius: f32) -> impl Fn(&glm::Vec3) -> glm::Vec3 {
+        move |d: &glm::Vec3| {
+            let len = glm::length(d);
+            if len < 1e-12 {
+                center
+            } else {
+                center + d * (radius / len)
+            }
+        }
+    }
+
+    fn near(a: &glm::Vec3, b: &glm::Vec3, tolerance: f32) -> bool {
+        glm::distance(a, b) < tolerance
+    }
+
+    #[test]
+    fn distance_between_separated_shapes() {
+        let one = glm::vec3(1.0, 1.0, 1.0);
+        let a = cuboid(glm::Vec3::zeros(), one);
+        let b = cuboid(glm::vec3(3.0, 0.5, 0.0), one);
+        let result = gjk_distance(&a, &b, glm::vec3(-1.0, 0.0, 0.0));
+        assert!(!result.intersects());
+        assert!((result.distance - 1.0).abs() < 1e-5);
+        assert!((result.point_a.x - 1.0).abs() < 1e-5);
+        assert!((result.point_b.x - 2.0).abs() < 1e-5);
+
+        // colț față în față cu colț, pe diagonală
+        let c = cuboid(glm::vec3(3.0, 3.0, 3.0), one);
+        let result = gjk_distance(&a, &c, glm::vec3(0.0, 0.0, 1.0));
+        assert!((result.distance - 3.0_f32.sqrt()).abs() < 1e-4);
+        assert!(near(&result.point_a, &one, 1e-4));
+        assert!(near(&result.point_b, &(one * 2.0), 1e-4));
+
+        let s = ball(glm::vec3(0.0, 4.0, 0.0), 0.5);
+        let result = gjk_distance(&a, &s, glm::Vec3::zeros());
+        assert!((result.distance - 2.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn overlapping_boxes_give_the_smallest_push() {
+        let one = glm::vec3(1.0, 1.0, 1.0);
+        let a = cuboid(glm::Vec3::zeros(), one);
+        let b = cuboid(glm::vec3(1.5, 0.2, -0.1), one);
+        let result = gjk_distance(&a, &b, glm::vec3(-1.5, -0.2, 0.1));
+        assert!(result.intersects());
+        let penetration = epa(&a, &b, &result.simplex).unwrap();
+        assert!(near(&penetration.normal, &glm::vec3(1.0, 0.0, 0.0), 1e-4));
+        assert!((penetration.depth - 0.5).abs() < 1e-4);
+        // punctele sunt pe suprafața fiecărei forme
+        assert!((penetration.point_a.x - 1.0).abs() < 1e-4);
+        assert!((penetration.point_b.x - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn overlapping_balls_converge() {
+        let a = ball(glm::Vec3::zeros(), 1.0);
+        let b = ball(glm::vec3(0.0, 1.2, 0.9), 1.0);
+        let result = gjk_distance(&a, &b, glm::vec3(0.0, -1.2, -0.9));
+        assert!(result.intersects());
+        let penetration = epa(&a, &b, &result.simplex).unwrap();
+        // centrele sunt la 1.5, deci adâncimea e 0.5 pe direcția dintre ele
+        assert!(near(&penetration.normal, &glm::vec3(0.0, 0.8, 0.6), 1e-2));
+        assert!((penetration.depth - 0.5).abs() < 1e-2);
+    }
+
+    #[test]
+    fn epa_completes_a_degenerate_simplex() {
+        // forme identice: GJK se oprește imediat, cu un singur punct în simplex
+        let half = glm::vec3(1.0, 2.0, 3.0);
+        let a = cuboid(glm::Vec3::zeros(), half);
+        let b = cuboid(glm::Vec3::zeros(), half);
+        let result = gjk_distance(&a, &b, glm::Vec3::zeros());
+        assert!(result.intersects());
+        let penetration = epa(&a, &b, &result.simplex).unwrap();
+        // ieșirea cea mai scurtă e pe axa cea mai subțire
+        assert!((penetration.depth - 2.0).abs() < 1e-4);
+        assert!(penetration.normal.x.abs() > 0.999);
+    }
+}
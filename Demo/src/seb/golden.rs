@@ -0,0 +1,521 @@
+use crate::seb::gui::panel::Panel;
+use crate::seb::gui::window::{GuiBuilder, GuiRenderer};
+use crate::seb::model::{Light, Model};
+use crate::seb::planet::Planet;
+use crate::seb::preprocessor::{self, Preprocessor};
+use crate::seb::primitives::{
+    Line, LineRenderer, Rectangle, RectangleRenderer, Sphere, SphereRenderer, Vector,
+    VectorRenderer,
+};
+use crate::seb::seb::ShaderError;
+use crate::seb::skybox::Skybox;
+use crate::seb::uniforms::{self, CameraBlock, UniformBuffer};
+use crate::seb::window::Window;
+use nalgebra_glm as glm;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/*
+Teste de regresie pe imagini: fiecare scenă e desenată o singură dată, cu o cameră fixă,
+într-o fereastră headless, iar pixelii citiți înapoi sunt comparați cu un PNG de referință
+din `dir`. O referință lipsă pică testul; referințele se scriu doar cu GOLDEN_UPDATE=1.
+La eșec se scriu în `dir/failed` imaginea obținută și una cu diferențele în roșu.
+*/
+pub struct Scene {
+    pub name: &'static str,
+    // erorile (shadere, fișiere lipsă) pică testul
+    pub draw: fn(&mut Window) -> Result<(), String>,
+}
+
+pub const SCENES: &[Scene] = &[
+    Scene {
+        name: "primitives",
+        draw: primitives,
+    },
+    Scene {
+        name: "gltf_shadow",
+        draw: gltf_shadow,
+    },
+    Scene {
+        name: "planet",
+        draw: planet,
+    },
+    Scene {
+        name: "gui",
+        draw: gui,
+    },
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    // diferența de culoare (ΔE în CIELAB) peste care un pixel e greșit; ~2.3 abia se vede
+    pub max_delta_e: f32,
+    // ce parte din pixeli poate fi greșită (antialiasing, rasterizatoare diferite)
+    pub max_bad_fraction: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            max_delta_e: 5.0,
+            max_bad_fraction: 0.002,
+        }
+    }
+}
+
+// RGBA8, rândurile de sus în jos
+#[derive(Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn from_window(window: &Window) -> Self {
+        let (width, height) = window.render_size();
+        Self {
+            width: width as u32,
+            height: height as u32,
+            pixels: window.read_pixels(),
+        }
+    }
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+        // paleta și adâncimile sub 8 biți devin RGB(A) pe 8 biți
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or(0)];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let data = &buffer[..info.buffer_size()];
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => data.to_vec(),
+            png::ColorType::Rgb => data
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => data
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(format!("{}: paletă neexpandată", path));
+            }
+        };
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if let Some(parent) = Path::new(path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", path, e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("{}: {}", path, e))?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+pub struct Comparison {
+    pub bad_pixels: usize,
+    pub max_delta_e: f32,
+    // imaginea obținută în gri, cu pixelii greșiți în roșu cu atât mai aprins cu cât diferă mai mult
+    pub diff: Image,
+}
+
+impl Comparison {
+    pub fn bad_fraction(&self) -> f32 {
+        let total = (self.diff.width * self.diff.height).max(1);
+        self.bad_pixels as f32 / total as f32
+    }
+    pub fn passed(&self, tolerance: &Tolerance) -> bool {
+        self.bad_fraction() <= tolerance.max_bad_fraction
+    }
+}
+
+pub fn compare(
+    reference: &Image,
+    actual: &Image,
+    tolerance: &Tolerance,
+) -> Result<Comparison, String> {
+    if reference.width != actual.width || reference.height != actual.height {
+        return Err(format!(
+            "dimensiuni diferite: referința e {}x{}, imaginea {}x{}",
+            reference.width, reference.height, actual.width, actual.height
+        ));
+    }
+    let mut bad_pixels = 0;
+    let mut max_delta_e = 0.0f32;
+    let mut diff = Vec::with_capacity(actual.pixels.len());
+    for (a, b) in reference
+        .pixels
+        .chunks_exact(4)
+        .zip(actual.pixels.chunks_exact(4))
+    {
+        let delta = delta_e(a, b);
+        max_delta_e = max_delta_e.max(delta);
+        if delta > tolerance.max_delta_e {
+            bad_pixels += 1;
+            let red = (128.0 + delta * 4.0).min(255.0) as u8;
+            diff.extend_from_slice(&[red, 0, 0, 255]);
+        } else {
+            let gray = ((b[0] as u32 + b[1] as u32 + b[2] as u32) / 12) as u8;
+            diff.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+    Ok(Comparison {
+        bad_pixels,
+        max_delta_e,
+        diff: Image {
+            width: actual.width,
+            height: actual.height,
+            pixels: diff,
+        },
+    })
+}
+
+/*
+ΔE76: distanța în CIELAB, unde diferențele egale se văd cam la fel de tare, spre deosebire
+de RGB unde ochiul e mult mai sensibil la verde decât la albastru. Alpha e ignorat.
+*/
+fn delta_e(a: &[u8], b: &[u8]) -> f32 {
+    let a = lab(a);
+    let b = lab(b);
+    glm::distance(&a, &b)
+}
+
+fn lab(pixel: &[u8]) -> glm::Vec3 {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(pixel[0]), linear(pixel[1]), linear(pixel[2]));
+    // sRGB -> XYZ, raportat la punctul alb D65
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let f = |t: f32| {
+        if t > 0.008856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    glm::vec3(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+// desenează toate scenele și întoarce câte au picat
+pub fn run(window: &mut Window, scenes: &[Scene], dir: &str, tolerance: &Tolerance) -> usize {
+    let update = std::env::var("GOLDEN_UPDATE").is_ok_and(|v| v == "1");
+    let mut failed = 0;
+    for scene in scenes {
+        window.set_color(0.0, 0.0, 0.0, 1.0);
+        if let Err(e) = (scene.draw)(window) {
+            failed += 1;
+            println!("{}: EȘUAT, {}", scene.name, e);
+            continue;
+        }
+        unsafe {
+            gl::Finish();
+        }
+        let actual = Image::from_window(window);
+        window.swap_buffers();
+
+        let reference_path = format!("{}/{}.png", dir, scene.name);
+        if update {
+            match actual.save(&reference_path) {
+                Ok(()) => println!("{}: referință nouă în {}", scene.name, reference_path),
+                Err(e) => {
+                    println!("{}: {}", scene.name, e);
+                    failed += 1;
+                }
+            }
+            continue;
+        }
+        if !Path::new(&reference_path).exists() {
+            failed += 1;
+            println!(
+                "{}: EȘUAT, lipsește referința {} (rulează cu GOLDEN_UPDATE=1)",
+                scene.name, reference_path
+            );
+            continue;
+        }
+
+        let result = Image::load(&reference_path)
+            .and_then(|reference| compare(&reference, &actual, tolerance));
+        match result {
+            Ok(comparison) if comparison.passed(tolerance) => {
+                println!(
+                    "{}: ok ({} pixeli diferiți, ΔE maxim {:.1})",
+                    scene.name, comparison.bad_pixels, comparison.max_delta_e
+                );
+            }
+            Ok(comparison) => {
+                failed += 1;
+                println!(
+                    "{}: EȘUAT, {} pixeli ({:.2}%) diferă cu peste ΔE {}, maxim {:.1}",
+                    scene.name,
+                    comparison.bad_pixels,
+                    comparison.bad_fraction() * 100.0,
+                    tolerance.max_delta_e,
+                    comparison.max_delta_e
+                );
+                let actual_path = format!("{}/failed/{}.png", dir, scene.name);
+                let diff_path = format!("{}/failed/{}.diff.png", dir, scene.name);
+                for (image, path) in [(&actual, &actual_path), (&comparison.diff, &diff_path)] {
+                    if let Err(e) = image.save(path) {
+                        println!("{}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{}: EȘUAT, {}", scene.name, e);
+            }
+        }
+    }
+    failed
+}
+
+// camera fixă a scenelor: perspectiva din Player, privind din `eye` spre `target`
+fn camera(window: &Window, eye: glm::Vec3, target: glm::Vec3) -> (glm::Mat4, glm::Mat4) {
+    let (w, h) = (window.width as f32, window.height as f32);
+    let projection = glm::perspective_fov(70f32.to_radians(), w, h, 0.01, 100.0);
+    let view = glm::look_at(&eye, &target, &glm::vec3(0.0, 1.0, 0.0));
+    (projection, view)
+}
+
+fn skybox() -> Result<Skybox, ShaderError> {
+    Skybox::new([
+        "./assets/spaceskybox/right.png",
+        "./assets/spaceskybox/left.png",
+        "./assets/spaceskybox/top.png",
+        "./assets/spaceskybox/bottom.png",
+        "./assets/spaceskybox/front.png",
+        "./assets/spaceskybox/back.png",
+    ])
+}
+
+// câte una din fiecare primitivă din demo2
+fn primitives(window: &mut Window) -> Result<(), String> {
+    let (projection, view) = camera(window, glm::vec3(0.5, 1.5, -6.0), glm::vec3(0.5, 1.0, 0.0));
+
+    let mut line = Line::from(glm::vec3(0.0, 0.0, 0.0), glm::vec3(1.0, 1.0, 1.0));
+    line.color = glm::vec4(1.0, 0.0, 0.0, 1.0);
+    let mut vector = Vector::from(glm::vec3(1.0, 1.0, 0.5), glm::vec3(2.0, 1.5, 0.5));
+    vector.color = glm::vec4(0.0, 0.5, 0.5, 1.0);
+    let mut rectangle = Rectangle::new();
+    rectangle.position = glm::vec3(-1.0, 1.0, 1.0);
+    rectangle.scale = glm::vec3(1.5, 0.5, 0.5);
+    rectangle.color = glm::vec4(0.0, 0.0, 1.0, 1.0);
+    rectangle.y_angle = 60.0;
+    rectangle.z_angle = 20.0;
+    let mut sphere = Sphere::new();
+    sphere.position = glm::vec3(0.5, -1.0, 0.3);
+    sphere.color = glm::vec4(1.0, 1.0, 0.0, 1.0);
+
+    LineRenderer::new()?.draw(projection, view, &mut [line]);
+    VectorRenderer::new()?.draw(projection, view, &mut [vector]);
+    RectangleRenderer::new()?.draw(projection, view, &mut [rectangle]);
+    SphereRenderer::new()?.draw(projection, view, &mut [sphere]);
+    skybox()?.draw(projection, view);
+    Ok(())
+}
+
+// casa din demo4 cu lumina și umbra într-o poziție fixă
+fn gltf_shadow(window: &mut Window) -> Result<(), String> {
+    let eye = glm::vec3(3.0, 2.0, 3.0);
+    let (projection, view) = camera(window, eye, glm::vec3(0.0, 0.0, 0.0));
+
+    let mut shaders = Preprocessor::new(preprocessor::ASSETS);
+    let mut model = Model::new("./assets/model/casa.glb");
+    model.init(&mut shaders)?;
+    let mut light = Light::new();
+    light.init_shadow(2048, 2048)?;
+    light.add_light(glm::vec3(2.0, 1.0, 1.0), glm::vec3(0.0, 0.0, 0.0));
+
+    light.bind_shadow();
+    model.draw_for_shadow(&light);
+    light.unbind_shadow();
+
+    let mut camera = UniformBuffer::new(uniforms::CAMERA_BINDING);
+    camera.update(&CameraBlock::new(&projection, &view, &eye));
+
+    window.set_color(0.0, 0.0, 0.0, 1.0);
+    model.draw(&light);
+    Ok(())
+}
+
+fn planet(window: &mut Window) -> Result<(), String> {
+    let eye = glm::vec3(0.0, 8.0, -25.0);
+    let (projection, view) = camera(window, eye, glm::vec3(0.0, 0.0, 0.0));
+
+    let mut planet = Planet::new();
+    planet.from_map("./assets/planet/worldgen1.jpg", 1.0, 10.0)?;
+    planet.generate_terrain();
+    planet.load_texture("./assets/planet/worldgen1.jpg")?;
+    planet.init()?;
+    planet.draw(projection, view, eye);
+    skybox()?.draw(projection, view);
+    Ok(())
+}
+
+// ferestre imbricate, cerc și chenar, ca în demo6
+fn gui(window: &mut Window) -> Result<(), String> {
+    let mut renderer = GuiRenderer::new("./assets/Roboto-VariableFont_wdth,wght.ttf", 20f32)?;
+    let mut builder = GuiBuilder::new(
+        glm::vec2(0.0, 0.0),
+        glm::vec2(window.width as f32, window.height as f32),
+    );
+
+    let mut win = Panel::new();
+    win.position = glm::vec2(40.0, 30.0);
+    win.size = glm::vec2(400.0, 300.0);
+    win.color = glm::vec4(1.0, 0.5, 0.0, 1.0);
+    win.border_thickness = 5f32;
+    win.border_color = glm::vec4(1.0, 1.0, 1.0, 1.0);
+
+    builder.add_window(win, |b| {
+        let mut circle = Panel::new();
+        circle.color = glm::vec4(1.0, 0.5, 0.9, 1.0);
+        circle.position = glm::vec2(10.0, 10.0);
+        circle.size = glm::vec2(40.0, 30.0);
+        circle.draw_as_circle = true;
+        circle.border_thickness = 1f32;
+        circle.z_index = 0.1;
+        circle.border_color = glm::vec4(0.0, 1.0, 1.0, 1.0);
+        b.push_panel(circle);
+
+        let mut inner = Panel::new();
+        inner.position = glm::vec2(50.0, 60.0);
+        inner.size = glm::vec2(100.0, 80.0);
+        inner.color = glm::vec4(0.2, 0.5, 0.0, 1.0);
+        inner.border_thickness = 1f32;
+        inner.border_color = glm::vec4(0.0, 0.0, 1.0, 1.0);
+        inner.z_index = 0.1;
+        b.add_window(inner, |_| {});
+    });
+
+    renderer.draw(&mut builder, window.width, window.height);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> Image {
+        Image {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        }
+    }
+
+    fn set_pixel(image: &mut Image, x: u32, y: u32, color: [u8; 4]) {
+        let i = ((y * image.width + x) * 4) as usize;
+        image.pixels[i..i + 4].copy_from_slice(&color);
+    }
+
+    #[test]
+    fn identical_images_pass() {
+        let image = solid(8, 8, [90, 140, 200, 255]);
+        let comparison = compare(&image, &image.clone(), &Tolerance::default()).unwrap();
+        assert_eq!(comparison.bad_pixels, 0);
+        assert_eq!(comparison.max_delta_e, 0.0);
+        assert!(comparison.passed(&Tolerance::default()));
+    }
+
+    #[test]
+    fn one_bad_pixel_fails_past_the_allowed_fraction() {
+        let reference = solid(10, 10, [128, 128, 128, 255]);
+        let mut actual = reference.clone();
+        set_pixel(&mut actual, 3, 7, [255, 0, 0, 255]);
+        // un pixel din 100
+        let loose = Tolerance {
+            max_delta_e: 5.0,
+            max_bad_fraction: 0.01,
+        };
+        let strict = Tolerance {
+            max_bad_fraction: 0.005,
+            ..loose
+        };
+        let comparison = compare(&reference, &actual, &strict).unwrap();
+        assert_eq!(comparison.bad_pixels, 1);
+        assert!(comparison.max_delta_e > strict.max_delta_e);
+        assert!(!comparison.passed(&strict));
+        assert!(comparison.passed(&loose));
+    }
+
+    #[test]
+    fn differences_under_max_delta_e_are_ignored() {
+        let reference = solid(4, 4, [128, 128, 128, 255]);
+        let mut actual = reference.clone();
+        set_pixel(&mut actual, 0, 0, [129, 128, 128, 255]);
+        let tolerance = Tolerance {
+            max_delta_e: 5.0,
+            max_bad_fraction: 0.0,
+        };
+        let comparison = compare(&reference, &actual, &tolerance).unwrap();
+        assert_eq!(comparison.bad_pixels, 0);
+        assert!(comparison.max_delta_e > 0.0);
+        assert!(comparison.passed(&tolerance));
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() {
+        let reference = solid(8, 8, [0, 0, 0, 255]);
+        let actual = solid(8, 6, [0, 0, 0, 255]);
+        assert!(compare(&reference, &actual, &Tolerance::default()).is_err());
+    }
+
+    #[test]
+    fn diff_is_red_exactly_where_pixels_differ() {
+        let reference = solid(6, 5, [40, 180, 60, 255]);
+        let mut actual = reference.clone();
+        let changed = [(0, 0), (5, 2), (2, 4)];
+        for &(x, y) in &changed {
+            set_pixel(&mut actual, x, y, [250, 250, 250, 255]);
+        }
+        let comparison = compare(&reference, &actual, &Tolerance::default()).unwrap();
+        assert_eq!(comparison.bad_pixels, changed.len());
+        for y in 0..5 {
+            for x in 0..6 {
+                let i = ((y * 6 + x) * 4) as usize;
+                let p = &comparison.diff.pixels[i..i + 4];
+                if changed.contains(&(x, y)) {
+                    assert!(
+                        p[0] >= 128 && p[1] == 0 && p[2] == 0,
+                        "{:?} la {},{}",
+                        p,
+                        x,
+                        y
+                    );
+                } else {
+                    assert!(p[0] == p[1] && p[1] == p[2], "{:?} la {},{}", p, x, y);
+                }
+            }
+        }
+    }
+}
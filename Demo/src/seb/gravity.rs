@@ -0,0 +1,304 @@
+use crate::seb::planet::Planet;
+use nalgebra_glm as glm;
+
+// cum scade atracția unui corp ceresc cu distanța
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Falloff {
+    // aceeași accelerație oriunde în `range`
+    Uniform,
+    // newtonian, scade cu pătratul distanței față de suprafață
+    InverseSquare,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum GravityField {
+    // aceeași direcție peste tot, ca pe o hartă plată
+    Directional(glm::Vec3),
+    // spre `center`; `strength` e accelerația la distanța `radius` (suprafața), `range` e până unde trage
+    Point {
+        center: glm::Vec3,
+        radius: f32,
+        strength: f32,
+        falloff: Falloff,
+        range: f32,
+    },
+}
+
+impl GravityField {
+    pub fn directional(acceleration: glm::Vec3) -> Self {
+        GravityField::Directional(acceleration)
+    }
+    pub fn point(center: glm::Vec3, radius: f32, strength: f32, falloff: Falloff) -> Self {
+        GravityField::Point {
+            center,
+            radius,
+            strength,
+            falloff,
+            range: f32::INFINITY,
+        }
+    }
+    // suprafața de bază a planetei e la `scale` de centru, munții sunt peste ea
+    pub fn from_planet(planet: &Planet, strength: f32, falloff: Falloff) -> Self {
+        Self::point(planet.position, planet.scale, strength, falloff)
+    }
+    pub fn acceleration(&self, position: &glm::Vec3) -> glm::Vec3 {
+        match *self {
+            GravityField::Directional(acceleration) => acceleration,
+            GravityField::Point {
+                center,
+                radius,
+                strength,
+                falloff,
+                range,
+            } => {
+                let offset = center - position;
+                let distance = glm::length(&offset);
+                if distance < 1e-6 || distance > range {
+                    return glm::Vec3::zeros();
+                }
+                let magnitude = match falloff {
+                    Falloff::Uniform => strength,
+                    // în interior scade liniar spre centru, ca la o sferă plină
+                    Falloff::InverseSquare if distance < radius => strength * distance / radius,
+                    Falloff::InverseSquare => strength * (radius / distance).powi(2),
+                };
+                offset * (magnitude / distance)
+            }
+        }
+    }
+    // sus e opus gravitației; unde nu trage nimic rămâne Y
+    pub fn up(&self, position: &glm::Vec3) -> glm::Vec3 {
+        up_from(&self.acceleration(position))
+    }
+}
+
+pub fn up_from(gravity: &glm::Vec3) -> glm::Vec3 {
+    if glm::length2(gravity) > 1e-12 {
+        -glm::normalize(gravity)
+    } else {
+        glm::vec3(0.0, 1.0, 0.0)
+    }
+}
+
+/*
+Rotația cea mai mică ce duce axa Y a lui `rotation` pe `up`, păstrând încotro privea obiectul.
+Pentru NPC-uri și modele care trebuie să stea drepte pe planetă.
+*/
+pub fn align_up(rotation: &glm::Mat3, up: &glm::Vec3) -> glm::Mat3 {
+    let current = rotation * glm::vec3(0.0, 1.0, 0.0);
+    let axis = glm::cross(&current, up);
+    let sin = glm::length(&axis);
+    let cos = glm::dot(&current, up);
+    if sin < 1e-6 {
+        if cos > 0.0 {
+            return *rotation;
+        }
+        // întors cu capul în jos, orice axă orizontală e bună
+        let flip = glm::mat4_to_mat3(&glm::rotation(
+            glm::pi(),
+            &(rotation * glm::vec3(1.0, 0.0, 0.0)),
+        ));
+        return flip * rotation;
+    }
+    glm::mat4_to_mat3(&glm::rotation(sin.atan2(cos), &(axis / sin))) * rotation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::{Collider, euler_to_matrix};
+    use crate::seb::heightfield::{Heightfield, Layout};
+    use crate::seb::physics::{PhysicsWorld, RigidBody};
+    use crate::seb::primitives::{Rectangle, Sphere};
+
+    fn near(a: &glm::Vec3, b: &glm::Vec3) -> bool {
+        glm::distance(a, b) < 1e-4
+    }
+
+    #[test]
+    fn inverse_square_outside_the_planet() {
+        let center = glm::vec3(1.0, 2.0, 3.0);
+        let field = GravityField::point(center, 2.0, 10.0, Falloff::InverseSquare);
+        let at_surface = field.acceleration(&(center + glm::vec3(2.0, 0.0, 0.0)));
+        assert!(near(&at_surface, &glm::vec3(-10.0, 0.0, 0.0)));
+        let twice = field.acceleration(&(center + glm::vec3(0.0, 0.0, -4.0)));
+        assert!(near(&twice, &glm::vec3(0.0, 0.0, 2.5)));
+
+        let uniform = GravityField::point(center, 2.0, 10.0, Falloff::Uniform);
+        let far = uniform.acceleration(&(center + glm::vec3(0.0, 40.0, 0.0)));
+        assert!(near(&far, &glm::vec3(0.0, -10.0, 0.0)));
+    }
+
+    #[test]
+    fn inverse_square_falls_off_linearly_inside() {
+        let field = GravityField::point(glm::Vec3::zeros(), 2.0, 10.0, Falloff::InverseSquare);
+        for (distance, expected) in [(2.0, 10.0), (1.5, 7.5), (1.0, 5.0), (0.5, 2.5)] {
+            let p = glm::normalize(&glm::vec3(1.0, -2.0, 0.5)) * distance;
+            let a = field.acceleration(&p);
+            assert!((glm::length(&a) - expected).abs() < 1e-4, "la {}", distance);
+            // tot spre centru
+            assert!(near(&glm::normalize(&a), &-glm::normalize(&p)));
+        }
+        // în centru nu are direcție
+        assert_eq!(field.acceleration(&glm::Vec3::zeros()), glm::Vec3::zeros());
+    }
+
+    #[test]
+    fn range_cuts_the_field_off() {
+        let GravityField::Point {
+            center,
+            radius,
+            strength,
+            falloff,
+            ..
+        } = GravityField::point(glm::Vec3::zeros(), 1.0, 4.0, Falloff::Uniform)
+        else {
+            unreachable!()
+        };
+        let field = GravityField::Point {
+            center,
+            radius,
+            strength,
+            falloff,
+            range: 5.0,
+        };
+        assert!(near(
+            &field.acceleration(&glm::vec3(4.9, 0.0, 0.0)),
+            &glm::vec3(-4.0, 0.0, 0.0)
+        ));
+        let outside = glm::vec3(5.1, 0.0, 0.0);
+        assert_eq!(field.acceleration(&outside), glm::Vec3::zeros());
+        assert_eq!(field.up(&outside), glm::vec3(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn up_is_opposite_to_gravity() {
+        assert_eq!(up_from(&glm::Vec3::zeros()), glm::vec3(0.0, 1.0, 0.0));
+        assert!(near(
+            &up_from(&glm::vec3(3.0, 0.0, -4.0)),
+            &glm::vec3(-0.6, 0.0, 0.8)
+        ));
+        let field = GravityField::point(glm::Vec3::zeros(), 1.0, 9.81, Falloff::InverseSquare);
+        assert!(near(
+            &field.up(&glm::vec3(0.0, 0.0, -3.0)),
+            &glm::vec3(0.0, 0.0, -1.0)
+        ));
+    }
+
+    // rotație proprie: coloane ortonormate, determinant 1
+    fn is_rotation(m: &glm::Mat3) -> bool {
+        (m.transpose() * m - glm::Mat3::identity()).norm() < 1e-4
+            && (m.determinant() - 1.0).abs() < 1e-4
+    }
+
+    #[test]
+    fn align_up_turns_the_y_axis() {
+        let rotation = euler_to_matrix(20.0, 35.0, -10.0);
+        let up = glm::normalize(&glm::vec3(1.0, 1.0, -2.0));
+        let aligned = align_up(&rotation, &up);
+        assert!(is_rotation(&aligned));
+        assert!(near(&(aligned * glm::vec3(0.0, 1.0, 0.0)), &up));
+
+        // deja drept: nu se schimbă nimic
+        let current = rotation * glm::vec3(0.0, 1.0, 0.0);
+        assert!((align_up(&rotation, &current) - rotation).norm() < 1e-5);
+    }
+
+    #[test]
+    fn align_up_handles_upside_down() {
+        for rotation in [glm::Mat3::identity(), euler_to_matrix(0.0, 70.0, 0.0)] {
+            let down = -(rotation * glm::vec3(0.0, 1.0, 0.0));
+            let aligned = align_up(&rotation, &down);
+            assert!(is_rotation(&aligned));
+            assert!(near(&(aligned * glm::vec3(0.0, 1.0, 0.0)), &down));
+            // întoarcerea e în jurul axei X a obiectului, care rămâne pe loc
+            assert!(near(
+                &(aligned * glm::vec3(1.0, 0.0, 0.0)),
+                &(rotation * glm::vec3(1.0, 0.0, 0.0))
+            ));
+        }
+    }
+
+    // o planetă netedă de rază 10.5, doar cu gravitația ei
+    fn planet_world() -> PhysicsWorld {
+        let center = glm::vec3(0.0, -30.0, 0.0);
+        let heights = (0..17).map(|_| vec![0.05; 32]).collect();
+        let mut world = PhysicsWorld::new();
+        world.gravity = glm::Vec3::zeros();
+        world.fields.push(GravityField::point(
+            center,
+            10.0,
+            9.81,
+            Falloff::InverseSquare,
+        ));
+        world.terrains.push(Heightfield::new(
+            heights,
+            Layout::Spherical {
+                position: center,
+                rotation: glm::Mat3::identity(),
+                scale: 10.0,
+            },
+        ));
+        world
+    }
+
+    #[test]
+    fn bodies_come_to_rest_on_a_planet() {
+        let mut world = planet_world();
+        let center = glm::vec3(0.0, -30.0, 0.0);
+        // din părți diferite ale planetei, inclusiv de dedesubt
+        let directions = [
+            glm::vec3(0.3, 1.0, 0.2),
+            glm::vec3(-1.0, -0.4, 0.6),
+            glm::vec3(0.2, -1.0, -0.5),
+        ];
+        let mut bodies = Vec::new();
+        for (i, dir) in directions.iter().enumerate() {
+            let position = center + glm::normalize(dir) * 13.0;
+            let collider = if i == 1 {
+                let mut r = Rectangle::new();
+                r.position = position;
+                r.scale = glm::vec3(0.4, 0.4, 0.4);
+                Collider::Rectangle(r)
+            } else {
+                let mut s = Sphere::new();
+                s.position = position;
+                s.scale = 0.5;
+                Collider::Sphere(s)
+            };
+            let mut body = RigidBody::new(collider, 1.0);
+            body.restitution = 0.0;
+            body.friction = 0.8;
+            // nu avem rezistență la rostogolire: fără amortizare o sferă s-ar legăna mult
+            // timp în groapa unei fațete
+            body.linear_damping = 0.5;
+            body.angular_damping = 1.0;
+            bodies.push(world.add_body(body));
+        }
+
+        for _ in 0..900 {
+            world.step(1.0 / 60.0);
+        }
+        let terrain = world.terrains[0].clone();
+        for &handle in &bodies {
+            let body = world.body(handle);
+            assert!(
+                body.is_sleeping() || glm::length(&body.velocity) < 0.05,
+                "{} încă se mișcă cu {:?}",
+                handle,
+                body.velocity
+            );
+            // stă pe teren, nu a trecut prin el și nici nu plutește
+            let (height, _) = terrain.ground(&body.position()).unwrap();
+            assert!(
+                height > 0.3 && height < 0.75,
+                "{} e la {} deasupra terenului",
+                handle,
+                height
+            );
+            // și stă deasupra, adică în afara centrului, în partea în care a căzut
+            let up = glm::normalize(&(body.position() - center));
+            assert!(glm::dot(&up, &glm::normalize(&directions[handle])) > 0.9);
+        }
+    }
+}
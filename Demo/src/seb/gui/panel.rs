@@ -1,5 +1,6 @@
 use crate::seb::gui::gui::Clip;
-use crate::seb::seb::{ToCStr, create_shader_from};
+use crate::seb::seb::{ShaderError, create_shader_from};
+use crate::seb::uniforms::UniformCache;
 use nalgebra_glm as glm;
 
 #[derive(Clone, Copy, Debug)]
@@ -101,6 +102,7 @@ struct PanelInstance {
 
 pub struct PanelRenderer {
     shader: u32,
+    uniforms: UniformCache,
     vao: u32,
     ebo: u32,
     indices_len: usize,
@@ -132,7 +134,7 @@ impl Drop for PanelRenderer {
 }
 
 impl PanelRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut ebo = 0;
         let mut indices: [u32; 6] = [0, 1, 2, 0, 2, 3];
@@ -300,9 +302,10 @@ impl PanelRenderer {
                 }
             }
         "#;
-        shader = create_shader_from(vshader, fshader);
-        Self {
+        shader = create_shader_from(vshader, fshader)?.id;
+        Ok(Self {
             shader: shader,
+            uniforms: UniformCache::new(),
             vao: vao,
             ebo: ebo,
             indices_len: indices.len(),
@@ -310,7 +313,7 @@ impl PanelRenderer {
             ivbo: ivbo,
             window_width: 0.0,
             window_height: 0.0,
-        }
+        })
     }
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.window_width = width as f32;
@@ -350,7 +353,7 @@ impl PanelRenderer {
             gl::UseProgram(self.shader);
 
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "ortho".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "ortho"),
                 1,
                 gl::FALSE,
                 ortho.as_ptr(),
@@ -1,7 +1,8 @@
 use std::ffi::c_float;
 
 use crate::seb::gui::gui::Clip;
-use crate::seb::seb::{ToCStr, create_shader_from};
+use crate::seb::seb::{ShaderError, create_shader_from};
+use crate::seb::uniforms::UniformCache;
 use gl::types::*;
 use nalgebra_glm as glm;
 use rusttype::Font;
@@ -224,6 +225,7 @@ pub struct TextBoxRenderer {
     window_width: u32,
     window_height: u32,
     shader: u32,
+    uniforms: UniformCache,
     vao: u32,
     vbo: u32,
     ebo: u32,
@@ -256,7 +258,7 @@ impl Drop for TextBoxRenderer {
     }
 }
 impl TextBoxRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut vbo = 0;
         let mut ebo = 0;
@@ -405,19 +407,20 @@ impl TextBoxRenderer {
                 fragColor = vec4(color.x, color.y, color.z, alpha * color.w);
             }
         "#;
-        shader_id = create_shader_from(vshader, fshader);
+        shader_id = create_shader_from(vshader, fshader)?.id;
 
-        Self {
+        Ok(Self {
             window_width: 0,
             window_height: 0,
             shader: shader_id,
+            uniforms: UniformCache::new(),
             vao,
             vbo,
             ebo,
             tvbo,
             ivbo,
             indices_len: indices.len(),
-        }
+        })
     }
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.window_width = width;
@@ -476,23 +479,23 @@ impl TextBoxRenderer {
             gl::UseProgram(self.shader);
 
             gl::Uniform1f(
-                gl::GetUniformLocation(self.shader, "atlas_width".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "atlas_width"),
                 font.width as f32,
             );
             gl::Uniform1f(
-                gl::GetUniformLocation(self.shader, "atlas_height".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "atlas_height"),
                 font.height as f32,
             );
 
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, font.texture_id);
             gl::Uniform1i(
-                gl::GetUniformLocation(self.shader, "texture0".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "texture0"),
                 0,
             );
 
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "ortho".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "ortho"),
                 1,
                 gl::FALSE,
                 ortho.as_ptr(),
@@ -6,6 +6,7 @@ use crate::seb::{
         panel::{self, Panel, PanelRenderer},
         text::{self, TextBoxD, TextBoxRenderer, TextFont},
     },
+    seb::ShaderError,
     window::Window,
 };
 use gl::NONE;
@@ -20,14 +21,14 @@ pub struct GuiRenderer {
     tr: TextBoxRenderer,
 }
 impl GuiRenderer {
-    pub fn new(font_path: &str, scale: f32) -> Self {
+    pub fn new(font_path: &str, scale: f32) -> Result<Self, ShaderError> {
         let mut font = TextFont::new(font_path, scale);
         font.init_chars_texture(false);
-        Self {
+        Ok(Self {
             font,
-            pr: PanelRenderer::new(),
-            tr: TextBoxRenderer::new(),
-        }
+            pr: PanelRenderer::new()?,
+            tr: TextBoxRenderer::new()?,
+        })
     }
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.pr.set_size(width, height);
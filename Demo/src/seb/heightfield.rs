@@ -0,0 +1,847 @@
+use crate::seb::broadphase::Aabb;
+use crate::seb::bvh::closest_segment_triangle;
+use crate::seb::collision::{
+    Collider, ContactManifold, ContactPoint, Feature, Obb, euler_to_matrix,
+};
+use crate::seb::model::Map;
+use crate::seb::planet::Planet;
+use crate::seb::raycast::closest_point_triangle;
+use nalgebra_glm as glm;
+
+// câte puncte pe marginea fiecărui capac al unui cilindru ating terenul
+const CYLINDER_RIM_POINTS: usize = 12;
+
+#[derive(Clone, Debug)]
+pub enum Layout {
+    // grid pe planul xz cu pasul `scale`, înălțimile scalate la fel, ca în Map::draw
+    Flat {
+        scale: f32,
+    },
+    // grid pe sferă (theta pe lățime, phi pe înălțime) cu raza 1 + h, ca în Planet::draw
+    Spherical {
+        position: glm::Vec3,
+        rotation: glm::Mat3,
+        scale: f32,
+    },
+}
+
+/*
+Teren din gridul de înălțimi al unei hărți sau planete, împărțit în triunghiuri exact ca la
+generate_terrain. Triunghiul k din celula (x, z) are indexul (z * coloane + x) * 2 + k.
+*/
+#[derive(Clone, Debug)]
+pub struct Heightfield {
+    pub heights: Vec<Vec<f32>>,
+    pub layout: Layout,
+    range: (f32, f32),
+}
+
+impl Heightfield {
+    pub fn new(heights: Vec<Vec<f32>>, layout: Layout) -> Self {
+        let range = heights
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+        Self {
+            heights,
+            layout,
+            range,
+        }
+    }
+    pub fn from_map(map: &Map) -> Self {
+        let width = if map.height() > 0 { map.width() } else { 0 };
+        Self::new(
+            grid(width, map.height(), |x, z| map.get(x, z)),
+            Layout::Flat { scale: map.scale() },
+        )
+    }
+    pub fn from_planet(planet: &Planet) -> Self {
+        let width = if planet.height() > 0 {
+            planet.width()
+        } else {
+            0
+        };
+        Self::new(
+            grid(width, planet.height(), |x, z| planet.get(x, z)),
+            Layout::Spherical {
+                position: planet.position,
+                rotation: euler_to_matrix(planet.x_angle, planet.y_angle, planet.z_angle),
+                scale: planet.scale,
+            },
+        )
+    }
+    /*
+    Planeta e copiată la construcție; după ce se mișcă sau se rotește (ex: SolarSystem::apply)
+    terenul trebuie adus din nou peste ea, altfel coliziunile rămân în locul vechi.
+    */
+    pub fn follow(&mut self, planet: &Planet) {
+        if let Layout::Spherical {
+            position,
+            rotation,
+            scale,
+        } = &mut self.layout
+        {
+            *position = planet.position;
+            *rotation = euler_to_matrix(planet.x_angle, planet.y_angle, planet.z_angle);
+            *scale = planet.scale;
+        }
+    }
+    pub fn width(&self) -> usize {
+        self.heights.first().map_or(0, |row| row.len())
+    }
+    pub fn height(&self) -> usize {
+        self.heights.len()
+    }
+    fn get(&self, x: usize, z: usize) -> f32 {
+        self.heights[z][x % self.width()]
+    }
+    // câte celule are gridul pe lățime (planeta se închide peste ultima coloană)
+    fn columns(&self) -> usize {
+        match self.layout {
+            Layout::Flat { .. } => self.width() - 1,
+            Layout::Spherical { .. } => self.width(),
+        }
+    }
+
+    pub fn vertex(&self, x: usize, z: usize) -> glm::Vec3 {
+        let h = self.get(x, z);
+        match &self.layout {
+            Layout::Flat { scale } => glm::vec3(x as f32, h, z as f32) * *scale,
+            Layout::Spherical {
+                position,
+                rotation,
+                scale,
+            } => {
+                let pi = std::f32::consts::PI;
+                let theta = x as f32 / self.width() as f32 * 2.0 * pi;
+                let phi = z as f32 / (self.height() - 1) as f32 * pi;
+                let local = glm::vec3(phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin())
+                    * (1.0 + h);
+                position + rotation * local * *scale
+            }
+        }
+    }
+    pub fn triangle(&self, index: usize) -> [glm::Vec3; 3] {
+        let cell = index / 2;
+        let (x, z) = (cell % self.columns(), cell / self.columns());
+        let [tl, tr, bl, br] = [
+            self.vertex(x, z),
+            self.vertex(x + 1, z),
+            self.vertex(x, z + 1),
+            self.vertex(x + 1, z + 1),
+        ];
+        match (&self.layout, index % 2) {
+            (Layout::Flat { .. }, 0) => [tl, bl, tr],
+            (Layout::Flat { .. }, _) => [tr, bl, br],
+            (Layout::Spherical { .. }, 0) => [tl, tr, bl],
+            (Layout::Spherical { .. }, _) => [tr, br, bl],
+        }
+    }
+    // normala triunghiului, întoarsă în sus (sau în afară pe planetă)
+    fn up_normal(&self, triangle: &[glm::Vec3; 3]) -> glm::Vec3 {
+        let [a, b, c] = triangle;
+        let n = glm::normalize(&glm::cross(&(b - a), &(c - a)));
+        let up = match &self.layout {
+            Layout::Flat { .. } => glm::vec3(0.0, 1.0, 0.0),
+            Layout::Spherical { position, .. } => a - position,
+        };
+        if glm::dot(&n, &up) < 0.0 { -n } else { n }
+    }
+
+    // coordonatele continue în grid ale unui punct din lume
+    fn grid_position(&self, p: &glm::Vec3) -> (f32, f32) {
+        match &self.layout {
+            Layout::Flat { scale } => (p.x / scale, p.z / scale),
+            Layout::Spherical {
+                position, rotation, ..
+            } => {
+                let local = glm::transpose(rotation) * (p - position);
+                let r = glm::length(&local).max(1e-9);
+                let pi = std::f32::consts::PI;
+                let phi = (local.y / r).clamp(-1.0, 1.0).acos();
+                let theta = local.z.atan2(local.x).rem_euclid(2.0 * pi);
+                (
+                    theta / (2.0 * pi) * self.width() as f32,
+                    phi / pi * (self.height() - 1) as f32,
+                )
+            }
+        }
+    }
+
+    // triunghiurile (indexurile) ale căror celule pot atinge cutia
+    pub fn triangles_near(&self, aabb: &Aabb) -> Vec<usize> {
+        let mut out = Vec::new();
+        if self.height() < 2 || self.width() < 2 {
+            return out;
+        }
+        let (low, high) = self.range;
+        let rows = self.height() - 1;
+        let columns = self.columns();
+        let (x_cells, z_cells): (Vec<usize>, Vec<usize>) = match &self.layout {
+            Layout::Flat { scale } => {
+                if aabb.min.y > high * scale || aabb.max.y < low * scale {
+                    return out;
+                }
+                let range = |a: f32, b: f32, n: usize| -> Vec<usize> {
+                    if b < 0.0 || a > n as f32 {
+                        return Vec::new();
+                    }
+                    let a = a.floor().clamp(0.0, (n - 1) as f32) as usize;
+                    let b = b.floor().clamp(0.0, (n - 1) as f32) as usize;
+                    (a..=b).collect()
+                };
+                (
+                    range(aabb.min.x / scale, aabb.max.x / scale, columns),
+                    range(aabb.min.z / scale, aabb.max.z / scale, rows),
+                )
+            }
+            Layout::Spherical {
+                position, scale, ..
+            } => {
+                // lucrăm cu sfera care cuprinde cutia, în unități ale planetei
+                let center = (aabb.center() - position) / *scale;
+                let radius = glm::length(&aabb.half_extents()) / scale;
+                let distance = glm::length(&center);
+                if distance - radius > 1.0 + high || distance + radius < 1.0 + low {
+                    return out;
+                }
+                let (gx, gz) = self.grid_position(&aabb.center());
+                let pi = std::f32::consts::PI;
+                let base = (1.0 + low).max(1e-3);
+                let d_phi = pi / rows as f32;
+                let d_theta = 2.0 * pi / columns as f32;
+                // unghiul sub care se vede sfera din centrul planetei
+                let angle = if radius >= distance * 0.99 {
+                    pi
+                } else {
+                    (radius / distance).asin().max(radius / base)
+                };
+                let z_lo = (gz - angle / d_phi - 1.0)
+                    .floor()
+                    .clamp(0.0, (rows - 1) as f32);
+                let z_hi = (gz + angle / d_phi + 1.0)
+                    .floor()
+                    .clamp(0.0, (rows - 1) as f32);
+                let sin_min = ((z_lo as usize)..=(z_hi as usize + 1))
+                    .map(|z| (z as f32 * d_phi).sin())
+                    .fold(f32::MAX, f32::min);
+                let spread = angle / (sin_min.max(1e-6) * d_theta) + 1.0;
+                let x_cells = if 2.0 * spread >= columns as f32 {
+                    (0..columns).collect()
+                } else {
+                    ((gx - spread).floor() as i64..=(gx + spread).floor() as i64)
+                        .map(|x| x.rem_euclid(columns as i64) as usize)
+                        .collect()
+                };
+                (x_cells, (z_lo as usize..=z_hi as usize).collect())
+            }
+        };
+        for &z in &z_cells {
+            for &x in &x_cells {
+                let cell = z * columns + x;
+                for index in [cell * 2, cell * 2 + 1] {
+                    let [a, b, c] = self.triangle(index);
+                    let bounds = Aabb::new(
+                        glm::min2(&glm::min2(&a, &b), &c),
+                        glm::max2(&glm::max2(&a, &b), &c),
+                    );
+                    if bounds.overlaps(aabb) {
+                        out.push(index);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /*
+    Înălțimea punctului deasupra terenului (negativă dacă e dedesubt), măsurată pe verticală
+    la hartă și pe rază la planetă, plus normala triunghiului de sub el.
+    Înlocuiește Map::get_y și Planet::get_position_on_sphere.
+    */
+    pub fn ground(&self, p: &glm::Vec3) -> Option<(f32, glm::Vec3)> {
+        if self.height() < 2 || self.width() < 2 {
+            return None;
+        }
+        let (gx, gz) = self.grid_position(p);
+        let columns = self.columns();
+        let rows = self.height() - 1;
+        if gz < 0.0 || gz > rows as f32 || gx < 0.0 || gx > columns as f32 {
+            return None;
+        }
+        let x = (gx.floor() as usize).min(columns - 1);
+        let z = (gz.floor() as usize).min(rows - 1);
+        let (tx, tz) = (gx - x as f32, gz - z as f32);
+        // ambele împărțiri au primul triunghi pe jumătatea cu tx + tz <= 1
+        let k = if tx + tz <= 1.0 { 0 } else { 1 };
+        let triangle = self.triangle((z * columns + x) * 2 + k);
+        let normal = self.up_normal(&triangle);
+        let along = match &self.layout {
+            Layout::Flat { .. } => glm::vec3(0.0, 1.0, 0.0),
+            Layout::Spherical { position, .. } => glm::normalize(&(p - position)),
+        };
+        let speed = glm::dot(&normal, &along);
+        if speed.abs() < 1e-6 {
+            return None;
+        }
+        Some((glm::dot(&normal, &(p - triangle[0])) / speed, normal))
+    }
+
+    /*
+    Contactele unui collider cu terenul, câte un manifold pe triunghi atins.
+    Ca la collide_manifold, colliderul e primul: normala merge din el spre teren.
+    */
+    pub fn collide(&self, collider: &Collider) -> Vec<ContactManifold> {
+        let mut manifolds: Vec<ContactManifold> = Vec::new();
+        let triangles = self.triangles_near(&collider.aabb());
+        if triangles.is_empty() {
+            return manifolds;
+        }
+        let mut push = |normal: glm::Vec3, point: ContactPoint| {
+            // la muchii și vârfuri mai multe triunghiuri dau același contact
+            let duplicate = manifolds.iter().any(|m| {
+                glm::dot(&m.normal, &normal) > 0.999
+                    && m.points
+                        .iter()
+                        .any(|p| glm::distance2(&p.position, &point.position) < 1e-8)
+            });
+            if duplicate {
+                return;
+            }
+            match manifolds
+                .iter_mut()
+                .find(|m| m.points[0].feature_b == point.feature_b)
+            {
+                Some(m) => m.points.push(point),
+                None => manifolds.push(ContactManifold {
+                    normal,
+                    points: vec![point],
+                }),
+            }
+        };
+
+        match collider {
+            Collider::Sphere(s) => {
+                for &index in &triangles {
+                    let triangle = self.triangle(index);
+                    let closest = closest_point_triangle(&s.position, &triangle);
+                    if let Some((normal, depth)) =
+                        self.penetration(&s.position, &closest, s.scale, &triangle)
+                    {
+                        push(
+                            normal,
+                            ContactPoint {
+                                position: closest + normal * (depth * 0.5),
+                                depth,
+                                feature_a: Feature::Surface,
+                                feature_b: Feature::Face(index as u32),
+                            },
+                        );
+                    }
+                }
+            }
+            Collider::Capsule(c) => {
+                let (a, b) = c.segment();
+                for &index in &triangles {
+                    let triangle = self.triangle(index);
+                    // capetele separat, ca o capsulă culcată să stea pe două puncte
+                    let mut found = false;
+                    for (k, end) in [a, b].iter().enumerate() {
+                        let closest = closest_point_triangle(end, &triangle);
+                        if let Some((normal, depth)) =
+                            self.penetration(end, &closest, c.radius, &triangle)
+                        {
+                            found = true;
+                            push(
+                                normal,
+                                ContactPoint {
+                                    position: closest + normal * (depth * 0.5),
+                                    depth,
+                                    feature_a: Feature::Vertex(k as u32),
+                                    feature_b: Feature::Face(index as u32),
+                                },
+                            );
+                        }
+                    }
+                    if found {
+                        continue;
+                    }
+                    let (on_segment, closest) = closest_segment_triangle(&a, &b, &triangle);
+                    if let Some((normal, depth)) =
+                        self.penetration(&on_segment, &closest, c.radius, &triangle)
+                    {
+                        push(
+                            normal,
+                            ContactPoint {
+                                position: closest + normal * (depth * 0.5),
+                                depth,
+                                feature_a: Feature::Edge(0),
+                                feature_b: Feature::Face(index as u32),
+                            },
+                        );
+                    }
+                }
+            }
+            Collider::Rectangle(r) => {
+                let obb = Obb::new(r);
+                // colțurile cutiei sub teren
+                for corner in 0..8 {
+                    if let Some((normal, point)) = self.point_contact(&obb.corner(corner), corner) {
+                        push(normal, point);
+                    }
+                }
+                // vârfurile terenului care intră în cutie (vârfuri de munte sub o cutie mare)
+                for &index in &triangles {
+                    for (k, v) in self.triangle(index).iter().enumerate() {
+                        let local = glm::vec3(
+                            glm::dot(&(v - obb.center), &obb.axes[0]),
+                            glm::dot(&(v - obb.center), &obb.axes[1]),
+                            glm::dot(&(v - obb.center), &obb.axes[2]),
+                        );
+                        let Some((axis, depth)) = (0..3)
+                            .map(|i| (i, obb.half[i] - local[i].abs()))
+                            .min_by(|a, b| a.1.total_cmp(&b.1))
+                        else {
+                            continue;
+                        };
+                        if depth <= 0.0 {
+                            continue;
+                        }
+                        let normal = obb.axes[axis] * local[axis].signum();
+                        // ieșim doar pe fețele de dedesubt, altfel e un colț deja tratat sus
+                        if glm::dot(&normal, &self.along(v)) > -0.5 {
+                            continue;
+                        }
+                        push(
+                            normal,
+                            ContactPoint {
+                                position: v + normal * (depth * 0.5),
+                                depth,
+                                feature_a: Feature::Face(
+                                    axis as u32 * 2 + u32::from(local[axis] < 0.0),
+                                ),
+                                feature_b: Feature::Vertex((index * 3 + k) as u32),
+                            },
+                        );
+                    }
+                }
+            }
+            /*
+            Forma convexă generală: vârfurile înfășurătorii, iar la cilindru puncte pe marginile
+            capacelor, ca la colțurile cutiei. Un vârf de munte care intră printr-o față fără
+            să treacă de vreun vârf al formei nu e văzut.
+            */
+            Collider::Cylinder(_) | Collider::ConvexHull(_) => {
+                for (k, p) in convex_points(collider).iter().enumerate() {
+                    if let Some((normal, point)) = self.point_contact(p, k as u32) {
+                        push(normal, point);
+                    }
+                }
+            }
+            // planul e infinit și static, nu are contacte cu terenul
+            Collider::Plane(_) => {}
+        }
+        manifolds
+    }
+    // un punct al formei sub teren: normala spre teren și contactul cu triunghiul de sub el
+    fn point_contact(&self, p: &glm::Vec3, vertex: u32) -> Option<(glm::Vec3, ContactPoint)> {
+        let (height, normal) = self.ground(p)?;
+        let depth = -height * glm::dot(&normal, &self.along(p));
+        if depth <= 0.0 {
+            return None;
+        }
+        let (gx, gz) = self.grid_position(p);
+        let x = (gx.floor().max(0.0) as usize).min(self.columns() - 1);
+        let z = (gz.floor().max(0.0) as usize).min(self.height() - 2);
+        let k = usize::from(gx - x as f32 + gz - z as f32 > 1.0);
+        Some((
+            -normal,
+            ContactPoint {
+                position: p + normal * (depth * 0.5),
+                depth,
+                feature_a: Feature::Vertex(vertex),
+                feature_b: Feature::Face(((z * self.columns() + x) * 2 + k) as u32),
+            },
+        ))
+    }
+    fn along(&self, p: &glm::Vec3) -> glm::Vec3 {
+        match &self.layout {
+            Layout::Flat { .. } => glm::vec3(0.0, 1.0, 0.0),
+            Layout::Spherical { position, .. } => glm::normalize(&(p - position)),
+        }
+    }
+    // normala (din formă spre teren) și adâncimea unei sfere de rază `radius` centrate în `center`
+    fn penetration(
+        &self,
+        center: &glm::Vec3,
+        closest: &glm::Vec3,
+        radius: f32,
+        triangle: &[glm::Vec3; 3],
+    ) -> Option<(glm::Vec3, f32)> {
+        let delta = closest - center;
+        let distance = glm::length(&delta);
+        let up = self.up_normal(triangle);
+        // centrul sub teren: împingem în sus, nu prin triunghi
+        let below = glm::dot(&up, &(center - triangle[0])) < 0.0
+            && self.ground(center).is_some_and(|(height, _)| height < 0.0);
+        let depth = if below {
+            radius + distance
+        } else {
+            radius - distance
+        };
+        if depth <= 0.0 {
+            return None;
+        }
+        let normal = if below || distance < 1e-6 {
+            -up
+        } else {
+            delta / distance
+        };
+        Some((normal, depth))
+    }
+}
+
+// punctele cu care cilindrul și înfășurătoarea ating terenul, în lume
+fn convex_points(collider: &Collider) -> Vec<glm::Vec3> {
+    match collider {
+        Collider::Cylinder(c) => {
+            let rotation = collider.rotation();
+            let (axis, side, front) = (
+                rotation * glm::vec3(0.0, 1.0, 0.0),
+                rotation * glm::vec3(1.0, 0.0, 0.0),
+                rotation * glm::vec3(0.0, 0.0, 1.0),
+            );
+            let mut points = Vec::with_capacity(2 * CYLINDER_RIM_POINTS);
+            for end in [-1.0, 1.0] {
+                let center = c.position + axis * (c.half_height * end);
+                for i in 0..CYLINDER_RIM_POINTS {
+                    let angle = i as f32 / CYLINDER_RIM_POINTS as f32 * std::f32::consts::TAU;
+                    points.push(center + (side * angle.cos() + front * angle.sin()) * c.radius);
+                }
+            }
+            points
+        }
+        Collider::ConvexHull(h) => h.world_points(),
+        _ => Vec::new(),
+    }
+}
+
+fn grid(width: usize, height: usize, get: impl Fn(usize, usize) -> Option<f32>) -> Vec<Vec<f32>> {
+    if width == 0 {
+        return Vec::new();
+    }
+    (0..height)
+        .map(|z| (0..width).map(|x| get(x, z).unwrap_or(0.0)).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::primitives::{Capsule, ConvexHull, Cylinder, Rectangle, Sphere};
+
+    // imagine PNG scrisă în temp, pentru Map::from_height_map și Planet::from_map
+    fn png(name: &str, width: u32, height: u32, color: png::ColorType, data: &[u8]) -> String {
+        let path =
+            std::env::temp_dir().join(format!("heightfield-{}-{}.png", std::process::id(), name));
+        let file = std::fs::File::create(&path).unwrap();
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    // hartă 8x8 care urcă pe x: înălțimea e x * 30 / 255
+    fn ramp_map() -> Map {
+        let data: Vec<u8> = (0..64).map(|i| (i % 8) as u8 * 30).collect();
+        let path = png("ramp", 8, 8, png::ColorType::Grayscale, &data);
+        let mut map = Map::new();
+        map.from_height_map(&path, 1.0).unwrap();
+        std::fs::remove_file(path).unwrap();
+        map
+    }
+
+    // planetă roșie peste tot, deci cu aceeași înălțime (0.05) în fiecare punct
+    fn smooth_planet() -> Planet {
+        let (width, height) = (64, 33);
+        let data = [255u8, 0, 0].repeat(width * height);
+        let path = png(
+            "planet",
+            width as u32,
+            height as u32,
+            png::ColorType::Rgb,
+            &data,
+        );
+        let mut planet = Planet::new();
+        planet.from_map(&path, 1.0, 10.0).unwrap();
+        std::fs::remove_file(path).unwrap();
+        planet.position = glm::vec3(3.0, -20.0, 5.0);
+        planet.y_angle = 30.0;
+        planet
+    }
+
+    // normala rampei, în sus
+    fn ramp_normal() -> glm::Vec3 {
+        glm::normalize(&glm::vec3(-30.0 / 255.0, 1.0, 0.0))
+    }
+
+    // cel mai adânc punct și normala manifoldului lui
+    fn deepest(manifolds: &[ContactManifold]) -> (f32, glm::Vec3) {
+        manifolds
+            .iter()
+            .flat_map(|m| m.points.iter().map(|p| (p.depth, m.normal)))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap()
+    }
+
+    /*
+    Cel mai adânc contact are normala terenului, spre el. Triunghiurile vecine pot da
+    contacte mai mici spre muchiile lor, dar tot în teren.
+    */
+    fn check_normals(manifolds: &[ContactManifold], up: &glm::Vec3, tolerance: f32) -> f32 {
+        let (depth, normal) = deepest(manifolds);
+        assert!(
+            glm::dot(&normal, &-up) > 1.0 - tolerance,
+            "normala {:?}, sus {:?}",
+            normal,
+            up
+        );
+        for m in manifolds {
+            assert!(glm::dot(&m.normal, up) < 0.0, "normala {:?}", m.normal);
+        }
+        depth
+    }
+
+    #[test]
+    fn flat_sphere_capsule_and_box_contacts() {
+        let map = ramp_map();
+        let terrain = Heightfield::from_map(&map);
+        let n = ramp_normal();
+        let (x, z) = (3.3, 4.6);
+        let ground = map.get_y(x, z);
+
+        // sfera cu centrul la 0.4 deasupra terenului, pe verticală
+        let mut sphere = Sphere::new();
+        sphere.position = glm::vec3(x, ground + 0.4, z);
+        sphere.scale = 0.5;
+        let manifolds = terrain.collide(&Collider::Sphere(sphere));
+        let depth = check_normals(&manifolds, &n, 1e-4);
+        assert!((depth - (0.5 - 0.4 * n.y)).abs() < 1e-4);
+
+        // capsula în picioare, cu capătul de jos la 0.2 deasupra terenului
+        let mut capsule = Capsule::new();
+        capsule.radius = 0.3;
+        capsule.half_height = 0.5;
+        capsule.position = glm::vec3(x, ground + 0.7, z);
+        let manifolds = terrain.collide(&Collider::Capsule(capsule));
+        let depth = check_normals(&manifolds, &n, 1e-4);
+        assert!((depth - (0.3 - 0.2 * n.y)).abs() < 1e-4);
+
+        // cutia afundată 0.1 în mijloc: fiecare colț de jos e sub teren cu cât spune get_y
+        let mut cube = Rectangle::new();
+        cube.position = glm::vec3(x, ground + 0.4, z);
+        cube.scale = glm::vec3(0.5, 0.5, 0.5);
+        let manifolds = terrain.collide(&Collider::Rectangle(cube));
+        check_normals(&manifolds, &n, 1e-4);
+        // vârfurile terenului care intră prin fața de jos au Feature::Face pe partea cutiei
+        let corners: Vec<(u32, f32)> = manifolds
+            .iter()
+            .flat_map(|m| &m.points)
+            .filter_map(|p| match p.feature_a {
+                Feature::Vertex(corner) => Some((corner, p.depth)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(corners.len(), 4);
+        for (corner, depth) in corners {
+            let p = Obb::new(&cube).corner(corner);
+            let expected = (map.get_y(p.x, p.z) - p.y) * n.y;
+            assert!((depth - expected).abs() < 1e-4, "colțul {}", corner);
+        }
+
+        // deasupra terenului nu e nimic
+        sphere.position.y += 1.0;
+        assert!(terrain.collide(&Collider::Sphere(sphere)).is_empty());
+    }
+
+    #[test]
+    fn spherical_sphere_capsule_and_box_contacts() {
+        let planet = smooth_planet();
+        let terrain = Heightfield::from_planet(&planet);
+        // raza suprafeței, după Planet: scale * (1 + h)
+        let surface = planet.scale * (1.0 + planet.get(0, 0).unwrap());
+        let rotation = euler_to_matrix(planet.x_angle, planet.y_angle, planet.z_angle);
+        // pe ecuatorul planetei rotite, între vârfurile gridului
+        let up = rotation * glm::normalize(&glm::vec3(1.0, 0.03, 0.02));
+        // fațetele sunt sub sferă cel mult la jumătatea diagonalei unei celule (pi / 32)
+        let sag = surface * (1.0 - (std::f32::consts::PI / 32.0 / 2f32.sqrt()).cos());
+
+        let mut sphere = Sphere::new();
+        sphere.position = planet.position + up * (surface + 0.4);
+        sphere.scale = 0.5;
+        let manifolds = terrain.collide(&Collider::Sphere(sphere));
+        let depth = check_normals(&manifolds, &up, 2e-3);
+        assert!(
+            depth <= 0.1 + 1e-3 && depth >= 0.1 - sag - 1e-3,
+            "{}",
+            depth
+        );
+
+        // capsula pe rază, cu capătul de jos la 0.2 deasupra suprafeței
+        let mut capsule = Capsule::new();
+        capsule.radius = 0.3;
+        capsule.half_height = 0.5;
+        capsule.position = planet.position + up * (surface + 0.7);
+        let stand = crate::seb::gravity::align_up(&glm::Mat3::identity(), &up);
+        let mut collider = Collider::Capsule(capsule);
+        collider.set_rotation(&stand);
+        let manifolds = terrain.collide(&collider);
+        let depth = check_normals(&manifolds, &up, 2e-3);
+        assert!(
+            depth <= 0.1 + 1e-3 && depth >= 0.1 - sag - 1e-3,
+            "{}",
+            depth
+        );
+
+        // cutia dreaptă pe suprafață, cu fața de jos la 0.1 sub ea
+        let mut cube = Rectangle::new();
+        cube.position = planet.position + up * (surface + 0.4);
+        cube.scale = glm::vec3(0.5, 0.5, 0.5);
+        let mut collider = Collider::Rectangle(cube);
+        collider.set_rotation(&stand);
+        let manifolds = terrain.collide(&collider);
+        check_normals(&manifolds, &up, 5e-3);
+        let Collider::Rectangle(cube) = collider else {
+            unreachable!()
+        };
+        // vârfurile terenului care intră prin fața de jos au Feature::Face pe partea cutiei
+        let corners: Vec<(u32, f32)> = manifolds
+            .iter()
+            .flat_map(|m| &m.points)
+            .filter_map(|p| match p.feature_a {
+                Feature::Vertex(corner) => Some((corner, p.depth)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(corners.len(), 4);
+        for (corner, depth) in corners {
+            let p = Obb::new(&cube).corner(corner);
+            // cât e colțul sub suprafața planetei, pe rază
+            let below = surface - glm::distance(&p, &planet.position);
+            assert!(
+                depth <= below + 1e-3 && depth >= below - sag - 1e-3,
+                "colțul {}: {} față de {}",
+                corner,
+                depth,
+                below
+            );
+        }
+    }
+
+    #[test]
+    fn cylinders_and_hulls_touch_the_terrain() {
+        let map = ramp_map();
+        let terrain = Heightfield::from_map(&map);
+        let n = ramp_normal();
+        let (x, z) = (3.5, 3.5);
+        let ground = map.get_y(x, z);
+
+        // cilindru culcat pe o parte, afundat 0.1
+        let mut cylinder = Cylinder::new();
+        cylinder.position = glm::vec3(x, ground + 0.4, z);
+        cylinder.radius = 0.5;
+        cylinder.x_angle = 90.0;
+        let collider = Collider::Cylinder(cylinder);
+        let manifolds = terrain.collide(&collider);
+        let depth = check_normals(&manifolds, &n, 1e-4);
+        assert!((depth - 0.1 * n.y).abs() < 1e-4);
+        let rim = convex_points(&collider);
+        let mut touching = Vec::new();
+        for point in manifolds.iter().flat_map(|m| &m.points) {
+            let Feature::Vertex(k) = point.feature_a else {
+                panic!("{:?}", point.feature_a);
+            };
+            let p = rim[k as usize];
+            let expected = (map.get_y(p.x, p.z) - p.y) * n.y;
+            assert!((point.depth - expected).abs() < 1e-4, "punctul {}", k);
+            touching.push(k as usize);
+        }
+        // punctul cel mai de jos al fiecărui capac e printre ele
+        for (c, cap) in rim.chunks(CYLINDER_RIM_POINTS).enumerate() {
+            let lowest = cap
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.y.total_cmp(&b.1.y))
+                .unwrap()
+                .0;
+            assert!(touching.contains(&(c * CYLINDER_RIM_POINTS + lowest)));
+        }
+
+        let hull = ConvexHull::from_points(&[
+            glm::vec3(-0.5, 0.0, -0.5),
+            glm::vec3(0.5, 0.0, -0.5),
+            glm::vec3(0.0, 0.0, 0.5),
+            glm::vec3(0.0, 1.0, 0.0),
+        ]);
+        let mut hull = hull;
+        // baza triunghiulară afundată 0.05 sub cel mai înalt colț al ei
+        let base_y = [(-0.5, -0.5), (0.5, -0.5), (0.0, 0.5)]
+            .iter()
+            .map(|(dx, dz)| map.get_y(x + dx, z + dz))
+            .fold(f32::MIN, f32::max);
+        hull.position += glm::vec3(x, base_y - 0.05, z);
+        let manifolds = terrain.collide(&Collider::ConvexHull(hull.clone()));
+        check_normals(&manifolds, &n, 1e-4);
+        for point in manifolds.iter().flat_map(|m| &m.points) {
+            let Feature::Vertex(k) = point.feature_a else {
+                panic!("{:?}", point.feature_a);
+            };
+            let p = hull.world_points()[k as usize];
+            let expected = (map.get_y(p.x, p.z) - p.y) * n.y;
+            assert!((point.depth - expected).abs() < 1e-4);
+        }
+        // vârful de sus nu e în contact
+        hull.position.y += 2.0;
+        assert!(terrain.collide(&Collider::ConvexHull(hull)).is_empty());
+    }
+
+    #[test]
+    fn follow_moves_the_terrain_with_the_planet() {
+        // sferă netedă de rază 1.1, construită în origine
+        let mut terrain = Heightfield::new(
+            vec![vec![0.1; 16]; 9],
+            Layout::Spherical {
+                position: glm::Vec3::zeros(),
+                rotation: glm::Mat3::identity(),
+                scale: 1.0,
+            },
+        );
+        let mut planet = Planet::new();
+        planet.position = glm::vec3(10.0, 0.0, 0.0);
+        planet.scale = 2.0;
+        planet.z_angle = 90.0;
+        let rotation = euler_to_matrix(planet.x_angle, planet.y_angle, planet.z_angle);
+        // deasupra ecuatorului planetei mutate și întoarse, chiar peste un vârf al gridului
+        let above = planet.position + rotation * glm::vec3(3.0, 0.0, 0.0);
+
+        let (before, _) = terrain.ground(&above).unwrap();
+        assert!(before > 5.0);
+
+        terrain.follow(&planet);
+        let pole = terrain.vertex(0, 0);
+        assert!(
+            glm::distance(
+                &pole,
+                &(planet.position + rotation * glm::vec3(0.0, 2.2, 0.0))
+            ) < 1e-4
+        );
+        let (height, normal) = terrain.ground(&above).unwrap();
+        assert!((height - 0.8).abs() < 1e-3, "înălțimea {}", height);
+        assert!(glm::dot(&normal, &(rotation * glm::vec3(1.0, 0.0, 0.0))) > 0.95);
+    }
+}
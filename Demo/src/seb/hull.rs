@@ -0,0 +1,289 @@
+use nalgebra_glm as glm;
+use std::collections::HashSet;
+
+struct Face {
+    vertices: [usize; 3],
+    normal: glm::Vec3,
+    offset: f32,
+    // punctele încă neprocesate care sunt în fața acestei fețe
+    outside: Vec<usize>,
+    alive: bool,
+}
+impl Face {
+    fn new(points: &[glm::Vec3], a: usize, b: usize, c: usize) -> Self {
+        let normal = glm::normalize(&glm::cross(
+            &(points[b] - points[a]),
+            &(points[c] - points[a]),
+        ));
+        Self {
+            vertices: [a, b, c],
+            normal,
+            offset: glm::dot(&normal, &points[a]),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+    fn distance(&self, p: &glm::Vec3) -> f32 {
+        glm::dot(&self.normal, p) - self.offset
+    }
+}
+
+/*
+Înfășurătoarea convexă 3D (quickhull).
+Întoarce doar vârfurile care sunt pe înfășurătoare, iar fețele (triunghiuri cu normala în afară)
+indexează în ele. Dacă punctele sunt coplanare întoarce punctele fără fețe.
+*/
+pub fn quickhull(input: &[glm::Vec3]) -> (Vec<glm::Vec3>, Vec<[u32; 3]>) {
+    if input.len() < 4 {
+        return (input.to_vec(), Vec::new());
+    }
+    let mut min = input[0];
+    let mut max = input[0];
+    for p in input {
+        min = glm::min2(&min, p);
+        max = glm::max2(&max, p);
+    }
+    let extent = max - min;
+    let eps = 1e-5 * extent.x.max(extent.y).max(extent.z).max(1e-3);
+
+    // tetraedrul de start: extremele pe axa cea mai lungă, apoi cele mai depărtate de linie și plan
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    let farthest = |score: &dyn Fn(&glm::Vec3) -> f32| -> usize {
+        (0..input.len())
+            .max_by(|&a, &b| score(&input[a]).total_cmp(&score(&input[b])))
+            .unwrap()
+    };
+    let i0 = farthest(&|p| -p[axis]);
+    let i1 = farthest(&|p| p[axis]);
+    let line = input[i1] - input[i0];
+    let i2 = farthest(&|p| glm::length2(&glm::cross(&line, &(p - input[i0]))));
+    let normal = glm::cross(&line, &(input[i2] - input[i0]));
+    if glm::length(&normal) < eps * eps {
+        return (input.to_vec(), Vec::new());
+    }
+    let normal = glm::normalize(&normal);
+    let i3 = farthest(&|p| glm::dot(&normal, &(p - input[i0])).abs());
+    if glm::dot(&normal, &(input[i3] - input[i0])).abs() < eps {
+        return (input.to_vec(), Vec::new());
+    }
+
+    let mut faces = Vec::new();
+    let center = (input[i0] + input[i1] + input[i2] + input[i3]) / 4.0;
+    for [a, b, c] in [[i0, i1, i2], [i0, i3, i1], [i1, i3, i2], [i2, i3, i0]] {
+        let mut face = Face::new(input, a, b, c);
+        if face.distance(&center) > 0.0 {
+            face = Face::new(input, a, c, b);
+        }
+        faces.push(face);
+    }
+    let tetra = [i0, i1, i2, i3];
+    let candidates: Vec<usize> = (0..input.len()).filter(|i| !tetra.contains(i)).collect();
+    assign_outside(&mut faces, 0, input, &candidates, eps);
+
+    while let Some(f) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        let apex = *faces[f]
+            .outside
+            .iter()
+            .max_by(|&&a, &&b| {
+                faces[f]
+                    .distance(&input[a])
+                    .total_cmp(&faces[f].distance(&input[b]))
+            })
+            .unwrap();
+
+        let visible: Vec<usize> = (0..faces.len())
+            .filter(|&i| faces[i].alive && faces[i].distance(&input[apex]) > eps)
+            .collect();
+        let mut edges = HashSet::new();
+        for &i in &visible {
+            let [a, b, c] = faces[i].vertices;
+            edges.extend([(a, b), (b, c), (c, a)]);
+        }
+
+        let mut orphans = Vec::new();
+        for &i in &visible {
+            faces[i].alive = false;
+            orphans.append(&mut faces[i].outside);
+        }
+        orphans.retain(|&p| p != apex);
+
+        // muchiile de orizont apar doar într-o față vizibilă
+        let first_new = faces.len();
+        for &i in &visible {
+            let [a, b, c] = faces[i].vertices;
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                if !edges.contains(&(v, u)) {
+                    faces.push(Face::new(input, u, v, apex));
+                }
+            }
+        }
+        assign_outside(&mut faces, first_new, input, &orphans, eps);
+    }
+
+    // păstrăm doar vârfurile folosite
+    let mut remap = vec![u32::MAX; input.len()];
+    let mut points = Vec::new();
+    let mut triangles = Vec::new();
+    for face in faces.iter().filter(|f| f.alive) {
+        let mut triangle = [0u32; 3];
+        for (k, &v) in face.vertices.iter().enumerate() {
+            if remap[v] == u32::MAX {
+                remap[v] = points.len() as u32;
+                points.push(input[v]);
+            }
+            triangle[k] = remap[v];
+        }
+        triangles.push(triangle);
+    }
+    (points, triangles)
+}
+
+// fiecare punct merge la fața (dintre cele noi) de care e cel mai departe, sau e aruncat dacă e înăuntru
+fn assign_outside(
+    faces: &mut [Face],
+    first: usize,
+    points: &[glm::Vec3],
+    candidates: &[usize],
+    eps: f32,
+) {
+    for &p in candidates {
+        let mut best = None;
+        let mut best_distance = eps;
+        for (i, face) in faces.iter().enumerate().skip(first) {
+            let d = face.distance(&points[p]);
+            if face.alive && d > best_distance {
+                best_distance = d;
+                best = Some(i);
+            }
+        }
+        if let Some(i) = best {
+            faces[i].outside.push(p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // generator simplu și determinist, ca testele să nu depindă de noroc
+    struct Lcg(u64);
+    impl Lcg {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+        fn vec3(&mut self, range: f32) -> glm::Vec3 {
+            (glm::vec3(self.next(), self.next(), self.next()) * 2.0).add_scalar(-1.0) * range
+        }
+    }
+
+    fn cube_corners() -> Vec<glm::Vec3> {
+        let mut corners = Vec::new();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    corners.push(glm::vec3(x, y, z));
+                }
+            }
+        }
+        corners
+    }
+
+    /*
+    Fiecare punct de intrare e în spatele tuturor fețelor, fiecare față e orientată în
+    afara centrului, iar fiecare muchie e folosită o dată în fiecare sens (suprafață închisă).
+    */
+    fn check_hull(input: &[glm::Vec3], points: &[glm::Vec3], faces: &[[u32; 3]]) {
+        assert!(faces.len() >= 4);
+        let center = points.iter().sum::<glm::Vec3>() / points.len() as f32;
+        let mut edges = HashSet::new();
+        for &[a, b, c] in faces {
+            let (a, b, c) = (a as usize, b as usize, c as usize);
+            let normal = glm::normalize(&glm::cross(
+                &(points[b] - points[a]),
+                &(points[c] - points[a]),
+            ));
+            assert!(glm::dot(&normal, &(points[a] - center)) > 0.0);
+            for p in input {
+                let d = glm::dot(&normal, &(p - points[a]));
+                assert!(d < 1e-4, "{:?} e cu {} în fața feței", p, d);
+            }
+            for edge in [(a, b), (b, c), (c, a)] {
+                assert!(edges.insert(edge), "muchia {:?} apare de două ori", edge);
+            }
+        }
+        for &(a, b) in &edges {
+            assert!(edges.contains(&(b, a)));
+        }
+        for p in points {
+            assert!(input.contains(p));
+        }
+    }
+
+    #[test]
+    fn random_points() {
+        let mut rng = Lcg(11);
+        for count in [4, 10, 100, 500] {
+            let input: Vec<glm::Vec3> = (0..count).map(|_| rng.vec3(5.0)).collect();
+            let (points, faces) = quickhull(&input);
+            check_hull(&input, &points, &faces);
+        }
+        // pe o sferă aproape toate punctele sunt vârfuri
+        let input: Vec<glm::Vec3> = (0..200)
+            .map(|_| glm::normalize(&rng.vec3(1.0)) * 3.0)
+            .collect();
+        let (points, faces) = quickhull(&input);
+        check_hull(&input, &points, &faces);
+        assert!(points.len() > 150);
+    }
+
+    #[test]
+    fn duplicate_points() {
+        let mut input = Vec::new();
+        for _ in 0..3 {
+            input.extend(cube_corners());
+        }
+        input.push(glm::Vec3::zeros());
+        input.push(glm::Vec3::zeros());
+        let (points, faces) = quickhull(&input);
+        check_hull(&input, &points, &faces);
+        assert_eq!(points.len(), 8);
+        assert_eq!(faces.len(), 12);
+    }
+
+    #[test]
+    fn coplanar_points() {
+        // toate în planul z = 0: nu există volum, deci nici fețe
+        let flat: Vec<glm::Vec3> = (0..5)
+            .flat_map(|x| (0..5).map(move |y| glm::vec3(x as f32, y as f32, 0.0)))
+            .collect();
+        let (points, faces) = quickhull(&flat);
+        assert_eq!(points.len(), flat.len());
+        assert!(faces.is_empty());
+
+        // puncte în plus pe fețele și muchiile unui cub nu devin vârfuri
+        let mut input = cube_corners();
+        for i in 0..5 {
+            let t = -1.0 + i as f32 * 0.5;
+            for j in 0..5 {
+                let s = -1.0 + j as f32 * 0.5;
+                input.push(glm::vec3(t, s, 1.0));
+                input.push(glm::vec3(t, -1.0, s));
+                input.push(glm::vec3(1.0, t, s));
+            }
+        }
+        let (points, faces) = quickhull(&input);
+        check_hull(&input, &points, &faces);
+        assert_eq!(points.len(), 8);
+    }
+}
@@ -0,0 +1,1143 @@
+use crate::seb::window::{MouseScroll, Window};
+use std::collections::HashMap;
+
+// aceleași legături ca în assets/input.cfg, pentru când fișierul lipsește
+const DEFAULT_CONFIG: &str = include_str!("../../assets/input.cfg");
+
+/*
+Toate tastele GLFW, cu numele din input.cfg. Key nu are from_i32, așa că și replay le
+caută aici când citește o înregistrare; o tastă nouă în glfw se adaugă doar în lista asta.
+*/
+pub const KEYS: &[(&str, glfw::Key)] = &[
+    ("A", glfw::Key::A),
+    ("B", glfw::Key::B),
+    ("C", glfw::Key::C),
+    ("D", glfw::Key::D),
+    ("E", glfw::Key::E),
+    ("F", glfw::Key::F),
+    ("G", glfw::Key::G),
+    ("H", glfw::Key::H),
+    ("I", glfw::Key::I),
+    ("J", glfw::Key::J),
+    ("K", glfw::Key::K),
+    ("L", glfw::Key::L),
+    ("M", glfw::Key::M),
+    ("N", glfw::Key::N),
+    ("O", glfw::Key::O),
+    ("P", glfw::Key::P),
+    ("Q", glfw::Key::Q),
+    ("R", glfw::Key::R),
+    ("S", glfw::Key::S),
+    ("T", glfw::Key::T),
+    ("U", glfw::Key::U),
+    ("V", glfw::Key::V),
+    ("W", glfw::Key::W),
+    ("X", glfw::Key::X),
+    ("Y", glfw::Key::Y),
+    ("Z", glfw::Key::Z),
+    ("0", glfw::Key::Num0),
+    ("1", glfw::Key::Num1),
+    ("2", glfw::Key::Num2),
+    ("3", glfw::Key::Num3),
+    ("4", glfw::Key::Num4),
+    ("5", glfw::Key::Num5),
+    ("6", glfw::Key::Num6),
+    ("7", glfw::Key::Num7),
+    ("8", glfw::Key::Num8),
+    ("9", glfw::Key::Num9),
+    ("F1", glfw::Key::F1),
+    ("F2", glfw::Key::F2),
+    ("F3", glfw::Key::F3),
+    ("F4", glfw::Key::F4),
+    ("F5", glfw::Key::F5),
+    ("F6", glfw::Key::F6),
+    ("F7", glfw::Key::F7),
+    ("F8", glfw::Key::F8),
+    ("F9", glfw::Key::F9),
+    ("F10", glfw::Key::F10),
+    ("F11", glfw::Key::F11),
+    ("F12", glfw::Key::F12),
+    ("Space", glfw::Key::Space),
+    ("Tab", glfw::Key::Tab),
+    ("Escape", glfw::Key::Escape),
+    ("Enter", glfw::Key::Enter),
+    ("Backspace", glfw::Key::Backspace),
+    ("Insert", glfw::Key::Insert),
+    ("Delete", glfw::Key::Delete),
+    ("Home", glfw::Key::Home),
+    ("End", glfw::Key::End),
+    ("PageUp", glfw::Key::PageUp),
+    ("PageDown", glfw::Key::PageDown),
+    ("Up", glfw::Key::Up),
+    ("Down", glfw::Key::Down),
+    ("Left", glfw::Key::Left),
+    ("Right", glfw::Key::Right),
+    ("CapsLock", glfw::Key::CapsLock),
+    ("LeftShift", glfw::Key::LeftShift),
+    ("RightShift", glfw::Key::RightShift),
+    ("LeftControl", glfw::Key::LeftControl),
+    ("RightControl", glfw::Key::RightControl),
+    ("LeftAlt", glfw::Key::LeftAlt),
+    ("RightAlt", glfw::Key::RightAlt),
+    ("LeftSuper", glfw::Key::LeftSuper),
+    ("RightSuper", glfw::Key::RightSuper),
+    ("Minus", glfw::Key::Minus),
+    ("Equal", glfw::Key::Equal),
+    ("Comma", glfw::Key::Comma),
+    ("Period", glfw::Key::Period),
+    ("Slash", glfw::Key::Slash),
+    ("Backslash", glfw::Key::Backslash),
+    ("Semicolon", glfw::Key::Semicolon),
+    ("Apostrophe", glfw::Key::Apostrophe),
+    ("LeftBracket", glfw::Key::LeftBracket),
+    ("RightBracket", glfw::Key::RightBracket),
+    ("GraveAccent", glfw::Key::GraveAccent),
+    ("World1", glfw::Key::World1),
+    ("World2", glfw::Key::World2),
+    ("ScrollLock", glfw::Key::ScrollLock),
+    ("NumLock", glfw::Key::NumLock),
+    ("PrintScreen", glfw::Key::PrintScreen),
+    ("Pause", glfw::Key::Pause),
+    ("F13", glfw::Key::F13),
+    ("F14", glfw::Key::F14),
+    ("F15", glfw::Key::F15),
+    ("F16", glfw::Key::F16),
+    ("F17", glfw::Key::F17),
+    ("F18", glfw::Key::F18),
+    ("F19", glfw::Key::F19),
+    ("F20", glfw::Key::F20),
+    ("F21", glfw::Key::F21),
+    ("F22", glfw::Key::F22),
+    ("F23", glfw::Key::F23),
+    ("F24", glfw::Key::F24),
+    ("F25", glfw::Key::F25),
+    ("Kp0", glfw::Key::Kp0),
+    ("Kp1", glfw::Key::Kp1),
+    ("Kp2", glfw::Key::Kp2),
+    ("Kp3", glfw::Key::Kp3),
+    ("Kp4", glfw::Key::Kp4),
+    ("Kp5", glfw::Key::Kp5),
+    ("Kp6", glfw::Key::Kp6),
+    ("Kp7", glfw::Key::Kp7),
+    ("Kp8", glfw::Key::Kp8),
+    ("Kp9", glfw::Key::Kp9),
+    ("KpDecimal", glfw::Key::KpDecimal),
+    ("KpDivide", glfw::Key::KpDivide),
+    ("KpMultiply", glfw::Key::KpMultiply),
+    ("KpSubtract", glfw::Key::KpSubtract),
+    ("KpAdd", glfw::Key::KpAdd),
+    ("KpEnter", glfw::Key::KpEnter),
+    ("KpEqual", glfw::Key::KpEqual),
+    ("Menu", glfw::Key::Menu),
+    ("Unknown", glfw::Key::Unknown),
+];
+
+const MOUSE_NAMES: &[(&str, glfw::MouseButton)] = &[
+    ("MouseLeft", glfw::MouseButton::Button1),
+    ("MouseRight", glfw::MouseButton::Button2),
+    ("MouseMiddle", glfw::MouseButton::Button3),
+    ("Mouse4", glfw::MouseButton::Button4),
+    ("Mouse5", glfw::MouseButton::Button5),
+    ("Mouse6", glfw::MouseButton::Button6),
+    ("Mouse7", glfw::MouseButton::Button7),
+    ("Mouse8", glfw::MouseButton::Button8),
+];
+
+const SCROLL_NAMES: &[(&str, MouseScroll)] = &[
+    ("ScrollUp", MouseScroll::Up),
+    ("ScrollDown", MouseScroll::Down),
+    ("ScrollLeft", MouseScroll::Left),
+    ("ScrollRight", MouseScroll::Right),
+];
+
+const MODIFIER_NAMES: &[(&str, glfw::Modifiers)] = &[
+    ("Ctrl", glfw::Modifiers::Control),
+    ("Shift", glfw::Modifiers::Shift),
+    ("Alt", glfw::Modifiers::Alt),
+    ("Super", glfw::Modifiers::Super),
+];
+
+// tastele care sunt ele însele modificatori, cu modificatorul pe care îl țin apăsat
+const MODIFIER_KEYS: &[(glfw::Key, glfw::Modifiers)] = &[
+    (glfw::Key::LeftShift, glfw::Modifiers::Shift),
+    (glfw::Key::RightShift, glfw::Modifiers::Shift),
+    (glfw::Key::LeftControl, glfw::Modifiers::Control),
+    (glfw::Key::RightControl, glfw::Modifiers::Control),
+    (glfw::Key::LeftAlt, glfw::Modifiers::Alt),
+    (glfw::Key::RightAlt, glfw::Modifiers::Alt),
+    (glfw::Key::LeftSuper, glfw::Modifiers::Super),
+    (glfw::Key::RightSuper, glfw::Modifiers::Super),
+];
+
+fn find_name<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, value)| value)
+}
+fn find_value<T: PartialEq>(table: &[(&'static str, T)], value: &T) -> Option<&'static str> {
+    table.iter().find(|(_, v)| v == value).map(|&(n, _)| n)
+}
+fn key_name(key: glfw::Key) -> String {
+    find_value(KEYS, &key).map_or_else(|| format!("{:?}", key), str::to_string)
+}
+fn parse_key(name: &str) -> Result<glfw::Key, String> {
+    find_name(KEYS, name).ok_or_else(|| format!("tastă necunoscută '{}'", name))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Key(glfw::Key),
+    Mouse(glfw::MouseButton),
+    // un pas de rotiță, ține doar frame-ul în care a venit
+    Scroll(MouseScroll),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub input: Input,
+    // trebuie ținuți odată cu tasta; alți modificatori ținuți în plus nu o opresc
+    pub modifiers: glfw::Modifiers,
+}
+
+impl Binding {
+    pub fn key(key: glfw::Key) -> Self {
+        Self {
+            input: Input::Key(key),
+            modifiers: glfw::Modifiers::empty(),
+        }
+    }
+    pub fn mouse(button: glfw::MouseButton) -> Self {
+        Self {
+            input: Input::Mouse(button),
+            modifiers: glfw::Modifiers::empty(),
+        }
+    }
+    pub fn scroll(scroll: MouseScroll) -> Self {
+        Self {
+            input: Input::Scroll(scroll),
+            modifiers: glfw::Modifiers::empty(),
+        }
+    }
+    pub fn with_modifiers(mut self, modifiers: glfw::Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+    // "Ctrl+Shift+S", "MouseLeft", "ScrollUp"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let name = parts.pop().unwrap_or_default();
+        let mut modifiers = glfw::Modifiers::empty();
+        for part in parts {
+            modifiers |= find_name(MODIFIER_NAMES, part)
+                .ok_or_else(|| format!("modificator necunoscut '{}'", part))?;
+        }
+        let input = if let Some(button) = find_name(MOUSE_NAMES, name) {
+            Input::Mouse(button)
+        } else if let Some(scroll) = find_name(SCROLL_NAMES, name) {
+            Input::Scroll(scroll)
+        } else {
+            Input::Key(parse_key(name)?)
+        };
+        Ok(Self { input, modifiers })
+    }
+    // inversul lui parse, pentru salvat și pentru afișat în meniuri
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        for (modifier_name, modifier) in MODIFIER_NAMES {
+            if self.modifiers.contains(*modifier) {
+                name.push_str(modifier_name);
+                name.push('+');
+            }
+        }
+        match self.input {
+            Input::Key(key) => name.push_str(&key_name(key)),
+            Input::Mouse(button) => name.push_str(find_value(MOUSE_NAMES, &button).unwrap()),
+            Input::Scroll(scroll) => name.push_str(find_value(SCROLL_NAMES, &scroll).unwrap()),
+        }
+        name
+    }
+    fn modifiers_held(&self, held: glfw::Modifiers) -> bool {
+        held.contains(self.modifiers)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AxisSource {
+    // prima tastă dă +1, a doua -1
+    Keys(glfw::Key, glfw::Key),
+    // cât s-a mișcat mouse-ul în frame-ul ăsta, în pixeli
+    MouseX,
+    MouseY,
+    // pașii de rotiță din frame-ul ăsta, sus și dreapta pozitive
+    ScrollX,
+    ScrollY,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisBinding {
+    pub source: AxisSource,
+    pub scale: f32,
+}
+
+impl AxisBinding {
+    pub fn new(source: AxisSource) -> Self {
+        Self { source, scale: 1.0 }
+    }
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+    // "MouseY * -1", "D/A"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (source, scale) = match text.split_once('*') {
+            Some((source, scale)) => {
+                let scale = scale
+                    .trim()
+                    .parse::<f32>()
+                    .map_err(|_| format!("scară greșită '{}'", scale.trim()))?;
+                (source.trim(), scale)
+            }
+            None => (text.trim(), 1.0),
+        };
+        let source = match source {
+            s if s.eq_ignore_ascii_case("MouseX") => AxisSource::MouseX,
+            s if s.eq_ignore_ascii_case("MouseY") => AxisSource::MouseY,
+            s if s.eq_ignore_ascii_case("ScrollX") => AxisSource::ScrollX,
+            s if s.eq_ignore_ascii_case("ScrollY") => AxisSource::ScrollY,
+            s => {
+                let (positive, negative) = s
+                    .split_once('/')
+                    .ok_or_else(|| format!("sursă de axă necunoscută '{}'", s))?;
+                AxisSource::Keys(parse_key(positive.trim())?, parse_key(negative.trim())?)
+            }
+        };
+        Ok(Self { source, scale })
+    }
+    pub fn name(&self) -> String {
+        let source = match self.source {
+            AxisSource::Keys(positive, negative) => {
+                format!("{}/{}", key_name(positive), key_name(negative))
+            }
+            AxisSource::MouseX => "MouseX".to_string(),
+            AxisSource::MouseY => "MouseY".to_string(),
+            AxisSource::ScrollX => "ScrollX".to_string(),
+            AxisSource::ScrollY => "ScrollY".to_string(),
+        };
+        if self.scale == 1.0 {
+            source
+        } else {
+            format!("{} * {}", source, self.scale)
+        }
+    }
+}
+
+// o tastă sau un buton de mouse, ce urmărește InputState
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    Key(glfw::Key),
+    Mouse(glfw::MouseButton),
+}
+
+impl From<glfw::Key> for Button {
+    fn from(key: glfw::Key) -> Self {
+        Button::Key(key)
+    }
+}
+
+impl From<glfw::MouseButton> for Button {
+    fn from(button: glfw::MouseButton) -> Self {
+        Button::Mouse(button)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ButtonState {
+    held: bool,
+    pressed: bool,
+    released: bool,
+    double: bool,
+    // apăsată sau repetată de sistem cât e ținută, în frame-ul ăsta
+    repeated: bool,
+    // de când e ținut, pentru held_time
+    press_time: f64,
+    // apăsarea care poate deveni dublu-click; după un dublu-click o luăm de la capăt
+    last_press: f64,
+    // modificatorii ținuți la apăsare
+    modifiers: glfw::Modifiers,
+}
+
+impl ButtonState {
+    fn new() -> Self {
+        Self {
+            held: false,
+            pressed: false,
+            released: false,
+            double: false,
+            repeated: false,
+            press_time: 0.0,
+            last_press: f64::NEG_INFINITY,
+            modifiers: glfw::Modifiers::empty(),
+        }
+    }
+}
+
+/*
+Starea tastelor și a butoanelor de mouse de la un frame la altul, construită din
+evenimentele adunate de `Window::poll_events`: ce s-a apăsat sau eliberat chiar în
+frame-ul ăsta, ce e ținut și de cât timp, dublu-click-uri, plus mișcarea mouse-ului
+și rotița adunate din toate evenimentele frame-ului.
+
+    state.update(&window);
+    if state.just_pressed(glfw::Key::Tab) { ... }
+    if state.double_pressed(glfw::MouseButton::Button1) { ... }
+*/
+pub struct InputState {
+    // două apăsări mai apropiate de atâtea secunde sunt dublu-click
+    pub double_press_time: f32,
+    buttons: HashMap<Button, ButtonState>,
+    mouse_delta: (f32, f32),
+    scroll: (f32, f32),
+    time: f64,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self {
+            double_press_time: 0.3,
+            buttons: HashMap::new(),
+            mouse_delta: (0.0, 0.0),
+            scroll: (0.0, 0.0),
+            time: 0.0,
+        }
+    }
+    // o dată pe frame, după `Window::poll_events`
+    pub fn update(&mut self, window: &Window) {
+        self.time = window.time;
+        for state in self.buttons.values_mut() {
+            state.pressed = false;
+            state.released = false;
+            state.double = false;
+            state.repeated = false;
+        }
+        let keys = window
+            .keyboard
+            .keys
+            .iter()
+            .map(|e| (Button::Key(e.key), e.action, e.modifiers));
+        let buttons = window
+            .mouse
+            .buttons
+            .iter()
+            .map(|e| (Button::Mouse(e.button), e.action, e.modifiers));
+        for (button, action, modifiers) in keys.chain(buttons) {
+            let state = self.buttons.entry(button).or_insert_with(ButtonState::new);
+            match action {
+                glfw::Action::Press => {
+                    state.double = self.time - state.last_press <= self.double_press_time as f64;
+                    state.last_press = if state.double {
+                        f64::NEG_INFINITY
+                    } else {
+                        self.time
+                    };
+                    state.held = true;
+                    state.pressed = true;
+                    state.repeated = true;
+                    state.press_time = self.time;
+                    state.modifiers = modifiers;
+                }
+                glfw::Action::Release => {
+                    state.held = false;
+                    state.released = true;
+                }
+                glfw::Action::Repeat => state.repeated = true,
+            }
+        }
+        self.mouse_delta = (window.mouse.dx as f32, window.mouse.dy as f32);
+        self.scroll = (window.mouse.scroll_x as f32, window.mouse.scroll_y as f32);
+    }
+
+    pub fn just_pressed(&self, button: impl Into<Button>) -> bool {
+        self.get(button).is_some_and(|s| s.pressed)
+    }
+    pub fn just_released(&self, button: impl Into<Button>) -> bool {
+        self.get(button).is_some_and(|s| s.released)
+    }
+    // ca just_pressed, plus repetările tastei ținute, pentru text
+    pub fn repeated(&self, button: impl Into<Button>) -> bool {
+        self.get(button).is_some_and(|s| s.repeated)
+    }
+    pub fn is_held(&self, button: impl Into<Button>) -> bool {
+        self.get(button).is_some_and(|s| s.held)
+    }
+    // de câte secunde e ținut, 0 dacă nu e
+    pub fn held_time(&self, button: impl Into<Button>) -> f32 {
+        self.get(button)
+            .filter(|s| s.held)
+            .map_or(0.0, |s| (self.time - s.press_time) as f32)
+    }
+    // a doua apăsare dintr-un dublu-click, doar în frame-ul ei
+    pub fn double_pressed(&self, button: impl Into<Button>) -> bool {
+        self.get(button).is_some_and(|s| s.double)
+    }
+    // modificatorii ținuți la ultima apăsare a butonului
+    pub fn press_modifiers(&self, button: impl Into<Button>) -> glfw::Modifiers {
+        self.get(button)
+            .map_or(glfw::Modifiers::empty(), |s| s.modifiers)
+    }
+    // modificatorii ținuți acum
+    pub fn modifiers(&self) -> glfw::Modifiers {
+        MODIFIER_KEYS
+            .iter()
+            .filter(|(key, _)| self.is_held(*key))
+            .fold(glfw::Modifiers::empty(), |held, (_, modifier)| {
+                held | *modifier
+            })
+    }
+    // în pixeli, adunat din toate mișcările frame-ului
+    pub fn mouse_delta(&self) -> (f32, f32) {
+        self.mouse_delta
+    }
+    // pași de rotiță în frame-ul ăsta, sus și dreapta pozitive
+    pub fn scroll(&self) -> (f32, f32) {
+        self.scroll
+    }
+    // timpul ferestrei la ultimul update
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn get(&self, button: impl Into<Button>) -> Option<&ButtonState> {
+        self.buttons.get(&button.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct ActionState {
+    down: bool,
+    pressed: bool,
+    released: bool,
+    double: bool,
+    repeated: bool,
+    held_time: f32,
+}
+
+struct Action {
+    name: String,
+    bindings: Vec<Binding>,
+    state: ActionState,
+}
+
+struct Axis {
+    name: String,
+    bindings: Vec<AxisBinding>,
+    value: f32,
+}
+
+/*
+Leagă acțiuni cu nume ("jump", "move_forward") de taste, butoane de mouse și rotiță, și
+axe ("look_x") de mișcarea mouse-ului sau de perechi de taste. Se apelează `update` o dată
+pe frame după `Window::poll_events`, apoi jocul întreabă doar de acțiuni:
+
+    input.update(&window);
+    if input.pressed("jump") { ... }
+    let yaw = input.axis("look_x") * sensitivity;
+
+O acțiune sau o axă care nu există e mereu oprită, respectiv zero.
+*/
+pub struct InputMap {
+    actions: Vec<Action>,
+    axes: Vec<Axis>,
+    // starea tastelor de sub acțiuni, pentru ce nu trece prin legături
+    pub state: InputState,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::parse(DEFAULT_CONFIG).expect("assets/input.cfg")
+    }
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self {
+            actions: Vec::new(),
+            axes: Vec::new(),
+            state: InputState::new(),
+        }
+    }
+    // formatul e descris la începutul lui assets/input.cfg
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut map = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            map.parse_line(line)
+                .map_err(|e| format!("linia {}: {}", number + 1, e))?;
+        }
+        Ok(map)
+    }
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+    // cu legăturile implicite dacă fișierul lipsește sau e greșit
+    pub fn load_or_default(path: &str) -> Self {
+        Self::load(path).unwrap_or_else(|e| {
+            println!("{}, folosesc tastele implicite", e);
+            Self::default()
+        })
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_config()).map_err(|e| format!("{}: {}", path, e))
+    }
+    pub fn to_config(&self) -> String {
+        let mut text = String::new();
+        for action in &self.actions {
+            let bindings: Vec<String> = action.bindings.iter().map(Binding::name).collect();
+            text.push_str(&format!(
+                "action {} = {}\n",
+                action.name,
+                bindings.join(", ")
+            ));
+        }
+        for axis in &self.axes {
+            let bindings: Vec<String> = axis.bindings.iter().map(AxisBinding::name).collect();
+            text.push_str(&format!("axis {} = {}\n", axis.name, bindings.join(", ")));
+        }
+        text
+    }
+
+    // adaugă o legătură în plus acțiunii
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        self.action_entry(action).bindings.push(binding);
+    }
+    // înlocuiește toate legăturile acțiunii, de exemplu din meniul de taste
+    pub fn rebind(&mut self, action: &str, binding: Binding) {
+        let entry = self.action_entry(action);
+        entry.bindings.clear();
+        entry.bindings.push(binding);
+    }
+    pub fn unbind(&mut self, action: &str) {
+        if let Some(entry) = self.actions.iter_mut().find(|a| a.name == action) {
+            entry.bindings.clear();
+            entry.state = ActionState::default();
+        }
+    }
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .iter()
+            .find(|a| a.name == action)
+            .map_or(&[], |a| &a.bindings)
+    }
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        self.axis_entry(axis).bindings.push(binding);
+    }
+    pub fn rebind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let entry = self.axis_entry(axis);
+        entry.bindings.clear();
+        entry.bindings.push(binding);
+    }
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes
+            .iter()
+            .find(|a| a.name == axis)
+            .map_or(&[], |a| &a.bindings)
+    }
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().map(|a| a.name.as_str())
+    }
+
+    /*
+    Prima tastă, buton sau rotiță apăsată în frame-ul ăsta, cu modificatorii ținuți.
+    Pentru meniul de taste: se așteaptă până întoarce ceva, apoi se dă la `rebind`.
+    Tastele modificator singure nu contează, ca Ctrl+S să poată fi prins, iar tastele
+    necunoscute lui GLFW sunt sărite, nu pot fi deosebite între ele la încărcare.
+    */
+    pub fn capture(&self, window: &Window) -> Option<Binding> {
+        let modifiers =
+            |m: glfw::Modifiers| m & !(glfw::Modifiers::CapsLock | glfw::Modifiers::NumLock);
+        for key in &window.keyboard.keys {
+            if key.action == glfw::Action::Press
+                && !MODIFIER_KEYS.iter().any(|(k, _)| *k == key.key)
+                && key.key != glfw::Key::Unknown
+            {
+                return Some(Binding::key(key.key).with_modifiers(modifiers(key.modifiers)));
+            }
+        }
+        for button in &window.mouse.buttons {
+            if button.action == glfw::Action::Press {
+                return Some(
+                    Binding::mouse(button.button).with_modifiers(modifiers(button.modifiers)),
+                );
+            }
+        }
+        let scroll = window.mouse.scroll.first()?;
+        Some(Binding::scroll(*scroll).with_modifiers(self.state.modifiers()))
+    }
+
+    pub fn update(&mut self, window: &Window) {
+        self.state.update(window);
+        let input = &self.state;
+        let held = input.modifiers();
+        let (scroll_x, scroll_y) = input.scroll();
+        for action in &mut self.actions {
+            let previous = action.state;
+            let mut state = ActionState::default();
+            for binding in &action.bindings {
+                let button = match binding.input {
+                    Input::Key(key) => Button::Key(key),
+                    Input::Mouse(button) => Button::Mouse(button),
+                    Input::Scroll(direction) => {
+                        let scrolled = binding.modifiers_held(held)
+                            && match direction {
+                                MouseScroll::Up => scroll_y > 0.0,
+                                MouseScroll::Down => scroll_y < 0.0,
+                                MouseScroll::Right => scroll_x > 0.0,
+                                MouseScroll::Left => scroll_x < 0.0,
+                            };
+                        state.down |= scrolled;
+                        state.pressed |= scrolled;
+                        state.repeated |= scrolled;
+                        continue;
+                    }
+                };
+                let down = input.is_held(button) && binding.modifiers_held(held);
+                let pressed = input.just_pressed(button)
+                    && binding.modifiers_held(input.press_modifiers(button));
+                state.down |= down;
+                state.pressed |= pressed;
+                state.released |= input.just_released(button);
+                state.double |= pressed && input.double_pressed(button);
+                state.repeated |= input.repeated(button) && binding.modifiers_held(held);
+                if down {
+                    state.held_time = state.held_time.max(input.held_time(button));
+                }
+            }
+            // eliberarea contează doar dacă acțiunea chiar a fost pornită
+            state.released &= previous.down || state.pressed;
+            action.state = state;
+        }
+
+        let (dx, dy) = input.mouse_delta();
+        for axis in &mut self.axes {
+            axis.value = axis
+                .bindings
+                .iter()
+                .map(|binding| {
+                    let value = match binding.source {
+                        AxisSource::Keys(positive, negative) => {
+                            input.is_held(positive) as i32 as f32
+                                - input.is_held(negative) as i32 as f32
+                        }
+                        AxisSource::MouseX => dx,
+                        AxisSource::MouseY => dy,
+                        AxisSource::ScrollX => scroll_x,
+                        AxisSource::ScrollY => scroll_y,
+                    };
+                    value * binding.scale
+                })
+                .sum();
+        }
+    }
+
+    // ținută apăsată acum
+    pub fn down(&self, action: &str) -> bool {
+        self.action_state(action).down
+    }
+    // apăsată în frame-ul ăsta
+    pub fn pressed(&self, action: &str) -> bool {
+        self.action_state(action).pressed
+    }
+    // eliberată în frame-ul ăsta
+    pub fn released(&self, action: &str) -> bool {
+        self.action_state(action).released
+    }
+    // apăsată sau repetată cât e ținută (ștergere în text, derulare prin liste)
+    pub fn repeated(&self, action: &str) -> bool {
+        self.action_state(action).repeated
+    }
+    // apăsată a doua oară la rând, repede (dublu-click, dublu-tap)
+    pub fn double_pressed(&self, action: &str) -> bool {
+        self.action_state(action).double
+    }
+    // de câte secunde e ținută, 0 dacă nu e
+    pub fn held_time(&self, action: &str) -> f32 {
+        self.action_state(action).held_time
+    }
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axes
+            .iter()
+            .find(|a| a.name == axis)
+            .map_or(0.0, |a| a.value)
+    }
+
+    fn action_state(&self, action: &str) -> ActionState {
+        self.actions
+            .iter()
+            .find(|a| a.name == action)
+            .map_or(ActionState::default(), |a| a.state)
+    }
+    fn action_entry(&mut self, name: &str) -> &mut Action {
+        if let Some(index) = self.actions.iter().position(|a| a.name == name) {
+            return &mut self.actions[index];
+        }
+        self.actions.push(Action {
+            name: name.to_string(),
+            bindings: Vec::new(),
+            state: ActionState::default(),
+        });
+        self.actions.last_mut().unwrap()
+    }
+    fn axis_entry(&mut self, name: &str) -> &mut Axis {
+        if let Some(index) = self.axes.iter().position(|a| a.name == name) {
+            return &mut self.axes[index];
+        }
+        self.axes.push(Axis {
+            name: name.to_string(),
+            bindings: Vec::new(),
+            value: 0.0,
+        });
+        self.axes.last_mut().unwrap()
+    }
+    // "action jump = Space, MouseRight" sau "axis look_y = MouseY * -1"
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        let (head, bindings) = line
+            .split_once('=')
+            .ok_or_else(|| "lipsește '='".to_string())?;
+        let mut head = head.split_whitespace();
+        let (Some(kind), Some(name), None) = (head.next(), head.next(), head.next()) else {
+            return Err("se așteaptă 'action <nume>' sau 'axis <nume>'".to_string());
+        };
+        let bindings = bindings.split(',').map(str::trim).filter(|b| !b.is_empty());
+        match kind {
+            "action" => {
+                self.action_entry(name);
+                for binding in bindings {
+                    self.bind(name, Binding::parse(binding)?);
+                }
+            }
+            "axis" => {
+                self.axis_entry(name);
+                for binding in bindings {
+                    self.bind_axis(name, AxisBinding::parse(binding)?);
+                }
+            }
+            kind => return Err(format!("'{}' nu e nici action, nici axis", kind)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::replay::Frame;
+    use crate::seb::window::KeyButton;
+
+    fn key(key: glfw::Key, action: glfw::Action, modifiers: glfw::Modifiers) -> KeyButton {
+        KeyButton {
+            key,
+            action,
+            modifiers,
+            scancode: 0,
+        }
+    }
+
+    fn press(k: glfw::Key) -> KeyButton {
+        key(k, glfw::Action::Press, glfw::Modifiers::empty())
+    }
+    fn release(k: glfw::Key) -> KeyButton {
+        key(k, glfw::Action::Release, glfw::Modifiers::empty())
+    }
+
+    // un frame: evenimentele date, apoi update, ca după poll_events
+    fn frame(input: &mut InputMap, window: &mut Window, time: f64, keys: &[KeyButton]) {
+        window.keyboard.reset();
+        window.mouse.reset();
+        window.time = time;
+        window.keyboard.keys.extend_from_slice(keys);
+        input.update(window);
+    }
+
+    #[test]
+    fn default_config_parses() {
+        let input = InputMap::default();
+        assert_eq!(input.bindings("jump"), &[Binding::key(glfw::Key::Space)]);
+        assert_eq!(
+            input.bindings("erase"),
+            &[Binding::key(glfw::Key::Backspace)]
+        );
+        assert_eq!(
+            input.axis_bindings("look_y"),
+            &[AxisBinding::new(AxisSource::MouseY).with_scale(-1.0)]
+        );
+        assert!(input.bindings("nu_exista").is_empty());
+    }
+
+    #[test]
+    fn parse_bindings() {
+        let input = InputMap::parse(
+            "# comentariu\n\
+             action save = Ctrl+S, MouseMiddle  # la sfârșit de linie\n\
+             action zoom = Shift+ScrollUp\n\
+             action empty =\n\
+             axis strafe = D/A * 2.5\n",
+        )
+        .unwrap();
+        assert_eq!(
+            input.bindings("save"),
+            &[
+                Binding::key(glfw::Key::S).with_modifiers(glfw::Modifiers::Control),
+                Binding::mouse(glfw::MouseButton::Button3),
+            ]
+        );
+        assert_eq!(
+            input.bindings("zoom"),
+            &[Binding::scroll(MouseScroll::Up).with_modifiers(glfw::Modifiers::Shift)]
+        );
+        // o acțiune fără legături există, doar că nu pornește niciodată
+        assert!(input.actions().any(|a| a == "empty"));
+        assert_eq!(
+            input.axis_bindings("strafe"),
+            &[AxisBinding::new(AxisSource::Keys(glfw::Key::D, glfw::Key::A)).with_scale(2.5)]
+        );
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let error = InputMap::parse("action a = W\naction b = Nimic")
+            .err()
+            .unwrap();
+        assert!(error.starts_with("linia 2:"), "{}", error);
+        assert!(InputMap::parse("action a W").is_err());
+        assert!(InputMap::parse("button a = W").is_err());
+        assert!(InputMap::parse("action a = Hyper+W").is_err());
+        assert!(InputMap::parse("axis a = MouseX * mult").is_err());
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let input = InputMap::default();
+        let text = input.to_config();
+        let again = InputMap::parse(&text).unwrap();
+        assert_eq!(again.to_config(), text);
+        for action in input.actions() {
+            assert_eq!(again.bindings(action), input.bindings(action), "{}", action);
+        }
+    }
+
+    #[test]
+    fn every_key_has_one_name() {
+        for (i, &(name, key)) in KEYS.iter().enumerate() {
+            assert_eq!(parse_key(&key_name(key)), Ok(key));
+            assert!(
+                KEYS[i + 1..]
+                    .iter()
+                    .all(|&(n, k)| k != key && !n.eq_ignore_ascii_case(name)),
+                "{} apare de două ori",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn rebind_replaces_bindings() {
+        let mut input = InputMap::parse("action jump = Space, MouseRight").unwrap();
+        input.bind("jump", Binding::key(glfw::Key::J));
+        assert_eq!(input.bindings("jump").len(), 3);
+        input.rebind("jump", Binding::key(glfw::Key::E));
+        assert_eq!(input.bindings("jump"), &[Binding::key(glfw::Key::E)]);
+        assert!(input.to_config().contains("action jump = E\n"));
+
+        let mut window = Window::new();
+        frame(&mut input, &mut window, 0.0, &[press(glfw::Key::Space)]);
+        assert!(!input.pressed("jump"));
+        frame(&mut input, &mut window, 0.1, &[press(glfw::Key::E)]);
+        assert!(input.pressed("jump"));
+
+        input.unbind("jump");
+        assert!(input.bindings("jump").is_empty());
+        assert!(!input.down("jump"));
+    }
+
+    #[test]
+    fn action_state_across_frames() {
+        let mut input = InputMap::parse("action save = Ctrl+S\naction erase = Backspace").unwrap();
+        let mut window = Window::new();
+        let ctrl = glfw::Modifiers::Control;
+
+        // fără Ctrl, S singur nu pornește acțiunea
+        frame(&mut input, &mut window, 0.0, &[press(glfw::Key::S)]);
+        assert!(!input.pressed("save") && !input.down("save"));
+        frame(&mut input, &mut window, 0.1, &[release(glfw::Key::S)]);
+        assert!(!input.released("save"));
+
+        frame(
+            &mut input,
+            &mut window,
+            0.2,
+            &[
+                press(glfw::Key::LeftControl),
+                key(glfw::Key::S, glfw::Action::Press, ctrl),
+            ],
+        );
+        assert!(input.pressed("save") && input.down("save"));
+        frame(&mut input, &mut window, 0.7, &[]);
+        assert!(!input.pressed("save") && input.down("save"));
+        assert!((input.held_time("save") - 0.5).abs() < 1e-6);
+        frame(&mut input, &mut window, 0.8, &[release(glfw::Key::S)]);
+        assert!(input.released("save") && !input.down("save"));
+
+        // repetările tastei ținute dau `repeated`, dar nu o nouă apăsare
+        let backspace = glfw::Key::Backspace;
+        let repeat = key(backspace, glfw::Action::Repeat, glfw::Modifiers::empty());
+        frame(&mut input, &mut window, 1.0, &[press(backspace)]);
+        assert!(input.pressed("erase") && input.repeated("erase"));
+        frame(&mut input, &mut window, 1.1, &[]);
+        assert!(!input.repeated("erase"));
+        frame(&mut input, &mut window, 1.5, &[repeat]);
+        assert!(!input.pressed("erase") && input.repeated("erase"));
+
+        // dublă doar când a doua apăsare vine repede după prima
+        frame(&mut input, &mut window, 1.6, &[release(backspace)]);
+        frame(&mut input, &mut window, 1.7, &[press(backspace)]);
+        assert!(!input.double_pressed("erase"));
+        frame(&mut input, &mut window, 1.75, &[release(backspace)]);
+        frame(&mut input, &mut window, 1.8, &[press(backspace)]);
+        assert!(input.double_pressed("erase"));
+    }
+
+    #[test]
+    fn axes_sum_their_bindings() {
+        let mut input = InputMap::parse("axis move = D/A, ScrollY * 2").unwrap();
+        let mut window = Window::new();
+        frame(&mut input, &mut window, 0.0, &[press(glfw::Key::D)]);
+        assert_eq!(input.axis("move"), 1.0);
+        window.keyboard.reset();
+        window.mouse.scroll_y = -1.0;
+        input.update(&window);
+        assert_eq!(input.axis("move"), -1.0);
+        assert_eq!(input.axis("nu_exista"), 0.0);
+    }
+
+    // un frame prin Window, cu evenimente GLFW, apoi update pe InputState
+    fn state_frame<'a>(
+        state: &'a mut InputState,
+        window: &mut Window,
+        time: f64,
+        events: &[glfw::WindowEvent],
+    ) -> &'a InputState {
+        window.apply_frame(Frame {
+            time,
+            events: events.iter().map(|e| (time, e.clone())).collect(),
+        });
+        state.update(window);
+        state
+    }
+
+    fn click(action: glfw::Action) -> glfw::WindowEvent {
+        glfw::WindowEvent::MouseButton(glfw::MouseButton::Button1, action, glfw::Modifiers::empty())
+    }
+    fn enter(action: glfw::Action) -> glfw::WindowEvent {
+        glfw::WindowEvent::Key(glfw::Key::Enter, 28, action, glfw::Modifiers::empty())
+    }
+
+    #[test]
+    fn state_double_press_and_held_time() {
+        use glfw::Action::{Press, Release};
+        let left = glfw::MouseButton::Button1;
+        let read = |state: &InputState| {
+            (
+                state.just_pressed(left),
+                state.double_pressed(left),
+                state.held_time(left),
+            )
+        };
+        let mut state = InputState::new();
+        let mut window = Window::new();
+        let mut frame = |time, events: &[glfw::WindowEvent]| {
+            read(state_frame(&mut state, &mut window, time, events))
+        };
+        assert_eq!(frame(1.0, &[click(Press)]), (true, false, 0.0));
+        assert_eq!(frame(1.1, &[click(Release)]), (false, false, 0.0));
+        assert_eq!(frame(1.2, &[click(Press)]), (true, true, 0.0));
+        // dubla ține doar un frame
+        assert_eq!(frame(1.25, &[click(Release)]), (false, false, 0.0));
+        // după un dublu-click a treia apăsare o ia de la capăt
+        assert_eq!(frame(1.3, &[click(Press)]), (true, false, 0.0));
+        let (pressed, double, held) = frame(1.5, &[]);
+        assert!(!pressed && !double);
+        assert!((held - 0.2).abs() < 1e-6, "{}", held);
+        assert_eq!(frame(1.9, &[click(Release)]), (false, false, 0.0));
+        // prea departe de apăsarea de la 1.3
+        assert_eq!(frame(2.0, &[click(Press)]), (true, false, 0.0));
+        assert_eq!(frame(2.5, &[click(Release)]), (false, false, 0.0));
+        assert_eq!(frame(2.75, &[click(Press)]), (true, false, 0.0));
+        assert!(state.is_held(left) && !state.is_held(glfw::MouseButton::Button2));
+    }
+
+    #[test]
+    fn state_repeated_only_while_repeating() {
+        use glfw::Action::{Press, Release, Repeat};
+        let key = glfw::Key::Enter;
+        let mut state = InputState::new();
+        let mut window = Window::new();
+
+        // apăsarea contează și ca prima repetare
+        let s = state_frame(&mut state, &mut window, 0.0, &[enter(Press)]);
+        assert!(s.just_pressed(key) && s.repeated(key) && s.is_held(key));
+        let s = state_frame(&mut state, &mut window, 0.1, &[]);
+        assert!(!s.just_pressed(key) && !s.repeated(key) && s.is_held(key));
+        let s = state_frame(&mut state, &mut window, 0.6, &[enter(Repeat)]);
+        assert!(!s.just_pressed(key) && s.repeated(key));
+        // repetările nu mută începutul apăsării
+        assert!((s.held_time(key) - 0.6).abs() < 1e-6);
+        let s = state_frame(
+            &mut state,
+            &mut window,
+            0.65,
+            &[enter(Repeat), enter(Repeat)],
+        );
+        assert!(s.repeated(key) && !s.double_pressed(key));
+        let s = state_frame(&mut state, &mut window, 0.7, &[]);
+        assert!(!s.repeated(key) && s.is_held(key));
+        let s = state_frame(&mut state, &mut window, 0.8, &[enter(Release)]);
+        assert!(s.just_released(key) && !s.repeated(key) && !s.is_held(key));
+        assert_eq!(s.held_time(key), 0.0);
+    }
+
+    #[test]
+    fn state_mouse_delta_and_scroll_per_frame() {
+        use glfw::WindowEvent::{CursorPos, Scroll};
+        let mut state = InputState::new();
+        let mut window = Window::new();
+        let mut frame = |time, events: &[glfw::WindowEvent]| {
+            let s = state_frame(&mut state, &mut window, time, events);
+            (s.mouse_delta(), s.scroll())
+        };
+        // prima poziție nu e o mișcare
+        assert_eq!(
+            frame(0.0, &[CursorPos(100.0, 100.0)]),
+            ((0.0, 0.0), (0.0, 0.0))
+        );
+        // toate evenimentele frame-ului se adună
+        let events = [
+            CursorPos(110.0, 95.0),
+            Scroll(0.0, 1.0),
+            CursorPos(130.0, 90.0),
+            Scroll(0.0, 1.0),
+            Scroll(-0.5, 0.0),
+        ];
+        assert_eq!(frame(0.1, &events), ((30.0, -10.0), (-0.5, 2.0)));
+        // fără evenimente totul se întoarce la zero
+        assert_eq!(frame(0.2, &[]), ((0.0, 0.0), (0.0, 0.0)));
+        assert_eq!(
+            frame(0.3, &[CursorPos(131.0, 90.0), Scroll(0.0, -1.0)]),
+            ((1.0, 0.0), (0.0, -1.0))
+        );
+        assert_eq!(state.time(), 0.3);
+    }
+}
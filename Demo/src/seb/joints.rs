@@ -0,0 +1,694 @@
+use crate::seb::physics::RigidBody;
+use nalgebra_glm as glm;
+
+// cât din eroarea de poziție corectăm pe pas, prin viteză
+const BAUMGARTE: f32 = 0.2;
+
+// locurile impulsurilor păstrate între pași (warm starting), pe tipuri de rânduri
+const POINT: usize = 0; // 3 rânduri
+const ANGLE: usize = 3; // 3 rânduri
+const LOWER: usize = 6;
+const UPPER: usize = 7;
+const MOTOR: usize = 8;
+const SLOTS: usize = 9;
+
+/*
+Ce grade de libertate lasă articulația între cele două corpuri:
+- Ball: ancorele coincid, rotația e liberă
+- Hinge: ancorele coincid și corpurile se rotesc doar în jurul axei (balamale, rotoare)
+- Distance: ancorele stau la `length`; cu `stiffness` > 0 e un arc cu amortizarea `damping`
+- Slider: fără rotație relativă, ancora lui b alunecă doar pe axă
+- Fixed: lipite
+*/
+#[derive(Clone, Copy, Debug)]
+pub enum JointKind {
+    Ball,
+    Hinge,
+    Distance {
+        length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+    Slider,
+    Fixed,
+}
+
+// motorul împinge spre viteza `speed` cu cel mult `max_force` (cuplu la rotații)
+#[derive(Clone, Copy, Debug)]
+pub struct Motor {
+    pub speed: f32,
+    pub max_force: f32,
+}
+impl Motor {
+    pub fn new(speed: f32, max_force: f32) -> Self {
+        Self { speed, max_force }
+    }
+}
+
+// un rând din jacobian: viteza relativă pe o direcție, cu impulsul ei acumulat
+#[derive(Clone, Copy, Debug)]
+struct Row {
+    slot: usize,
+    linear: glm::Vec3,
+    angular_a: glm::Vec3,
+    angular_b: glm::Vec3,
+    mass: f32,
+    rhs: f32,   // viteza la care vrem să ajungem
+    gamma: f32, // moliciune, doar la arcuri
+    lower: f32,
+    upper: f32,
+    impulse: f32,
+}
+
+/*
+Legătura dintre corpurile `a` și `b` (b poate fi TERRAIN, adică lumea). Ancorele și axa se dau
+în spațiul lumii, la poziția de acum a corpurilor, și sunt fixate pe corpuri la add_joint.
+Limita și motorul sunt pe gradul de libertate rămas:
+- Hinge: unghiul în jurul axei, în radiani, față de poziția de la add_joint
+- Slider: deplasarea pe axă față de ancoră
+- Distance: lungimea (ca limită e o frânghie, dacă nu are arc)
+- Ball: unghiul dintre axele celor două corpuri (con), motorul rotește în jurul axei lui a
+- Fixed: nu are
+*/
+#[derive(Clone, Debug)]
+pub struct Joint {
+    pub kind: JointKind,
+    pub a: usize,
+    pub b: usize,
+    pub anchor_a: glm::Vec3,
+    pub anchor_b: glm::Vec3,
+    pub axis: glm::Vec3,
+    pub limit: Option<(f32, f32)>,
+    pub motor: Option<Motor>,
+    // dacă cele două corpuri se mai ciocnesc între ele (ușa de tocul ei de obicei nu)
+    pub collide_connected: bool,
+    local_anchor_a: glm::Vec3,
+    local_anchor_b: glm::Vec3,
+    local_axis_a: glm::Vec3,
+    local_axis_b: glm::Vec3,
+    // o perpendiculară pe axă, de la care măsurăm unghiul balamalei
+    local_normal_a: glm::Vec3,
+    local_normal_b: glm::Vec3,
+    // rotația lui b în spațiul lui a la add_joint
+    reference: glm::Mat3,
+    rows: Vec<Row>,
+    impulses: [f32; SLOTS],
+    inverse_mass: [f32; 2],
+    inverse_inertia: [glm::Mat3; 2],
+}
+
+impl Joint {
+    pub fn new(
+        kind: JointKind,
+        a: usize,
+        b: usize,
+        anchor_a: glm::Vec3,
+        anchor_b: glm::Vec3,
+        axis: glm::Vec3,
+    ) -> Self {
+        let axis = if glm::length2(&axis) > 1e-12 {
+            glm::normalize(&axis)
+        } else {
+            glm::vec3(0.0, 1.0, 0.0)
+        };
+        Self {
+            kind,
+            a,
+            b,
+            anchor_a,
+            anchor_b,
+            axis,
+            limit: None,
+            motor: None,
+            collide_connected: false,
+            local_anchor_a: glm::Vec3::zeros(),
+            local_anchor_b: glm::Vec3::zeros(),
+            local_axis_a: axis,
+            local_axis_b: axis,
+            local_normal_a: perpendicular(&axis),
+            local_normal_b: perpendicular(&axis),
+            reference: glm::Mat3::identity(),
+            rows: Vec::new(),
+            impulses: [0.0; SLOTS],
+            inverse_mass: [0.0; 2],
+            inverse_inertia: [glm::Mat3::zeros(); 2],
+        }
+    }
+    pub fn ball(a: usize, b: usize, anchor: glm::Vec3) -> Self {
+        Self::new(
+            JointKind::Ball,
+            a,
+            b,
+            anchor,
+            anchor,
+            glm::vec3(0.0, -1.0, 0.0),
+        )
+    }
+    pub fn hinge(a: usize, b: usize, anchor: glm::Vec3, axis: glm::Vec3) -> Self {
+        Self::new(JointKind::Hinge, a, b, anchor, anchor, axis)
+    }
+    pub fn slider(a: usize, b: usize, anchor: glm::Vec3, axis: glm::Vec3) -> Self {
+        Self::new(JointKind::Slider, a, b, anchor, anchor, axis)
+    }
+    pub fn fixed(a: usize, b: usize, anchor: glm::Vec3) -> Self {
+        Self::new(
+            JointKind::Fixed,
+            a,
+            b,
+            anchor,
+            anchor,
+            glm::vec3(0.0, 1.0, 0.0),
+        )
+    }
+    // bară rigidă cu lungimea de acum dintre ancore
+    pub fn distance(a: usize, b: usize, anchor_a: glm::Vec3, anchor_b: glm::Vec3) -> Self {
+        Self::spring(a, b, anchor_a, anchor_b, 0.0, 0.0)
+    }
+    // `stiffness` în N/m, `damping` în N·s/m
+    pub fn spring(
+        a: usize,
+        b: usize,
+        anchor_a: glm::Vec3,
+        anchor_b: glm::Vec3,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        let kind = JointKind::Distance {
+            length: glm::distance(&anchor_a, &anchor_b),
+            stiffness,
+            damping,
+        };
+        Self::new(kind, a, b, anchor_a, anchor_b, anchor_b - anchor_a)
+    }
+    pub fn with_limit(mut self, lower: f32, upper: f32) -> Self {
+        self.limit = Some((lower, upper));
+        self
+    }
+    pub fn with_motor(mut self, speed: f32, max_force: f32) -> Self {
+        self.motor = Some(Motor::new(speed, max_force));
+        self
+    }
+
+    /*
+    Schimbă corpurile între ele. Axa se întoarce, așa că unghiul balamalei, deplasarea
+    sliderului, limitele și sensul motorului rămân aceleași.
+    */
+    pub fn swap_bodies(&mut self) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        std::mem::swap(&mut self.anchor_a, &mut self.anchor_b);
+        self.axis = -self.axis;
+    }
+    // trece ancorele și axa în spațiul corpurilor, apelat de add_joint
+    pub fn attach(&mut self, a: &RigidBody, b: &RigidBody) {
+        let (rot_a, rot_b) = (a.rotation(), b.rotation());
+        self.local_anchor_a = rot_a.transpose() * (self.anchor_a - a.position());
+        self.local_anchor_b = rot_b.transpose() * (self.anchor_b - b.position());
+        self.local_axis_a = rot_a.transpose() * self.axis;
+        self.local_axis_b = rot_b.transpose() * self.axis;
+        let normal = perpendicular(&self.axis);
+        self.local_normal_a = rot_a.transpose() * normal;
+        self.local_normal_b = rot_b.transpose() * normal;
+        self.reference = rot_a.transpose() * rot_b;
+        self.impulses = [0.0; SLOTS];
+    }
+    // unghiul balamalei în jurul axei, în radiani
+    pub fn angle(&self, a: &RigidBody, b: &RigidBody) -> f32 {
+        let axis = a.rotation() * self.local_axis_a;
+        let normal_a = a.rotation() * self.local_normal_a;
+        let normal_b = b.rotation() * self.local_normal_b;
+        glm::dot(&glm::cross(&normal_a, &normal_b), &axis).atan2(glm::dot(&normal_a, &normal_b))
+    }
+    // cât a alunecat ancora lui b pe axă
+    pub fn translation(&self, a: &RigidBody, b: &RigidBody) -> f32 {
+        let axis = a.rotation() * self.local_axis_a;
+        glm::dot(&(self.world_anchor_b(b) - self.world_anchor_a(a)), &axis)
+    }
+    pub fn world_anchor_a(&self, a: &RigidBody) -> glm::Vec3 {
+        a.position() + a.rotation() * self.local_anchor_a
+    }
+    pub fn world_anchor_b(&self, b: &RigidBody) -> glm::Vec3 {
+        b.position() + b.rotation() * self.local_anchor_b
+    }
+
+    // construim rândurile pentru pasul ăsta; corpurile statice sau adormite nu se mișcă
+    pub fn prepare(&mut self, a: &RigidBody, b: &RigidBody, dt: f32) {
+        self.rows.clear();
+        let still = |body: &RigidBody| body.is_static() || body.is_sleeping();
+        if still(a) && still(b) {
+            return;
+        }
+        self.inverse_mass = [a.inverse_mass(), b.inverse_mass()];
+        self.inverse_inertia = [a.inverse_inertia(), b.inverse_inertia()];
+
+        let (rot_a, rot_b) = (a.rotation(), b.rotation());
+        let ra = rot_a * self.local_anchor_a;
+        let rb = rot_b * self.local_anchor_b;
+        let d = (b.position() + rb) - (a.position() + ra);
+        let axis = rot_a * self.local_axis_a;
+
+        match self.kind {
+            JointKind::Ball => {
+                self.point_rows(&ra, &rb, &d, dt);
+                let axis_b = rot_b * self.local_axis_b;
+                let swing = glm::cross(&axis, &axis_b);
+                let length = glm::length(&swing);
+                if let Some(limit) = self.limit
+                    && length > 1e-4
+                {
+                    let n = swing / length;
+                    let angle = length.atan2(glm::dot(&axis, &axis_b));
+                    self.limit_rows(angle, glm::Vec3::zeros(), n, n, limit, dt);
+                }
+                self.motor_row(glm::Vec3::zeros(), axis, axis, dt);
+            }
+            JointKind::Hinge => {
+                self.point_rows(&ra, &rb, &d, dt);
+                // axa lui b rămâne pe axa lui a
+                let axis_b = rot_b * self.local_axis_b;
+                let t1 = rot_a * self.local_normal_a;
+                let t2 = glm::cross(&axis, &t1);
+                for (k, t) in [t1, t2].iter().enumerate() {
+                    let j = glm::cross(&axis_b, t);
+                    let error = glm::dot(t, &axis_b);
+                    self.push_row(ANGLE + k, glm::Vec3::zeros(), j, j, -BAUMGARTE * error / dt);
+                }
+                if let Some(limit) = self.limit {
+                    let angle = self.angle(a, b);
+                    self.limit_rows(angle, glm::Vec3::zeros(), axis, axis, limit, dt);
+                }
+                self.motor_row(glm::Vec3::zeros(), axis, axis, dt);
+            }
+            JointKind::Distance {
+                length,
+                stiffness,
+                damping,
+            } => {
+                let current = glm::length(&d);
+                if current < 1e-6 {
+                    return;
+                }
+                let n = d / current;
+                let (ja, jb) = (glm::cross(&ra, &n), glm::cross(&rb, &n));
+                if stiffness > 0.0 {
+                    // arc moale, implicit (ca în Box2D), ca să nu explodeze la rigidități mari
+                    let gamma = dt * (damping + dt * stiffness);
+                    let gamma = if gamma > 0.0 { 1.0 / gamma } else { 0.0 };
+                    let bias = (current - length) * dt * stiffness * gamma;
+                    self.push_row(POINT, n, ja, jb, -bias);
+                    let row = self.rows.last_mut().unwrap();
+                    row.gamma = gamma;
+                    row.mass = effective_mass(
+                        &self.inverse_mass,
+                        &self.inverse_inertia,
+                        &n,
+                        &ja,
+                        &jb,
+                        gamma,
+                    );
+                } else if self.limit.is_none() {
+                    self.push_row(POINT, n, ja, jb, -BAUMGARTE * (current - length) / dt);
+                }
+                if let Some(limit) = self.limit {
+                    self.limit_rows(current, n, ja, jb, limit, dt);
+                }
+                self.motor_row(n, ja, jb, dt);
+            }
+            JointKind::Slider => {
+                self.angle_rows(&rot_a, &rot_b, dt);
+                // ancora lui b stă pe dreapta prin ancora lui a, pe axă
+                let arm = ra + d;
+                let t1 = rot_a * self.local_normal_a;
+                let t2 = glm::cross(&axis, &t1);
+                for (k, t) in [t1, t2].iter().enumerate() {
+                    let (ja, jb) = (glm::cross(&arm, t), glm::cross(&rb, t));
+                    let error = glm::dot(t, &d);
+                    self.push_row(POINT + k, *t, ja, jb, -BAUMGARTE * error / dt);
+                }
+                let (ja, jb) = (glm::cross(&arm, &axis), glm::cross(&rb, &axis));
+                if let Some(limit) = self.limit {
+                    self.limit_rows(glm::dot(&d, &axis), axis, ja, jb, limit, dt);
+                }
+                self.motor_row(axis, ja, jb, dt);
+            }
+            JointKind::Fixed => {
+                self.point_rows(&ra, &rb, &d, dt);
+                self.angle_rows(&rot_a, &rot_b, dt);
+            }
+        }
+    }
+    pub fn warm_start(&self, a: &mut RigidBody, b: &mut RigidBody) {
+        for row in &self.rows {
+            self.apply(a, b, row, row.impulse);
+        }
+    }
+    pub fn solve(&mut self, a: &mut RigidBody, b: &mut RigidBody) {
+        for k in 0..self.rows.len() {
+            let row = self.rows[k];
+            let velocity = glm::dot(&row.linear, &(b.velocity - a.velocity))
+                + glm::dot(&row.angular_b, &b.angular_velocity)
+                - glm::dot(&row.angular_a, &a.angular_velocity);
+            let lambda = row.mass * (row.rhs - velocity - row.gamma * row.impulse);
+            let impulse = (row.impulse + lambda).clamp(row.lower, row.upper);
+            let lambda = impulse - row.impulse;
+            self.rows[k].impulse = impulse;
+            self.impulses[row.slot] = impulse;
+            self.apply(a, b, &row, lambda);
+        }
+    }
+
+    fn apply(&self, a: &mut RigidBody, b: &mut RigidBody, row: &Row, lambda: f32) {
+        a.velocity -= row.linear * (lambda * self.inverse_mass[0]);
+        a.angular_velocity -= self.inverse_inertia[0] * row.angular_a * lambda;
+        b.velocity += row.linear * (lambda * self.inverse_mass[1]);
+        b.angular_velocity += self.inverse_inertia[1] * row.angular_b * lambda;
+    }
+    fn push_row(
+        &mut self,
+        slot: usize,
+        linear: glm::Vec3,
+        angular_a: glm::Vec3,
+        angular_b: glm::Vec3,
+        rhs: f32,
+    ) {
+        let mass = effective_mass(
+            &self.inverse_mass,
+            &self.inverse_inertia,
+            &linear,
+            &angular_a,
+            &angular_b,
+            0.0,
+        );
+        self.rows.push(Row {
+            slot,
+            linear,
+            angular_a,
+            angular_b,
+            mass,
+            rhs,
+            gamma: 0.0,
+            lower: f32::NEG_INFINITY,
+            upper: f32::INFINITY,
+            impulse: self.impulses[slot],
+        });
+    }
+    // ancorele coincid, pe cele trei axe ale lumii
+    fn point_rows(&mut self, ra: &glm::Vec3, rb: &glm::Vec3, d: &glm::Vec3, dt: f32) {
+        for k in 0..3 {
+            let mut e = glm::Vec3::zeros();
+            e[k] = 1.0;
+            let (ja, jb) = (glm::cross(ra, &e), glm::cross(rb, &e));
+            self.push_row(POINT + k, e, ja, jb, -BAUMGARTE * d[k] / dt);
+        }
+    }
+    // rotația relativă rămâne cea de la add_joint
+    fn angle_rows(&mut self, rot_a: &glm::Mat3, rot_b: &glm::Mat3, dt: f32) {
+        // cât e rotit b față de unde ar trebui, ca vector de rotație mic
+        let e = rot_b * (rot_a * self.reference).transpose();
+        let error = glm::vec3(
+            e[(2, 1)] - e[(1, 2)],
+            e[(0, 2)] - e[(2, 0)],
+            e[(1, 0)] - e[(0, 1)],
+        ) * 0.5;
+        for k in 0..3 {
+            let mut axis = glm::Vec3::zeros();
+            axis[k] = 1.0;
+            self.push_row(
+                ANGLE + k,
+                glm::Vec3::zeros(),
+                axis,
+                axis,
+                -BAUMGARTE * error[k] / dt,
+            );
+        }
+    }
+    /*
+    Limitele împing doar înapoi spre interval. Până la limită lăsăm viteza care o atinge
+    exact la sfârșitul pasului, peste ea corectăm ca la restul erorilor.
+    */
+    fn limit_rows(
+        &mut self,
+        value: f32,
+        linear: glm::Vec3,
+        angular_a: glm::Vec3,
+        angular_b: glm::Vec3,
+        (lower, upper): (f32, f32),
+        dt: f32,
+    ) {
+        let error = value - lower;
+        let rhs = if error < 0.0 {
+            -BAUMGARTE * error / dt
+        } else {
+            -error / dt
+        };
+        self.push_row(LOWER, linear, angular_a, angular_b, rhs);
+        let row = self.rows.last_mut().unwrap();
+        row.lower = 0.0;
+        row.impulse = row.impulse.max(0.0);
+
+        let error = value - upper;
+        let rhs = if error > 0.0 {
+            -BAUMGARTE * error / dt
+        } else {
+            -error / dt
+        };
+        self.push_row(UPPER, linear, angular_a, angular_b, rhs);
+        let row = self.rows.last_mut().unwrap();
+        row.upper = 0.0;
+        row.impulse = row.impulse.min(0.0);
+    }
+    fn motor_row(
+        &mut self,
+        linear: glm::Vec3,
+        angular_a: glm::Vec3,
+        angular_b: glm::Vec3,
+        dt: f32,
+    ) {
+        let Some(motor) = self.motor else {
+            return;
+        };
+        self.push_row(MOTOR, linear, angular_a, angular_b, motor.speed);
+        let row = self.rows.last_mut().unwrap();
+        row.lower = -motor.max_force * dt;
+        row.upper = motor.max_force * dt;
+        row.impulse = row.impulse.clamp(row.lower, row.upper);
+    }
+}
+
+fn effective_mass(
+    inverse_mass: &[f32; 2],
+    inverse_inertia: &[glm::Mat3; 2],
+    linear: &glm::Vec3,
+    angular_a: &glm::Vec3,
+    angular_b: &glm::Vec3,
+    gamma: f32,
+) -> f32 {
+    let k = (inverse_mass[0] + inverse_mass[1]) * glm::length2(linear)
+        + glm::dot(angular_a, &(inverse_inertia[0] * angular_a))
+        + glm::dot(angular_b, &(inverse_inertia[1] * angular_b))
+        + gamma;
+    if k > 1e-9 { 1.0 / k } else { 0.0 }
+}
+
+fn perpendicular(n: &glm::Vec3) -> glm::Vec3 {
+    let t = if n.x.abs() < 0.57 {
+        glm::cross(n, &glm::vec3(1.0, 0.0, 0.0))
+    } else {
+        glm::cross(n, &glm::vec3(0.0, 1.0, 0.0))
+    };
+    glm::normalize(&t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::Collider;
+    use crate::seb::physics::{PhysicsWorld, TERRAIN};
+    use crate::seb::primitives::{Rectangle, Sphere};
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn run(world: &mut PhysicsWorld, steps: usize) {
+        for _ in 0..steps {
+            world.step(DT);
+        }
+    }
+
+    fn cuboid(position: glm::Vec3, scale: glm::Vec3) -> RigidBody {
+        let mut r = Rectangle::new();
+        r.position = position;
+        r.scale = scale;
+        let mut body = RigidBody::new(Collider::Rectangle(r), 1.0);
+        body.can_sleep = false;
+        body
+    }
+
+    fn ball(position: glm::Vec3) -> RigidBody {
+        let mut s = Sphere::new();
+        s.position = position;
+        s.scale = 0.25;
+        let mut body = RigidBody::new(Collider::Sphere(s), 1.0);
+        body.can_sleep = false;
+        body
+    }
+
+    #[test]
+    fn hinge_stops_at_its_limits() {
+        let mut world = PhysicsWorld::new();
+        // bară orizontală prinsă de lume la un capăt
+        let bar = world.add_body(cuboid(glm::vec3(1.0, 5.0, 0.0), glm::vec3(1.0, 0.1, 0.1)));
+        let hinge = world.add_joint(
+            Joint::hinge(
+                TERRAIN,
+                bar,
+                glm::vec3(0.0, 5.0, 0.0),
+                glm::vec3(0.0, 0.0, 1.0),
+            )
+            .with_limit(-0.5, 0.3),
+        );
+        // cade spre unghiuri negative, până la limita de jos
+        run(&mut world, 180);
+        let angle = world.joint_angle(hinge);
+        assert!((angle + 0.5).abs() < 0.02, "unghiul {}", angle);
+
+        world.gravity = -world.gravity;
+        run(&mut world, 180);
+        let angle = world.joint_angle(hinge);
+        assert!((angle - 0.3).abs() < 0.02, "unghiul {}", angle);
+        // ancora a rămas pe loc
+        let (a, b) = world.joint_anchors(hinge);
+        assert!(glm::distance(&a, &b) < 0.01);
+    }
+
+    #[test]
+    fn hinge_motor_reaches_its_speed() {
+        let mut world = PhysicsWorld::new();
+        world.gravity = glm::Vec3::zeros();
+        let wheel = world.add_body(cuboid(glm::Vec3::zeros(), glm::vec3(0.5, 0.5, 0.1)));
+        let hinge = world.add_joint(
+            Joint::hinge(TERRAIN, wheel, glm::Vec3::zeros(), glm::vec3(0.0, 0.0, 1.0))
+                .with_motor(2.0, 50.0),
+        );
+        run(&mut world, 60);
+        let w = world.body(wheel).angular_velocity;
+        assert!((w.z - 2.0).abs() < 0.02, "{:?}", w);
+        assert!(w.x.abs() < 1e-3 && w.y.abs() < 1e-3);
+        // unghiul crește în sensul motorului
+        let before = world.joint_angle(hinge);
+        world.step(DT);
+        let turned = world.joint_angle(hinge) - before;
+        assert!((turned - 2.0 * DT).abs() < 1e-3, "{}", turned);
+    }
+
+    #[test]
+    fn slider_stops_at_its_limits() {
+        let mut world = PhysicsWorld::new();
+        let start = glm::vec3(0.0, 5.0, 0.0);
+        let axis = glm::normalize(&glm::vec3(1.0, -1.0, 0.0));
+        let block = world.add_body(cuboid(start, glm::vec3(0.2, 0.2, 0.2)));
+        let slider =
+            world.add_joint(Joint::slider(TERRAIN, block, start, axis).with_limit(-0.5, 0.5));
+        run(&mut world, 180);
+        let body = world.body(block);
+        let offset = body.position() - start;
+        // alunecă pe axă până la capăt, fără să se rotească
+        assert!((glm::length(&offset) - 0.5).abs() < 0.02, "{:?}", offset);
+        assert!(glm::length(&glm::cross(&offset, &axis)) < 0.01);
+        assert!((world.joint_translation(slider).abs() - 0.5).abs() < 0.02);
+        assert!((body.rotation() - glm::Mat3::identity()).norm() < 1e-3);
+    }
+
+    #[test]
+    fn spring_settles_at_its_length() {
+        let mut world = PhysicsWorld::new();
+        world.gravity = glm::Vec3::zeros();
+        let anchor = glm::vec3(0.0, 1.0, 0.0);
+        let end = glm::vec3(2.0, 1.0, 0.0);
+        let weight = world.add_body(ball(end));
+        world.add_joint(Joint::spring(TERRAIN, weight, anchor, end, 50.0, 2.0));
+        // întins și tras într-o parte
+        world
+            .body_mut(weight)
+            .set_position(glm::vec3(3.0, 1.5, 0.0));
+        run(&mut world, 600);
+        let body = world.body(weight);
+        let length = glm::distance(&body.position(), &anchor);
+        assert!((length - 2.0).abs() < 0.01, "lungimea {}", length);
+        assert!(glm::length(&body.velocity) < 0.01);
+    }
+
+    #[test]
+    fn rope_does_not_stretch() {
+        let mut world = PhysicsWorld::new();
+        let anchor = glm::vec3(0.0, 5.0, 0.0);
+        let end = glm::vec3(0.0, 3.0, 0.0);
+        let weight = world.add_body(ball(end));
+        world.add_joint(Joint::distance(TERRAIN, weight, anchor, end).with_limit(0.0, 2.0));
+        // aruncat în sus și într-o parte: frânghia se strânge, apoi se întinde
+        world.body_mut(weight).velocity = glm::vec3(3.0, 4.0, 0.0);
+        let (mut shortest, mut longest) = (f32::MAX, 0.0f32);
+        for _ in 0..300 {
+            world.step(DT);
+            let length = glm::distance(&world.body(weight).position(), &anchor);
+            shortest = shortest.min(length);
+            longest = longest.max(length);
+        }
+        assert!(longest < 2.02, "s-a întins la {}", longest);
+        assert!(shortest < 1.8, "nu s-a strâns, minim {}", shortest);
+    }
+
+    #[test]
+    fn fixed_keeps_its_relative_pose() {
+        let mut world = PhysicsWorld::new();
+        let a = world.add_body(cuboid(glm::vec3(0.0, 5.0, 0.0), glm::vec3(0.5, 0.5, 0.5)));
+        let mut other = cuboid(glm::vec3(1.0, 5.2, 0.0), glm::vec3(0.5, 0.3, 0.3));
+        other
+            .collider
+            .set_rotation(&crate::seb::collision::euler_to_matrix(0.0, 30.0, 0.0));
+        let b = world.add_body(other);
+        let pose = |world: &PhysicsWorld| {
+            let (a, b) = (world.body(a), world.body(b));
+            let inverse = a.rotation().transpose();
+            (
+                inverse * (b.position() - a.position()),
+                inverse * b.rotation(),
+            )
+        };
+        let (offset, rotation) = pose(&world);
+        world.add_joint(Joint::fixed(a, b, glm::vec3(0.5, 5.0, 0.0)));
+        // aruncate împreună, rotindu-se
+        world.body_mut(a).velocity = glm::vec3(1.0, 3.0, 0.0);
+        world.body_mut(a).angular_velocity = glm::vec3(0.5, 2.0, -1.0);
+        run(&mut world, 120);
+        let (new_offset, new_rotation) = pose(&world);
+        assert!(
+            glm::distance(&offset, &new_offset) < 0.02,
+            "{:?}",
+            new_offset
+        );
+        assert!((rotation - new_rotation).norm() < 0.02);
+        // au zburat și s-au rotit de-a binelea
+        assert!((world.body(a).rotation() - glm::Mat3::identity()).norm() > 0.5);
+    }
+
+    #[test]
+    fn joined_bodies_do_not_collide_until_the_joint_goes() {
+        let mut world = PhysicsWorld::new();
+        world.gravity = glm::Vec3::zeros();
+        let a = world.add_body(ball(glm::vec3(0.0, 0.0, 0.0)));
+        let b = world.add_body(ball(glm::vec3(0.2, 0.0, 0.0)));
+        let joint = world.add_joint(Joint::ball(a, b, glm::vec3(0.1, 0.0, 0.0)));
+        world.step(DT);
+        assert!(world.contacts().is_empty());
+
+        world.joint_mut(joint).collide_connected = true;
+        world.step(DT);
+        assert!(!world.contacts().is_empty());
+        world.joint_mut(joint).collide_connected = false;
+        world.step(DT);
+        assert!(world.contacts().is_empty());
+
+        world.remove_joint(joint);
+        world.step(DT);
+        assert!(!world.contacts().is_empty());
+    }
+}
@@ -2,12 +2,34 @@
 pub mod seb;
 pub mod window;
 pub mod player;
+pub mod character;
 pub mod primitives;
 pub mod collision;
+pub mod broadphase;
+pub mod bvh;
+pub mod ccd;
+pub mod debug_draw;
+pub mod framebuffer;
+pub mod gjk;
+pub mod golden;
+pub mod gravity;
+pub mod hull;
+pub mod heightfield;
+pub mod input;
+pub mod joints;
+pub mod physics;
+pub mod raycast;
+pub mod replay;
 pub mod gltfmodel;
 pub mod model;
+pub mod orbit;
 pub mod planet;
+pub mod preprocessor;
+pub mod shader_registry;
 pub mod skybox;
+pub mod storage_buffer;
 pub mod test;
+pub mod timestep;
+pub mod uniforms;
 pub mod gui;
 
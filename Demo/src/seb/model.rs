@@ -1,14 +1,24 @@
 
 
 use crate::seb::{
+    framebuffer,
     gltfmodel::GLTFModel,
-    seb::{ToCStr, create_shader_from},
+    preprocessor::Preprocessor,
+    seb::{ShaderError, create_shader_from},
+    uniforms::{self, LightBlock, UniformCache},
 };
 use nalgebra_glm as glm;
 
+// shaderele tuturor modelelor; ShaderRegistry le poate reîncărca din mers
+pub const VERTEX_SHADER: &str = "./assets/model/shaders/vertex.glsl";
+pub const FRAGMENT_SHADER: &str = "./assets/model/shaders/fragment.glsl";
+// varianta folosită de Model: cu umbre din Light
+pub const DEFINES: &[(&str, &str)] = &[("SHADOWS", "1")];
+
 pub struct Model {
     pub gltf_model: GLTFModel,
     pub shader_id: u32,
+    uniforms: UniformCache,
 }
 impl Model {
     pub fn new(file: &str) -> Self {
@@ -18,6 +28,7 @@ impl Model {
         Self {
             gltf_model: model,
             shader_id: 0,
+            uniforms: UniformCache::new(),
         }
     }
     pub fn apply_animation(&mut self, index: usize, time: f32) {
@@ -100,13 +111,21 @@ impl Model {
             }
         }
     }
-    fn init_shaders(&mut self) {
-        let vshader = std::fs::read_to_string("./assets/model/shaders/vertex.glsl").unwrap();
-        let fshader = std::fs::read_to_string("./assets/model/shaders/fragment.glsl").unwrap();
-        self.shader_id = create_shader_from(&vshader, &fshader);
+    /*
+    Programul e păstrat în `shaders`, deci modelele cu același preprocesor îl compilează o
+    singură dată; preprocesorul trebuie să trăiască cât modelele, la drop șterge programele.
+    */
+    pub fn init(&mut self, shaders: &mut Preprocessor) -> Result<(), ShaderError> {
+        self.shader_id = shaders.program(VERTEX_SHADER, FRAGMENT_SHADER, DEFINES)?;
+        self.init_meshes();
+        Ok(())
+    }
+    fn location(&self, name: &str) -> i32 {
+        self.uniforms.location(self.shader_id, name)
     }
-    pub fn init(&mut self) {
-        self.init_shaders();
+    // ca init, dar cu un program care nu e al modelului (de obicei din ShaderRegistry)
+    pub fn init_with_shader(&mut self, shader_id: u32) {
+        self.shader_id = shader_id;
         self.init_meshes();
     }
     pub fn draw_for_shadow(&self, light: &Light) {
@@ -124,91 +143,41 @@ impl Model {
             }
         }
     }
-    pub fn draw(&self, projection: glm::Mat4, view: glm::Mat4, eye: glm::Vec3, light: Light) {
+    /*
+    projection, view și poziția camerei vin din blocul Camera, pe care modelul nu îl scrie:
+    cine desenează face înainte `camera.update(&CameraBlock::new(..))` pe un
+    UniformBuffer legat la CAMERA_BINDING, altfel modelul e desenat cu ce a rămas acolo.
+    */
+    pub fn draw(&self, light: &Light) {
+        debug_assert!(
+            uniforms::bound_buffer(uniforms::CAMERA_BINDING) != 0,
+            "Model::draw fără blocul Camera scris"
+        );
         unsafe {
             gl::UseProgram(self.shader_id);
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "projection".c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                projection.as_ptr(),
-            );
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "view".c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                view.as_ptr(),
-            );
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "lightSpaceMatrix".c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                light.light_space_matrix.as_ptr(),
-            );
-            gl::Uniform3fv(
-                gl::GetUniformLocation(self.shader_id, "viewPos".c_str().as_ptr()),
-                1,
-                eye.as_ptr(),
-            );
-            gl::Uniform3fv(
-                gl::GetUniformLocation(self.shader_id, "lightPos".c_str().as_ptr()),
-                1,
-                light.light_pos.as_ptr(),
-            );
+            light.bind_block();
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, light.depth_texture);
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.shader_id, "shadowMap".c_str().as_ptr() as *const _),
-                0,
-            );
+            gl::Uniform1i(self.location("shadowMap"), 0);
             for mesh in &self.gltf_model.meshes {
                 let model = mesh.translation * mesh.rotation * mesh.scale;
-                gl::UniformMatrix4fv(
-                    gl::GetUniformLocation(self.shader_id, "model".c_str().as_ptr()),
-                    1,
-                    gl::FALSE,
-                    model.as_ptr(),
-                );
+                gl::UniformMatrix4fv(self.location("model"), 1, gl::FALSE, model.as_ptr());
 
                 if let Some(mat) = &mesh.material {
                     gl::Uniform3fv(
-                        gl::GetUniformLocation(self.shader_id, "baseColor".c_str().as_ptr()),
+                        self.location("baseColor"),
                         1,
                         mat.base_color_factor.as_ptr(),
                     );
-                    gl::Uniform1f(
-                        gl::GetUniformLocation(self.shader_id, "metallic".c_str().as_ptr()),
-                        mat.metallic_factor,
-                    );
-                    gl::Uniform1f(
-                        gl::GetUniformLocation(self.shader_id, "roughness".c_str().as_ptr()),
-                        mat.roughness_factor,
-                    );
+                    gl::Uniform1f(self.location("metallic"), mat.metallic_factor);
+                    gl::Uniform1f(self.location("roughness"), mat.roughness_factor);
                     if let Some(index) = mat.base_color_texture {
                         gl::ActiveTexture(gl::TEXTURE1); // activezi slotul 0
                         gl::BindTexture(gl::TEXTURE_2D, self.gltf_model.textures_map[&index]); // faci bind la textura ta în slotul 0
-                        gl::Uniform1i(
-                            gl::GetUniformLocation(
-                                self.shader_id,
-                                "baseColorTexture".c_str().as_ptr() as *const i8,
-                            ),
-                            1,
-                        );
-                        gl::Uniform1i(
-                            gl::GetUniformLocation(
-                                self.shader_id,
-                                "useBaseColorTexture".c_str().as_ptr() as *const i8,
-                            ),
-                            1,
-                        );
+                        gl::Uniform1i(self.location("baseColorTexture"), 1);
+                        gl::Uniform1i(self.location("useBaseColorTexture"), 1);
                     } else {
-                        gl::Uniform1i(
-                            gl::GetUniformLocation(
-                                self.shader_id,
-                                "useBaseColorTexture".c_str().as_ptr() as *const i8,
-                            ),
-                            0,
-                        );
+                        gl::Uniform1i(self.location("useBaseColorTexture"), 0);
                     }
                 }
                 gl::BindVertexArray(mesh.vao);
@@ -236,8 +205,11 @@ pub struct Light {
     pub light_pos: glm::Vec3,
     pub light_target: glm::Vec3,
     pub light_space_matrix: glm::Mat4,
+    // uniform bufferul cu aceleași două, pentru shadere (blocul Light); 0 până la add_light
+    pub block: u32,
 
     pub shadow_shader: u32,
+    shadow_model_location: i32,
 }
 
 impl Light {
@@ -250,11 +222,13 @@ impl Light {
             light_pos: glm::vec3(0.0, 0.0, 0.0),
             light_target: glm::vec3(0.0, 0.0, 0.0),
             light_space_matrix: glm::Mat4::identity(),
+            block: 0,
             shadow_shader: 0,
+            shadow_model_location: -1,
         }
     }
 
-    pub fn init_shadow(&mut self, width: i32, height: i32) {
+    pub fn init_shadow(&mut self, width: i32, height: i32) -> Result<(), ShaderError> {
         self.width = width;
         self.height = height;
         unsafe {
@@ -314,30 +288,41 @@ impl Light {
 
             gl::DrawBuffer(gl::NONE);
             gl::ReadBuffer(gl::NONE);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer::default_framebuffer());
         }
 
         let shadow_vshader = r#"
                 #version 330 core
                 layout(location = 0) in vec3 aPos;
-                uniform mat4 light_matrix;
+                layout(std140) uniform Light
+                {
+                    mat4 lightSpaceMatrix;
+                    vec4 lightPos;
+                };
                 uniform mat4 model;
                 void main()
                 {
-                    gl_Position = light_matrix * model * vec4(aPos, 1.0);
+                    gl_Position = lightSpaceMatrix * model * vec4(aPos, 1.0);
                 }
                 "#;
         let shadow_fshader = r#"
                 #version 330 core
                 void main() {}
                 "#;
-        self.shadow_shader = create_shader_from(shadow_vshader, shadow_fshader);
+        self.shadow_shader = create_shader_from(shadow_vshader, shadow_fshader)?.id;
+        self.shadow_model_location = uniforms::reflect(self.shadow_shader)
+            .get("model")
+            .copied()
+            .unwrap_or(-1);
+        Ok(())
     }
 
     pub fn add_light(&mut self, light_pos: glm::Vec3, light_target: glm::Vec3) {
         self.light_pos = light_pos;
         self.light_target = light_target;
         self.light_space_matrix = Self::compute_light_space_matrix(&light_pos, &light_target);
+        let data = LightBlock::new(&self.light_space_matrix, &self.light_pos);
+        uniforms::upload(&mut self.block, uniforms::LIGHT_BINDING, &data);
     }
 
     fn compute_light_space_matrix(pos: &glm::Vec3, target: &glm::Vec3) -> glm::Mat4 {
@@ -356,31 +341,26 @@ impl Light {
             gl::Clear(gl::DEPTH_BUFFER_BIT);
 
             gl::UseProgram(self.shadow_shader);
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shadow_shader, "light_matrix".c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                self.light_space_matrix.as_ptr(),
-            );
         }
+        self.bind_block();
+    }
+
+    // când mai multe lumini își împart punctul LIGHT_BINDING
+    pub fn bind_block(&self) {
+        uniforms::bind_buffer(uniforms::LIGHT_BINDING, self.block);
     }
 
     pub fn bind_shadow_model(&self, model: glm::Mat4) {
         unsafe {
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shadow_shader, "model".c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                model.as_ptr(),
-            );
+            gl::UniformMatrix4fv(self.shadow_model_location, 1, gl::FALSE, model.as_ptr());
         }
     }
     pub fn unbind_shadow(&self) {
         unsafe {
             gl::CullFace(gl::BACK);
             //gl::Disable(gl::CULL_FACE);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
+        framebuffer::bind_default();
     }
 }
 #[repr(C)]
@@ -394,10 +374,12 @@ pub struct Map {
     vertices: Vec<Vertex>,
     indices: Vec<u32>,
     shader_id: u32,
+    uniforms: UniformCache,
     texture_id: u32,
     vao: u32,
     ebo: u32,
     vbo: u32,
+    height_range: (f32, f32),
     scale: f32,
 }
 
@@ -408,10 +390,12 @@ impl Map {
             vertices: Vec::new(),
             indices: Vec::new(),
             shader_id: 0,
+            uniforms: UniformCache::new(),
             texture_id: 0,
             vao: 0,
             ebo: 0,
             vbo: 0,
+            height_range: (0.0, 0.0),
             scale: 1.0,
         }
     }
@@ -449,6 +433,11 @@ impl Map {
             }
         }
         self.map = map;
+        self.height_range = self
+            .map
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
 
         Ok(())
     }
@@ -547,7 +536,7 @@ impl Map {
         self.indices = indices;
     }
 
-    pub fn init(&mut self) {
+    pub fn init(&mut self) -> Result<(), ShaderError> {
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
             gl::GenBuffers(1, &mut self.vbo);
@@ -682,7 +671,8 @@ impl Map {
                 fragColor = vec4(final_color, 1.0);
             }
         "#;
-        self.shader_id = create_shader_from(vshader, fshader);
+        self.shader_id = create_shader_from(vshader, fshader)?.id;
+        Ok(())
     }
     pub fn load_texture(&mut self, path: &str) -> Result<(), String> {
         let result = stb_image::image::load(path);
@@ -747,25 +737,25 @@ impl Map {
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "model".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "model"),
                 1,
                 gl::FALSE,
                 model.as_ptr(),
             );
             gl::Uniform3fv(
-                gl::GetUniformLocation(self.shader_id, "cam_pos".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "cam_pos"),
                 1,
                 cam_pos.as_ptr(),
             );
@@ -817,4 +807,13 @@ impl Map {
     pub fn height(&self) -> usize {
         self.map.len()
     }
+
+    // cea mai mică și cea mai mare valoare din hartă
+    pub fn height_range(&self) -> (f32, f32) {
+        self.height_range
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
 }
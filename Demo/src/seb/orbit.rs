@@ -0,0 +1,347 @@
+use crate::seb::planet::Planet;
+use nalgebra_glm as glm;
+
+const KEPLER_ITERATIONS: usize = 16;
+
+/*
+Elementele orbitale clasice ale unei elipse în jurul corpului central. Unghiurile sunt
+în grade, ca restul unghiurilor din proiect. Planul de referință e XZ (Y e sus).
+*/
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f32,
+    pub eccentricity: f32, // între 0 (cerc) și 1
+    pub inclination: f32,
+    pub ascending_node: f32,
+    pub periapsis: f32,    // argumentul periapsidei
+    pub mean_anomaly: f32, // unde e corpul pe orbită la momentul 0
+}
+
+impl OrbitalElements {
+    pub fn circular(radius: f32) -> Self {
+        Self {
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            ascending_node: 0.0,
+            periapsis: 0.0,
+            mean_anomaly: 0.0,
+        }
+    }
+    // `mu` e G * (M + m)
+    pub fn period(&self, mu: f32) -> f32 {
+        std::f32::consts::TAU * (self.semi_major_axis.powi(3) / mu).sqrt()
+    }
+    /*
+    Poziția și viteza față de corpul central după `time` secunde. Ecuația lui Kepler
+    (M = E - e sin E) se rezolvă cu Newton, în f64 ca să nu pierdem precizie la timpi mari.
+    */
+    pub fn state(&self, mu: f32, time: f64) -> (glm::Vec3, glm::Vec3) {
+        let a = self.semi_major_axis as f64;
+        let e = self.eccentricity.clamp(0.0, 0.99) as f64;
+        let mu = mu as f64;
+        let n = (mu / (a * a * a)).sqrt();
+        let m =
+            ((self.mean_anomaly as f64).to_radians() + n * time).rem_euclid(std::f64::consts::TAU);
+
+        let mut anomaly = if e > 0.8 { std::f64::consts::PI } else { m };
+        for _ in 0..KEPLER_ITERATIONS {
+            let delta = (anomaly - e * anomaly.sin() - m) / (1.0 - e * anomaly.cos());
+            anomaly -= delta;
+            if delta.abs() < 1e-12 {
+                break;
+            }
+        }
+        let (sin, cos) = anomaly.sin_cos();
+        let b = a * (1.0 - e * e).sqrt();
+        let r = a * (1.0 - e * cos);
+        let speed = (mu * a).sqrt() / r;
+
+        // în planul orbitei: x spre periapsidă, y în sensul mișcării
+        let position = glm::vec3((a * (cos - e)) as f32, (b * sin) as f32, 0.0);
+        let velocity = glm::vec3(
+            (-speed * sin) as f32,
+            (speed * (1.0 - e * e).sqrt() * cos) as f32,
+            0.0,
+        );
+        let rotation = self.rotation();
+        (rotation * position, rotation * velocity)
+    }
+    // din planul orbitei în lume: Ω în jurul polului, i în jurul nodului, ω în jurul polului
+    fn rotation(&self) -> glm::Mat3 {
+        let pole = glm::vec3(0.0, 0.0, 1.0);
+        let node = glm::vec3(1.0, 0.0, 0.0);
+        let m = glm::rotation(self.ascending_node.to_radians(), &pole)
+            * glm::rotation(self.inclination.to_radians(), &node)
+            * glm::rotation(self.periapsis.to_radians(), &pole);
+        // polul orbitelor (Z la astronomi) devine Y
+        let to_world = glm::mat3(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0);
+        to_world * glm::mat4_to_mat3(&m)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    // fiecare corp pe elipsa lui în jurul părintelui, exact dar fără atracții între vecini
+    Kepler,
+    // toate corpurile se atrag între ele, cu leapfrog (simplectic, energia nu derivă)
+    NBody,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CelestialBody {
+    pub mass: f32,
+    pub position: glm::Vec3,
+    pub velocity: glm::Vec3,
+    // părintele și orbita în jurul lui, folosite de Kepler; fără ele corpul stă pe loc
+    pub orbit: Option<(usize, OrbitalElements)>,
+    pub tilt: f32, // înclinarea axei de rotație, în grade
+    pub spin: f32, // grade pe secundă
+    pub angle: f32,
+}
+
+/*
+Un sistem solar mic care mută planetele: `update` avansează timpul simulat cu
+dt * time_scale, iar `apply` copiază poziția și rotația pe un `Planet`.
+*/
+pub struct SolarSystem {
+    pub bodies: Vec<CelestialBody>,
+    pub gravitational_constant: f32,
+    pub integrator: Integrator,
+    pub time_scale: f32,
+    // cel mai mare pas de integrare N-body, în timp simulat
+    pub max_step: f32,
+    // netezește atracția la distanțe foarte mici, ca două corpuri apropiate să nu explodeze
+    pub softening: f32,
+    time: f64,
+}
+
+impl SolarSystem {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            gravitational_constant: 1.0,
+            integrator: Integrator::Kepler,
+            time_scale: 1.0,
+            max_step: 0.01,
+            softening: 0.01,
+            time: 0.0,
+        }
+    }
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    pub fn add_body(&mut self, mass: f32, position: glm::Vec3, velocity: glm::Vec3) -> usize {
+        self.bodies.push(CelestialBody {
+            mass,
+            position,
+            velocity,
+            orbit: None,
+            tilt: 0.0,
+            spin: 0.0,
+            angle: 0.0,
+        });
+        self.bodies.len() - 1
+    }
+    // corp pe orbită în jurul lui `parent`, pornit din punctul de pe orbită de acum
+    pub fn add_orbit(&mut self, parent: usize, mass: f32, elements: OrbitalElements) -> usize {
+        let center = self.bodies[parent];
+        let mu = self.gravitational_constant * (center.mass + mass);
+        let (position, velocity) = elements.state(mu, self.time);
+        let index = self.add_body(mass, center.position + position, center.velocity + velocity);
+        self.bodies[index].orbit = Some((parent, elements));
+        index
+    }
+    pub fn update(&mut self, dt: f32) {
+        let dt = dt * self.time_scale;
+        if dt <= 0.0 {
+            return;
+        }
+        match self.integrator {
+            Integrator::Kepler => {
+                self.time += dt as f64;
+                self.update_kepler();
+            }
+            Integrator::NBody => {
+                let steps = (dt / self.max_step).ceil().max(1.0) as usize;
+                let h = dt / steps as f32;
+                for _ in 0..steps {
+                    self.leapfrog(h);
+                    self.time += h as f64;
+                }
+            }
+        }
+        for body in &mut self.bodies {
+            body.angle = (body.angle + body.spin * dt).rem_euclid(360.0);
+        }
+    }
+    // energia totală, ar trebui să rămână aproape constantă
+    pub fn energy(&self) -> f32 {
+        let g = self.gravitational_constant;
+        let mut energy = 0.0;
+        for (i, a) in self.bodies.iter().enumerate() {
+            energy += 0.5 * a.mass * glm::length2(&a.velocity);
+            for b in &self.bodies[i + 1..] {
+                let distance = (glm::distance2(&a.position, &b.position)
+                    + self.softening * self.softening)
+                    .sqrt();
+                energy -= g * a.mass * b.mass / distance;
+            }
+        }
+        energy
+    }
+    // axa de rotație e Y-ul planetei, înclinat în jurul lui Z
+    pub fn apply(&self, index: usize, planet: &mut Planet) {
+        let body = &self.bodies[index];
+        planet.position = body.position;
+        planet.x_angle = 0.0;
+        planet.y_angle = body.angle;
+        planet.z_angle = body.tilt;
+    }
+
+    // părinții sunt adăugați înaintea copiilor, deci sunt deja mutați când ajungem la copii
+    fn update_kepler(&mut self) {
+        for i in 0..self.bodies.len() {
+            let Some((parent, elements)) = self.bodies[i].orbit else {
+                continue;
+            };
+            let center = self.bodies[parent];
+            let mu = self.gravitational_constant * (center.mass + self.bodies[i].mass);
+            let (position, velocity) = elements.state(mu, self.time);
+            self.bodies[i].position = center.position + position;
+            self.bodies[i].velocity = center.velocity + velocity;
+        }
+    }
+    // kick-drift-kick
+    fn leapfrog(&mut self, h: f32) {
+        let accelerations = self.compute_accelerations();
+        for (body, acceleration) in self.bodies.iter_mut().zip(&accelerations) {
+            body.velocity += acceleration * (h * 0.5);
+            body.position += body.velocity * h;
+        }
+        let accelerations = self.compute_accelerations();
+        for (body, acceleration) in self.bodies.iter_mut().zip(&accelerations) {
+            body.velocity += acceleration * (h * 0.5);
+        }
+    }
+    fn compute_accelerations(&self) -> Vec<glm::Vec3> {
+        let g = self.gravitational_constant;
+        let epsilon = self.softening * self.softening;
+        let mut accelerations = vec![glm::Vec3::zeros(); self.bodies.len()];
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let (a, b) = (&self.bodies[i], &self.bodies[j]);
+                let offset = b.position - a.position;
+                let distance2 = glm::length2(&offset) + epsilon;
+                let pull = offset * (g / (distance2 * distance2.sqrt()));
+                accelerations[i] += pull * b.mass;
+                accelerations[j] -= pull * a.mass;
+            }
+        }
+        accelerations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near(a: &glm::Vec3, b: &glm::Vec3, tolerance: f32) -> bool {
+        glm::distance(a, b) < tolerance
+    }
+
+    fn ellipse(a: f32, e: f32, mean_anomaly: f32) -> OrbitalElements {
+        OrbitalElements {
+            eccentricity: e,
+            mean_anomaly,
+            ..OrbitalElements::circular(a)
+        }
+    }
+
+    // anomalia medie citită înapoi din poziție, ca să verificăm ecuația lui Kepler
+    fn mean_anomaly_of(elements: &OrbitalElements, position: &glm::Vec3) -> f64 {
+        let (a, e) = (
+            elements.semi_major_axis as f64,
+            elements.eccentricity as f64,
+        );
+        let b = a * (1.0 - e * e).sqrt();
+        // fără înclinare planul orbitei e XZ, cu y-ul orbitei spre -Z
+        let (x, y) = (position.x as f64, -position.z as f64);
+        let anomaly = (y / b).atan2(x / a + e);
+        (anomaly - e * anomaly.sin()).rem_euclid(2.0 * PI)
+    }
+
+    #[test]
+    fn circular_orbit_positions() {
+        let elements = OrbitalElements::circular(2.0);
+        let period = elements.period(1.0) as f64;
+        let (position, velocity) = elements.state(1.0, 0.0);
+        assert!(near(&position, &glm::vec3(2.0, 0.0, 0.0), 1e-5));
+        assert!(near(
+            &velocity,
+            &glm::vec3(0.0, 0.0, -(0.5f32).sqrt()),
+            1e-5
+        ));
+
+        let (position, _) = elements.state(1.0, period * 0.25);
+        assert!(near(&position, &glm::vec3(0.0, 0.0, -2.0), 1e-4));
+        let (position, _) = elements.state(1.0, period * 0.5);
+        assert!(near(&position, &glm::vec3(-2.0, 0.0, 0.0), 1e-4));
+        let (position, _) = elements.state(1.0, period);
+        assert!(near(&position, &glm::vec3(2.0, 0.0, 0.0), 1e-4));
+    }
+
+    #[test]
+    fn eccentric_orbit_apsides() {
+        let elements = ellipse(1.0, 0.9, 0.0);
+        let period = elements.period(1.0) as f64;
+        // periapsida la a(1 - e), apoapsida la a(1 + e)
+        let (position, velocity) = elements.state(1.0, 0.0);
+        assert!(near(&position, &glm::vec3(0.1, 0.0, 0.0), 1e-5));
+        // vis-viva: v² = mu (2 / r - 1 / a)
+        assert!((glm::length2(&velocity) - 19.0).abs() < 1e-3);
+        let (position, velocity) = elements.state(1.0, period * 0.5);
+        assert!(near(&position, &glm::vec3(-1.9, 0.0, 0.0), 1e-4));
+        assert!((glm::length2(&velocity) - (2.0 / 1.9 - 1.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn kepler_equation_near_apoapsis() {
+        // în jurul lui M = π, Newton pornit din M sau din π trebuie să conveargă la fel
+        for e in [0.0, 0.5, 0.85, 0.99] {
+            for degrees in [179.0, 179.99, 180.0, 180.01, 181.0] {
+                let elements = ellipse(1.0, e, degrees);
+                let (position, velocity) = elements.state(1.0, 0.0);
+                let m = mean_anomaly_of(&elements, &position);
+                assert!(
+                    (m - (degrees as f64).to_radians()).abs() < 1e-5,
+                    "e = {}, M = {}: {}",
+                    e,
+                    degrees,
+                    m.to_degrees()
+                );
+                let r = glm::length(&position);
+                assert!((glm::length2(&velocity) - (2.0 / r - 1.0)).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn leapfrog_keeps_energy() {
+        let mut system = SolarSystem::new();
+        system.integrator = Integrator::NBody;
+        let sun = system.add_body(1000.0, glm::Vec3::zeros(), glm::Vec3::zeros());
+        system.add_orbit(sun, 1.0, OrbitalElements::circular(10.0));
+        system.add_orbit(sun, 0.5, ellipse(16.0, 0.3, 90.0));
+        let start = system.energy();
+
+        // câteva zeci de ture pentru planeta interioară
+        let mut drift: f32 = 0.0;
+        for _ in 0..20_000 {
+            system.update(0.01);
+            drift = drift.max(((system.energy() - start) / start).abs());
+        }
+        assert!(drift < 1e-3, "energia a variat cu {}", drift);
+    }
+}
@@ -0,0 +1,1294 @@
+use crate::seb::broadphase::DynamicTree;
+use crate::seb::bvh::MeshBvh;
+use crate::seb::ccd::{self, Motion, TimeOfImpact};
+use crate::seb::collision::{
+    Collider, CollisionFilter, ContactManifold, Feature, collide_manifold, collide_pair,
+};
+use crate::seb::gravity::{self, GravityField};
+use crate::seb::heightfield::Heightfield;
+use crate::seb::joints::Joint;
+use crate::seb::primitives::Plane;
+use crate::seb::raycast::{self, RayHit, Target};
+use nalgebra_glm as glm;
+use std::collections::{HashMap, HashSet};
+
+const PENETRATION_SLOP: f32 = 0.005;
+const POSITION_CORRECTION: f32 = 0.8;
+// sub viteza asta (m/s) nu mai sărim, altfel obiectele tremură pe sol
+const RESTITUTION_THRESHOLD: f32 = 0.5;
+const SLEEP_LINEAR_VELOCITY: f32 = 0.05;
+const SLEEP_ANGULAR_VELOCITY: f32 = 0.05;
+const SLEEP_TIME: f32 = 0.5;
+
+// `Contact::b` pentru contactele cu terenul (un corp static nevăzut)
+pub const TERRAIN: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerPhase {
+    Enter,
+    Stay,
+    Exit,
+}
+
+// `other` a intrat, stă sau a ieșit din corpul `trigger` în ultimul pas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub trigger: usize,
+    pub other: usize,
+    pub phase: TriggerPhase,
+}
+
+#[derive(Clone, Debug)]
+pub struct RigidBody {
+    pub collider: Collider,
+    pub mass: f32,
+    pub velocity: glm::Vec3,
+    pub angular_velocity: glm::Vec3, // radiani pe secundă
+    pub restitution: f32,
+    pub friction: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    pub can_sleep: bool,
+    // ciocnire continuă cu celelalte corpuri, pentru obiecte mici și rapide (gloanțe)
+    pub ccd: bool,
+    pub filter: CollisionFilter,
+    /*
+    Zonă care doar raportează cine intră și iese (vezi trigger_events), fără contacte.
+    Nu cade și nu primește forțe, chiar dacă are masă: se mișcă doar cu `velocity` sau
+    set_position, altfel ar trece prin teren fără să se oprească.
+    */
+    pub trigger: bool,
+    force: glm::Vec3,
+    torque: glm::Vec3,
+    // sfera nu-și ține rotația în collider, dar ancorele articulațiilor au nevoie de ea
+    orientation: glm::Mat3,
+    world_inverse_inertia: glm::Mat3,
+    sleeping: bool,
+    sleep_timer: f32,
+}
+
+impl RigidBody {
+    pub fn new(collider: Collider, mass: f32) -> Self {
+        // planul e infinit, poate fi doar static
+        let mass = if matches!(collider, Collider::Plane(_)) {
+            0.0
+        } else {
+            mass.max(0.0)
+        };
+        Self {
+            collider,
+            mass,
+            velocity: glm::Vec3::zeros(),
+            angular_velocity: glm::Vec3::zeros(),
+            restitution: 0.2,
+            friction: 0.5,
+            linear_damping: 0.05,
+            angular_damping: 0.1,
+            gravity_scale: 1.0,
+            can_sleep: true,
+            ccd: false,
+            filter: CollisionFilter::default(),
+            trigger: false,
+            force: glm::Vec3::zeros(),
+            torque: glm::Vec3::zeros(),
+            orientation: glm::Mat3::identity(),
+            world_inverse_inertia: glm::Mat3::zeros(),
+            sleeping: false,
+            sleep_timer: 0.0,
+        }
+    }
+    // corp static (masă infinită), nu se mișcă din impulsuri
+    pub fn fixed(collider: Collider) -> Self {
+        Self::new(collider, 0.0)
+    }
+    pub fn is_static(&self) -> bool {
+        self.mass <= 0.0
+    }
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+    // activ și nu stă pe loc de la ultimul pas
+    fn is_moving(&self) -> bool {
+        !self.is_static() && !self.sleeping && self.sleep_timer == 0.0
+    }
+    pub fn wake_up(&mut self) {
+        self.sleeping = false;
+        self.sleep_timer = 0.0;
+    }
+    pub fn inverse_mass(&self) -> f32 {
+        if self.is_static() {
+            0.0
+        } else {
+            1.0 / self.mass
+        }
+    }
+    pub fn position(&self) -> glm::Vec3 {
+        self.collider.position()
+    }
+    pub fn set_position(&mut self, position: glm::Vec3) {
+        self.collider.set_position(position);
+        self.wake_up();
+    }
+    pub fn rotation(&self) -> glm::Mat3 {
+        match &self.collider {
+            Collider::Sphere(_) => self.orientation,
+            collider => collider.rotation(),
+        }
+    }
+    // diagonala tensorului de inerție în spațiul local
+    fn local_inertia(&self) -> glm::Vec3 {
+        match &self.collider {
+            Collider::Rectangle(r) => {
+                // scale-ul e jumătate din latură
+                let s = r.scale.component_mul(&r.scale);
+                glm::vec3(s.y + s.z, s.x + s.z, s.x + s.y) * (self.mass / 3.0)
+            }
+            Collider::Sphere(s) => {
+                let i = 0.4 * self.mass * s.scale * s.scale;
+                glm::vec3(i, i, i)
+            }
+            Collider::Capsule(c) => {
+                // masa se împarte după volum între cilindru și cele două semisfere
+                let (r, h) = (c.radius, c.half_height);
+                let cylinder_volume = std::f32::consts::PI * r * r * 2.0 * h;
+                let sphere_volume = 4.0 / 3.0 * std::f32::consts::PI * r * r * r;
+                let mc = self.mass * cylinder_volume / (cylinder_volume + sphere_volume);
+                let ms = self.mass - mc;
+                let axial = mc * r * r / 2.0 + ms * 0.4 * r * r;
+                let side =
+                    mc * (r * r / 4.0 + h * h / 3.0) + ms * (0.4 * r * r + h * h + 0.75 * h * r);
+                glm::vec3(side, axial, side)
+            }
+            Collider::Cylinder(c) => {
+                let (r, h) = (c.radius, c.half_height);
+                let axial = self.mass * r * r / 2.0;
+                let side = self.mass * (3.0 * r * r + 4.0 * h * h) / 12.0;
+                glm::vec3(side, axial, side)
+            }
+            Collider::ConvexHull(h) => {
+                // aproximare cu cutia care încadrează vârfurile
+                let mut half = glm::Vec3::zeros();
+                for p in &h.points {
+                    half = glm::max2(&half, &p.abs());
+                }
+                let s = half.component_mul(&half);
+                glm::vec3(s.y + s.z, s.x + s.z, s.x + s.y) * (self.mass / 3.0)
+            }
+            Collider::Plane(_) => glm::Vec3::zeros(),
+        }
+    }
+    pub fn inverse_inertia(&self) -> glm::Mat3 {
+        if self.is_static() {
+            return glm::Mat3::zeros();
+        }
+        let inertia = self.local_inertia();
+        let inv = glm::vec3(
+            if inertia.x > 0.0 {
+                1.0 / inertia.x
+            } else {
+                0.0
+            },
+            if inertia.y > 0.0 {
+                1.0 / inertia.y
+            } else {
+                0.0
+            },
+            if inertia.z > 0.0 {
+                1.0 / inertia.z
+            } else {
+                0.0
+            },
+        );
+        let rot = self.rotation();
+        rot * glm::diagonal3x3(&inv) * rot.transpose()
+    }
+    pub fn apply_force(&mut self, force: glm::Vec3) {
+        self.force += force;
+        self.wake_up();
+    }
+    pub fn apply_torque(&mut self, torque: glm::Vec3) {
+        self.torque += torque;
+        self.wake_up();
+    }
+    pub fn apply_impulse(&mut self, impulse: glm::Vec3, point: glm::Vec3) {
+        if self.is_static() {
+            return;
+        }
+        let r = point - self.position();
+        self.velocity += impulse * self.inverse_mass();
+        self.angular_velocity += self.inverse_inertia() * glm::cross(&r, &impulse);
+        self.wake_up();
+    }
+    // ca apply_impulse, dar nu trezește corpul și folosește inerția calculată la începutul pasului
+    fn apply_contact_impulse(&mut self, impulse: glm::Vec3, point: glm::Vec3) {
+        if self.is_static() || self.sleeping {
+            return;
+        }
+        let r = point - self.position();
+        self.velocity += impulse * self.inverse_mass();
+        self.angular_velocity += self.world_inverse_inertia * glm::cross(&r, &impulse);
+    }
+    pub fn velocity_at(&self, point: glm::Vec3) -> glm::Vec3 {
+        self.velocity + glm::cross(&self.angular_velocity, &(point - self.position()))
+    }
+    fn integrate_velocity(&mut self, gravity: glm::Vec3, dt: f32) {
+        if self.is_static() || self.sleeping || self.trigger {
+            self.force = glm::Vec3::zeros();
+            self.torque = glm::Vec3::zeros();
+            return;
+        }
+        self.velocity += (gravity * self.gravity_scale + self.force * self.inverse_mass()) * dt;
+        self.angular_velocity += self.world_inverse_inertia * self.torque * dt;
+
+        self.velocity *= 1.0 / (1.0 + dt * self.linear_damping);
+        self.angular_velocity *= 1.0 / (1.0 + dt * self.angular_damping);
+
+        self.force = glm::Vec3::zeros();
+        self.torque = glm::Vec3::zeros();
+    }
+    fn integrate_position(&mut self, dt: f32) {
+        if self.is_static() || self.sleeping {
+            return;
+        }
+        let position = self.position() + self.velocity * dt;
+        self.collider.set_position(position);
+
+        let w = glm::length(&self.angular_velocity);
+        if w > 1e-6 {
+            let delta = glm::mat4_to_mat3(&glm::rotation(w * dt, &(self.angular_velocity / w)));
+            let rot = delta * self.rotation();
+            if matches!(self.collider, Collider::Sphere(_)) {
+                self.orientation = rot;
+            } else {
+                self.collider.set_rotation(&rot);
+            }
+        }
+    }
+}
+
+fn any_perpendicular(n: &glm::Vec3) -> glm::Vec3 {
+    let t = if n.x.abs() < 0.57 {
+        glm::cross(n, &glm::vec3(1.0, 0.0, 0.0))
+    } else {
+        glm::cross(n, &glm::vec3(0.0, 1.0, 0.0))
+    };
+    glm::normalize(&t)
+}
+
+fn ordered(i: usize, j: usize) -> (usize, usize) {
+    if i < j { (i, j) } else { (j, i) }
+}
+
+fn bodies_pair_mut<'a>(
+    bodies: &'a mut [RigidBody],
+    ground: &'a mut RigidBody,
+    a: usize,
+    b: usize,
+) -> (&'a mut RigidBody, &'a mut RigidBody) {
+    if b == TERRAIN {
+        (&mut bodies[a], ground)
+    } else if a < b {
+        let (left, right) = bodies.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = bodies.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub a: usize,
+    pub b: usize,
+    pub normal: glm::Vec3, // de la a spre b
+    pub point: glm::Vec3,
+    pub depth: f32,
+    pub feature_a: Feature,
+    pub feature_b: Feature,
+    pub normal_impulse: f32,
+    // impulsul de frecare din frame-ul trecut, proiectat pe tangentele noi la prepare
+    friction_impulse: glm::Vec3,
+    tangents: [glm::Vec3; 2],
+    tangent_impulse: [f32; 2],
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    velocity_bias: f32,
+    friction: f32,
+    share: f32, // fracțiunea din corecția de poziție a perechii
+}
+
+pub struct PhysicsWorld {
+    pub bodies: Vec<RigidBody>,
+    pub gravity: glm::Vec3,
+    // atracțiile planetelor, adunate peste `gravity`
+    pub fields: Vec<GravityField>,
+    pub iterations: usize,
+    // geometrie statică din modele încărcate, ciocnită doar cu sfere și capsule
+    pub meshes: Vec<MeshBvh>,
+    // terenuri din hărți și planete, ciocnite cu toate formele în afară de plan
+    pub terrains: Vec<Heightfield>,
+    // rezolvate împreună cu contactele, în aceleași iterații
+    pub joints: Vec<Joint>,
+    ground: RigidBody,
+    // perechile (mic, mare) legate fără collide_connected, construite din `joints`
+    joined: HashSet<(usize, usize)>,
+    // câte articulații aveau `joined` la ultima construire, None dacă trebuie refăcut
+    joined_count: Option<usize>,
+    contacts: Vec<Contact>,
+    // perechile (trigger, corp) suprapuse la pasul trecut, sortate
+    overlaps: Vec<(usize, usize)>,
+    trigger_events: Vec<TriggerEvent>,
+    broadphase: DynamicTree,
+    proxies: Vec<usize>,
+}
+
+impl PhysicsWorld {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            gravity: glm::vec3(0.0, -9.81, 0.0),
+            fields: Vec::new(),
+            iterations: 20,
+            meshes: Vec::new(),
+            terrains: Vec::new(),
+            joints: Vec::new(),
+            ground: RigidBody {
+                restitution: 0.0,
+                ..RigidBody::fixed(Collider::Plane(Plane::new()))
+            },
+            joined: HashSet::new(),
+            joined_count: Some(0),
+            contacts: Vec::new(),
+            overlaps: Vec::new(),
+            trigger_events: Vec::new(),
+            broadphase: DynamicTree::new(),
+            proxies: Vec::new(),
+        }
+    }
+    pub fn add_body(&mut self, body: RigidBody) -> usize {
+        self.bodies.push(body);
+        self.bodies.len() - 1
+    }
+    pub fn body(&self, handle: usize) -> &RigidBody {
+        &self.bodies[handle]
+    }
+    // TERRAIN e lumea, pentru articulațiile prinse de ea
+    fn body_or_ground(&self, handle: usize) -> &RigidBody {
+        if handle == TERRAIN {
+            &self.ground
+        } else {
+            &self.bodies[handle]
+        }
+    }
+    pub fn body_mut(&mut self, handle: usize) -> &mut RigidBody {
+        &mut self.bodies[handle]
+    }
+    // ancorele și axa articulației se iau de pe corpuri cum sunt acum
+    pub fn add_joint(&mut self, mut joint: Joint) -> usize {
+        // rezolvarea ține lumea mereu în b
+        if joint.a == TERRAIN {
+            joint.swap_bodies();
+        }
+        joint.attach(self.body_or_ground(joint.a), self.body_or_ground(joint.b));
+        if self.joined_count == Some(self.joints.len()) {
+            if !joint.collide_connected {
+                self.joined.insert(ordered(joint.a, joint.b));
+            }
+            self.joined_count = Some(self.joints.len() + 1);
+        }
+        self.joints.push(joint);
+        self.joints.len() - 1
+    }
+    // articulațiile de după `handle` coboară cu o poziție
+    pub fn remove_joint(&mut self, handle: usize) -> Joint {
+        self.joined_count = None;
+        self.joints.remove(handle)
+    }
+    pub fn joint(&self, handle: usize) -> &Joint {
+        &self.joints[handle]
+    }
+    pub fn joint_mut(&mut self, handle: usize) -> &mut Joint {
+        // se pot schimba corpurile sau collide_connected
+        self.joined_count = None;
+        &mut self.joints[handle]
+    }
+    // unghiul unei balamale, în radiani
+    pub fn joint_angle(&self, handle: usize) -> f32 {
+        let joint = &self.joints[handle];
+        joint.angle(self.body_or_ground(joint.a), self.body_or_ground(joint.b))
+    }
+    // deplasarea pe axă a unui slider
+    pub fn joint_translation(&self, handle: usize) -> f32 {
+        let joint = &self.joints[handle];
+        joint.translation(self.body_or_ground(joint.a), self.body_or_ground(joint.b))
+    }
+    // capetele articulației în lume, pentru desenat
+    pub fn joint_anchors(&self, handle: usize) -> (glm::Vec3, glm::Vec3) {
+        let joint = &self.joints[handle];
+        (
+            joint.world_anchor_a(self.body_or_ground(joint.a)),
+            joint.world_anchor_b(self.body_or_ground(joint.b)),
+        )
+    }
+    pub fn gravity_at(&self, position: &glm::Vec3) -> glm::Vec3 {
+        self.fields.iter().fold(self.gravity, |total, field| {
+            total + field.acceleration(position)
+        })
+    }
+    // direcția sus în punctul dat, pentru orientarea corpurilor și a personajelor
+    pub fn up_at(&self, position: &glm::Vec3) -> glm::Vec3 {
+        gravity::up_from(&self.gravity_at(position))
+    }
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+    pub fn broadphase(&self) -> &DynamicTree {
+        &self.broadphase
+    }
+    // evenimentele din ultimul step, sortate după trigger și corp
+    pub fn trigger_events(&self) -> &[TriggerEvent] {
+        &self.trigger_events
+    }
+    // `object` din RayHit e handle-ul corpului lovit; doar corpurile cu stratul în `mask`, fără triggere
+    pub fn raycast(
+        &self,
+        origin: glm::Vec3,
+        dir: glm::Vec3,
+        max_distance: f32,
+        mask: u32,
+    ) -> Option<RayHit> {
+        self.spherecast(origin, dir, 0.0, max_distance, mask)
+    }
+    pub fn spherecast(
+        &self,
+        origin: glm::Vec3,
+        dir: glm::Vec3,
+        radius: f32,
+        max_distance: f32,
+        mask: u32,
+    ) -> Option<RayHit> {
+        let (handles, targets): (Vec<usize>, Vec<Target>) = self
+            .bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| !body.trigger && body.filter.layer & mask != 0)
+            .map(|(i, body)| (i, Target::Collider(&body.collider)))
+            .unzip();
+        raycast::spherecast(&targets, origin, dir, radius, max_distance).map(|hit| RayHit {
+            object: handles[hit.object],
+            ..hit
+        })
+    }
+    pub fn step(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        let gravity: Vec<glm::Vec3> = self
+            .bodies
+            .iter()
+            .map(|body| self.gravity_at(&body.position()))
+            .collect();
+        for (body, gravity) in self.bodies.iter_mut().zip(gravity) {
+            body.world_inverse_inertia = body.inverse_inertia();
+            body.integrate_velocity(gravity, dt);
+        }
+
+        self.find_contacts();
+        self.prepare_contacts();
+        self.prepare_joints(dt);
+        self.warm_start();
+        for _ in 0..self.iterations {
+            self.solve_joints();
+            self.solve_contacts();
+        }
+
+        let previous: Vec<glm::Vec3> = self.bodies.iter().map(|b| b.position()).collect();
+        let impacts = self.find_impacts(dt);
+        for (i, body) in self.bodies.iter_mut().enumerate() {
+            match impacts.iter().find(|(a, _, _)| *a == i) {
+                Some((_, _, toi)) => body.integrate_position(dt * toi.t),
+                None => body.integrate_position(dt),
+            }
+        }
+        self.resolve_impacts(&impacts);
+        self.correct_positions();
+        self.collide_meshes(&previous);
+        self.update_sleep(dt);
+        self.update_triggers();
+    }
+    // `bodies` e public, așa că sincronizăm arborele aici în loc de add_body
+    fn update_broadphase(&mut self) {
+        if self.proxies.len() > self.bodies.len() {
+            self.broadphase.clear();
+            self.proxies.clear();
+        }
+        for (i, body) in self.bodies.iter().enumerate() {
+            let aabb = body.collider.aabb();
+            match self.proxies.get(i) {
+                Some(&proxy) => {
+                    self.broadphase.update(proxy, aabb);
+                }
+                None => self.proxies.push(self.broadphase.insert(aabb, i)),
+            }
+        }
+    }
+    // `joints` e public, așa că o listă schimbată direct e prinsă după lungime
+    fn update_joined(&mut self) {
+        if self.joined_count == Some(self.joints.len()) {
+            return;
+        }
+        self.joined = self
+            .joints
+            .iter()
+            .filter(|joint| !joint.collide_connected)
+            .map(|joint| ordered(joint.a, joint.b))
+            .collect();
+        self.joined_count = Some(self.joints.len());
+    }
+    // triggerele nu ating nimic, iar filtrele trebuie să se potrivească în ambele sensuri
+    fn can_collide(&self, i: usize, j: usize) -> bool {
+        let (a, b) = (&self.bodies[i], &self.bodies[j]);
+        !a.trigger
+            && !b.trigger
+            && a.filter.can_collide(&b.filter)
+            && !self.joined.contains(&ordered(i, j))
+    }
+    fn find_contacts(&mut self) {
+        let previous = std::mem::take(&mut self.contacts);
+        self.update_broadphase();
+        self.update_joined();
+
+        // aceleași perechi ca `candidate_pairs`, dar din arborele păstrat între pași;
+        // static și adormit nu se pot ciocni între ele
+        let active = |body: &RigidBody| !body.is_static() && !body.sleeping;
+        for (i, j) in self.broadphase.pairs() {
+            let (a, b) = (&self.bodies[i], &self.bodies[j]);
+            if (active(a) || active(b)) && self.can_collide(i, j) {
+                self.test_pair(i, j);
+            }
+        }
+        for i in 0..self.bodies.len() {
+            let body = &self.bodies[i];
+            if body.is_static() || body.sleeping || body.trigger {
+                continue;
+            }
+            let manifolds: Vec<ContactManifold> = self
+                .terrains
+                .iter()
+                .flat_map(|terrain| terrain.collide(&body.collider))
+                .collect();
+            let friction = body.friction;
+            for manifold in manifolds {
+                self.add_contacts(i, TERRAIN, &manifold, friction);
+            }
+        }
+
+        // warm starting: un contact cu aceleași trăsături ca în frame-ul trecut își păstrează impulsul
+        let cache: HashMap<_, _> = previous
+            .iter()
+            .map(|c| {
+                (
+                    (c.a, c.b, c.feature_a, c.feature_b),
+                    (c.normal_impulse, c.friction_impulse),
+                )
+            })
+            .collect();
+        for c in &mut self.contacts {
+            if let Some(&(normal, friction)) = cache.get(&(c.a, c.b, c.feature_a, c.feature_b)) {
+                c.normal_impulse = normal;
+                c.friction_impulse = friction;
+            }
+        }
+    }
+    fn test_pair(&mut self, i: usize, j: usize) {
+        let (a, b) = (&self.bodies[i], &self.bodies[j]);
+        let Some(manifold) = collide_manifold(&a.collider, &b.collider) else {
+            return;
+        };
+        let friction = (a.friction * b.friction).sqrt();
+
+        // trezim doar dacă celălalt chiar se mișcă, altfel doi vecini care adorm la momente diferite
+        // s-ar trezi unul pe altul la nesfârșit
+        let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, i, j);
+        if a.sleeping && b.is_moving() {
+            a.wake_up();
+        }
+        if b.sleeping && a.is_moving() {
+            b.wake_up();
+        }
+
+        self.add_contacts(i, j, &manifold, friction);
+    }
+    fn add_contacts(&mut self, i: usize, j: usize, manifold: &ContactManifold, friction: f32) {
+        let share = 1.0 / manifold.points.len() as f32;
+        for point in &manifold.points {
+            self.contacts.push(Contact {
+                a: i,
+                b: j,
+                normal: manifold.normal,
+                point: point.position,
+                depth: point.depth,
+                feature_a: point.feature_a,
+                feature_b: point.feature_b,
+                normal_impulse: 0.0,
+                friction_impulse: glm::Vec3::zeros(),
+                tangents: [glm::Vec3::zeros(); 2],
+                tangent_impulse: [0.0; 2],
+                normal_mass: 0.0,
+                tangent_mass: [0.0; 2],
+                velocity_bias: 0.0,
+                friction,
+                share,
+            });
+        }
+    }
+    fn prepare_contacts(&mut self) {
+        for c in &mut self.contacts {
+            let a = &self.bodies[c.a];
+            let b = if c.b == TERRAIN {
+                &self.ground
+            } else {
+                &self.bodies[c.b]
+            };
+            let ra = c.point - a.position();
+            let rb = c.point - b.position();
+            let inv_ia = a.world_inverse_inertia;
+            let inv_ib = b.world_inverse_inertia;
+            let inv_mass = a.inverse_mass() + b.inverse_mass();
+
+            let effective_mass = |dir: &glm::Vec3| -> f32 {
+                let ta = glm::cross(&(inv_ia * glm::cross(&ra, dir)), &ra);
+                let tb = glm::cross(&(inv_ib * glm::cross(&rb, dir)), &rb);
+                let k = inv_mass + glm::dot(dir, &(ta + tb));
+                if k > 1e-9 { 1.0 / k } else { 0.0 }
+            };
+
+            let relative = b.velocity_at(c.point) - a.velocity_at(c.point);
+            let vn = glm::dot(&relative, &c.normal);
+
+            // prima tangentă pe direcția alunecării, ca frecarea să fie izotropă
+            let sliding = relative - c.normal * vn;
+            let t1 = if glm::length(&sliding) > 1e-4 {
+                glm::normalize(&sliding)
+            } else {
+                any_perpendicular(&c.normal)
+            };
+            let t2 = glm::cross(&c.normal, &t1);
+
+            c.tangents = [t1, t2];
+            c.tangent_impulse = [
+                glm::dot(&c.friction_impulse, &t1),
+                glm::dot(&c.friction_impulse, &t2),
+            ];
+            c.normal_mass = effective_mass(&c.normal);
+            c.tangent_mass = [effective_mass(&t1), effective_mass(&t2)];
+
+            let restitution = a.restitution.max(b.restitution);
+            c.velocity_bias = if vn < -RESTITUTION_THRESHOLD {
+                -restitution * vn
+            } else {
+                0.0
+            };
+        }
+    }
+    // un corp care se mișcă trezește corpul legat de el, ca la contacte
+    fn prepare_joints(&mut self, dt: f32) {
+        for joint in &mut self.joints {
+            if joint.a == joint.b || joint.a == TERRAIN {
+                continue;
+            }
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, joint.a, joint.b);
+            if a.sleeping && b.is_moving() {
+                a.wake_up();
+            }
+            if b.sleeping && a.is_moving() {
+                b.wake_up();
+            }
+            joint.prepare(a, b, dt);
+            joint.warm_start(a, b);
+        }
+    }
+    fn solve_joints(&mut self) {
+        for joint in &mut self.joints {
+            if joint.a == joint.b || joint.a == TERRAIN {
+                continue;
+            }
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, joint.a, joint.b);
+            joint.solve(a, b);
+        }
+    }
+    fn warm_start(&mut self) {
+        for c in &self.contacts {
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, c.a, c.b);
+            let impulse = c.normal * c.normal_impulse
+                + c.tangents[0] * c.tangent_impulse[0]
+                + c.tangents[1] * c.tangent_impulse[1];
+            a.apply_contact_impulse(-impulse, c.point);
+            b.apply_contact_impulse(impulse, c.point);
+        }
+    }
+    fn solve_contacts(&mut self) {
+        for c in &mut self.contacts {
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, c.a, c.b);
+
+            let relative = b.velocity_at(c.point) - a.velocity_at(c.point);
+            let vn = glm::dot(&relative, &c.normal);
+            let mut lambda = (c.velocity_bias - vn) * c.normal_mass;
+            let old = c.normal_impulse;
+            c.normal_impulse = (old + lambda).max(0.0);
+            lambda = c.normal_impulse - old;
+            let impulse = c.normal * lambda;
+            a.apply_contact_impulse(-impulse, c.point);
+            b.apply_contact_impulse(impulse, c.point);
+
+            let max_friction = c.friction * c.normal_impulse;
+            for k in 0..2 {
+                let relative = b.velocity_at(c.point) - a.velocity_at(c.point);
+                let vt = glm::dot(&relative, &c.tangents[k]);
+                let mut lambda = -vt * c.tangent_mass[k];
+                let old = c.tangent_impulse[k];
+                c.tangent_impulse[k] = (old + lambda).clamp(-max_friction, max_friction);
+                lambda = c.tangent_impulse[k] - old;
+                let impulse = c.tangents[k] * lambda;
+                a.apply_contact_impulse(-impulse, c.point);
+                b.apply_contact_impulse(impulse, c.point);
+            }
+            c.friction_impulse =
+                c.tangents[0] * c.tangent_impulse[0] + c.tangents[1] * c.tangent_impulse[1];
+        }
+    }
+    fn correct_positions(&mut self) {
+        for c in &self.contacts {
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, c.a, c.b);
+            let inv_a = a.inverse_mass();
+            let inv_b = b.inverse_mass();
+            let total = inv_a + inv_b;
+            if total <= 0.0 {
+                continue;
+            }
+            let correction =
+                (c.depth - PENETRATION_SLOP).max(0.0) * POSITION_CORRECTION * c.share / total;
+            if correction <= 0.0 {
+                continue;
+            }
+            let pa = a.position() - c.normal * correction * inv_a;
+            let pb = b.position() + c.normal * correction * inv_b;
+            a.collider.set_position(pa);
+            b.collider.set_position(pb);
+        }
+    }
+    /*
+    Pentru corpurile cu ccd căutăm primul impact din pas cu celelalte corpuri, ca un glonț
+    să nu treacă printr-un perete subțire. Ce e deja în contact rămâne pe seama solverului.
+    */
+    fn find_impacts(&self, dt: f32) -> Vec<(usize, usize, TimeOfImpact)> {
+        let motion = |body: &RigidBody| {
+            if body.is_static() || body.sleeping {
+                Motion::default()
+            } else {
+                Motion::new(&body.velocity, &body.angular_velocity, dt)
+            }
+        };
+        let mut impacts = Vec::new();
+        let mut candidates = Vec::new();
+        for (i, body) in self.bodies.iter().enumerate() {
+            if !body.ccd || body.is_static() || body.sleeping || body.trigger {
+                continue;
+            }
+            let motion_a = motion(body);
+            let start = body.collider.aabb();
+            let end = motion_a.apply(&body.collider, 1.0).aabb();
+            candidates.clear();
+            self.broadphase.query(&start.union(&end), &mut candidates);
+
+            let mut first: Option<(usize, TimeOfImpact)> = None;
+            for &j in &candidates {
+                if j == i || !self.can_collide(i, j) {
+                    continue;
+                }
+                let other = &self.bodies[j];
+                let Some(toi) =
+                    ccd::time_of_impact(&body.collider, &motion_a, &other.collider, &motion(other))
+                else {
+                    continue;
+                };
+                if toi.t > 0.0 && first.is_none_or(|(_, f)| toi.t < f.t) {
+                    first = Some((j, toi));
+                }
+            }
+            if let Some((j, toi)) = first {
+                impacts.push((i, j, toi));
+            }
+        }
+        impacts
+    }
+    // corpul oprit la impact primește un impuls pe normală, restul pasului se pierde
+    fn resolve_impacts(&mut self, impacts: &[(usize, usize, TimeOfImpact)]) {
+        for &(i, j, toi) in impacts {
+            let (a, b) = bodies_pair_mut(&mut self.bodies, &mut self.ground, i, j);
+            let n = toi.normal;
+            let approach = glm::dot(&(a.velocity_at(toi.point) - b.velocity_at(toi.point)), &n);
+            if approach <= 0.0 {
+                continue;
+            }
+            let ra = toi.point - a.position();
+            let rb = toi.point - b.position();
+            let ta = glm::cross(&(a.inverse_inertia() * glm::cross(&ra, &n)), &ra);
+            let tb = glm::cross(&(b.inverse_inertia() * glm::cross(&rb, &n)), &rb);
+            let k = a.inverse_mass() + b.inverse_mass() + glm::dot(&n, &(ta + tb));
+            if k <= 1e-9 {
+                continue;
+            }
+            let restitution = if approach > RESTITUTION_THRESHOLD {
+                a.restitution.max(b.restitution)
+            } else {
+                0.0
+            };
+            let impulse = n * ((1.0 + restitution) * approach / k);
+            a.apply_impulse(-impulse, toi.point);
+            b.apply_impulse(impulse, toi.point);
+        }
+    }
+    /*
+    Scoatem corpurile din mesh-uri și le tăiem viteza spre ele, cu restituție și frecare.
+    Mesh-urile sunt subțiri, așa că întâi plimbăm forma de la poziția veche la cea nouă
+    și o oprim la primul triunghi, altfel la viteze mari ar trece prin ele.
+    */
+    fn collide_meshes(&mut self, previous: &[glm::Vec3]) {
+        if self.meshes.is_empty() {
+            return;
+        }
+        for (body, previous) in self.bodies.iter_mut().zip(previous) {
+            if body.is_static() || body.sleeping || body.trigger {
+                continue;
+            }
+            // punctele de pe axă care se plimbă ca sfere, relative la centru
+            let (ends, radius) = match &body.collider {
+                Collider::Sphere(s) => (vec![glm::Vec3::zeros()], s.scale),
+                Collider::Capsule(c) => {
+                    let (a, b) = c.segment();
+                    (vec![a - c.position, b - c.position], c.radius)
+                }
+                _ => continue,
+            };
+            // suma normalelor de contact, ponderate cu cât am împins
+            let mut total = glm::Vec3::zeros();
+            let motion = body.position() - previous;
+            let length = glm::length(&motion);
+            if length > radius * 0.5 {
+                let dir = motion / length;
+                let first_hit = self
+                    .meshes
+                    .iter()
+                    .flat_map(|mesh| {
+                        ends.iter()
+                            .filter_map(|end| mesh.cast(&(previous + end), &dir, radius, length))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((t, normal, _)) = first_hit {
+                    body.collider.set_position(previous + dir * t);
+                    total += normal * (length - t);
+                }
+            }
+
+            for mesh in &self.meshes {
+                let mtv = match &body.collider {
+                    Collider::Sphere(s) => mesh.sphere_mtv(&s.position, s.scale),
+                    Collider::Capsule(c) => {
+                        let (a, b) = c.segment();
+                        mesh.capsule_mtv(&a, &b, c.radius)
+                    }
+                    _ => None,
+                };
+                if let Some(mtv) = mtv {
+                    let position = body.position() + mtv;
+                    body.collider.set_position(position);
+                    total += mtv;
+                }
+            }
+            if glm::length2(&total) < 1e-12 {
+                continue;
+            }
+            let normal = glm::normalize(&total);
+            let vn = glm::dot(&body.velocity, &normal);
+            if vn >= 0.0 {
+                continue;
+            }
+            let mut tangent = body.velocity - normal * vn;
+            let speed = glm::length(&tangent);
+            if speed > 1e-9 {
+                tangent *= (speed - body.friction * -vn).max(0.0) / speed;
+            }
+            let bounce = if vn < -RESTITUTION_THRESHOLD {
+                -vn * body.restitution
+            } else {
+                0.0
+            };
+            body.velocity = tangent + normal * bounce;
+        }
+    }
+    /*
+    Ce corpuri se suprapun cu fiecare trigger după pas, comparat cu pasul trecut.
+    Corpurile adormite rămân înăuntru, așa că primesc Stay până se mișcă de acolo.
+    */
+    fn update_triggers(&mut self) {
+        self.trigger_events.clear();
+        if !self.bodies.iter().any(|b| b.trigger) && self.overlaps.is_empty() {
+            return;
+        }
+        self.update_broadphase();
+
+        let mut overlaps = Vec::new();
+        let mut candidates = Vec::new();
+        for (i, body) in self.bodies.iter().enumerate() {
+            if !body.trigger {
+                continue;
+            }
+            candidates.clear();
+            self.broadphase
+                .query(&body.collider.aabb(), &mut candidates);
+            for &j in &candidates {
+                let other = &self.bodies[j];
+                if j == i || other.trigger || !body.filter.can_collide(&other.filter) {
+                    continue;
+                }
+                if collide_pair(&body.collider, &other.collider).is_some() {
+                    overlaps.push((i, j));
+                }
+            }
+        }
+        overlaps.sort_unstable();
+
+        let event = |&(trigger, other): &(usize, usize), phase| TriggerEvent {
+            trigger,
+            other,
+            phase,
+        };
+        for pair in &overlaps {
+            let phase = if self.overlaps.binary_search(pair).is_ok() {
+                TriggerPhase::Stay
+            } else {
+                TriggerPhase::Enter
+            };
+            self.trigger_events.push(event(pair, phase));
+        }
+        // corpurile șterse din `bodies` ies și ele
+        for pair in &self.overlaps {
+            if overlaps.binary_search(pair).is_err() {
+                self.trigger_events.push(event(pair, TriggerPhase::Exit));
+            }
+        }
+        self.trigger_events.sort_by_key(|e| (e.trigger, e.other));
+        self.overlaps = overlaps;
+    }
+    fn update_sleep(&mut self, dt: f32) {
+        for body in &mut self.bodies {
+            if body.is_static() || body.sleeping || !body.can_sleep {
+                continue;
+            }
+            let slow = glm::length(&body.velocity) < SLEEP_LINEAR_VELOCITY
+                && glm::length(&body.angular_velocity) < SLEEP_ANGULAR_VELOCITY;
+            if slow {
+                body.sleep_timer += dt;
+                if body.sleep_timer > SLEEP_TIME {
+                    body.sleeping = true;
+                    body.velocity = glm::Vec3::zeros();
+                    body.angular_velocity = glm::Vec3::zeros();
+                }
+            } else {
+                body.sleep_timer = 0.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::joints::Joint;
+    use crate::seb::primitives::{Rectangle, Sphere};
+
+    fn ball(position: glm::Vec3) -> RigidBody {
+        let mut sphere = Sphere::new();
+        sphere.position = position;
+        sphere.scale = 0.25;
+        RigidBody::new(Collider::Sphere(sphere), 1.0)
+    }
+
+    fn cuboid(position: glm::Vec3, scale: glm::Vec3) -> Collider {
+        let mut r = Rectangle::new();
+        r.position = position;
+        r.scale = scale;
+        Collider::Rectangle(r)
+    }
+
+    // un pendul prins de lume: o dată cu lumea ca b, o dată ca a
+    fn pendulum(world_first: bool) -> (PhysicsWorld, usize, usize) {
+        let mut world = PhysicsWorld::new();
+        let mut sphere = Sphere::new();
+        sphere.position = glm::vec3(1.0, 5.0, 0.0);
+        sphere.scale = 0.1;
+        let body = world.add_body(RigidBody::new(Collider::Sphere(sphere), 1.0));
+        let (anchor, axis) = (glm::vec3(0.0, 5.0, 0.0), glm::vec3(0.0, 0.0, 1.0));
+        let joint = if world_first {
+            Joint::hinge(TERRAIN, body, anchor, axis)
+        } else {
+            Joint::hinge(body, TERRAIN, anchor, axis)
+        };
+        let handle = world.add_joint(joint.with_limit(-1.0, 1.0));
+        (world, body, handle)
+    }
+
+    #[test]
+    fn joint_to_world_as_a_is_solved() {
+        let (mut first, body_first, joint_first) = pendulum(true);
+        let (mut second, body_second, joint_second) = pendulum(false);
+        for _ in 0..120 {
+            first.step(1.0 / 60.0);
+            second.step(1.0 / 60.0);
+        }
+        let position = first.body(body_first).position();
+        // ține de ancoră, nu cade liber
+        let radius = glm::distance(&position, &glm::vec3(0.0, 5.0, 0.0));
+        assert!((radius - 1.0).abs() < 0.05, "raza {}", radius);
+        assert!(position.y > 3.5, "a căzut la {:?}", position);
+
+        let other = second.body(body_second).position();
+        assert!(glm::distance(&position, &other) < 1e-3);
+        let (angle, other_angle) = (
+            first.joint_angle(joint_first),
+            second.joint_angle(joint_second),
+        );
+        // același unghi, măsurat din partea cealaltă
+        assert!(angle.abs() > 0.01);
+        assert!(
+            (angle + other_angle).abs() < 1e-3,
+            "{} vs {}",
+            angle,
+            other_angle
+        );
+    }
+
+    #[test]
+    fn masks_keep_layers_apart() {
+        let mut world = PhysicsWorld::new();
+        let mut floor = RigidBody::fixed(cuboid(
+            glm::vec3(0.0, -0.5, 0.0),
+            glm::vec3(10.0, 0.5, 10.0),
+        ));
+        // podeaua e pe stratul 2 și nu vede stratul 4
+        floor.filter = CollisionFilter::new(2, !4);
+        world.add_body(floor);
+
+        let lands = world.add_body(ball(glm::vec3(-2.0, 1.0, 0.0)));
+        // nu vede stratul podelei
+        let mut blind = ball(glm::vec3(0.0, 1.0, 0.0));
+        blind.filter = CollisionFilter::new(1, 1);
+        let blind = world.add_body(blind);
+        // vede podeaua, dar podeaua nu-l vede pe el
+        let mut unseen = ball(glm::vec3(2.0, 1.0, 0.0));
+        unseen.filter = CollisionFilter::new(4, u32::MAX);
+        let unseen = world.add_body(unseen);
+
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        let y = world.body(lands).position().y;
+        assert!((y - 0.25).abs() < 0.05, "stă la {}", y);
+        for handle in [blind, unseen] {
+            assert!(
+                world.body(handle).position().y < -1.0,
+                "{} nu a căzut",
+                handle
+            );
+        }
+        assert!(
+            world
+                .contacts()
+                .iter()
+                .all(|c| c.a == lands || c.b == lands)
+        );
+    }
+
+    #[test]
+    fn trigger_reports_enter_stay_exit_in_order() {
+        let mut world = PhysicsWorld::new();
+        // are masă, dar un trigger nu cade
+        let mut zone = RigidBody::new(cuboid(glm::Vec3::zeros(), glm::vec3(1.0, 1.0, 1.0)), 2.0);
+        zone.trigger = true;
+        zone.can_sleep = false;
+        let zone = world.add_body(zone);
+        let falling = world.add_body(ball(glm::vec3(0.0, 3.0, 0.0)));
+        // stratul lui nu e văzut de trigger, deci nu apare în evenimente
+        let mut ignored = ball(glm::vec3(0.7, 3.0, 0.0));
+        ignored.filter = CollisionFilter::new(2, u32::MAX);
+        world.body_mut(zone).filter = CollisionFilter::new(1, !2);
+        world.add_body(ignored);
+
+        let mut phases = Vec::new();
+        for _ in 0..120 {
+            world.step(1.0 / 60.0);
+            for event in world.trigger_events() {
+                assert_eq!((event.trigger, event.other), (zone, falling));
+                phases.push(event.phase);
+            }
+        }
+        assert_eq!(world.body(zone).position(), glm::Vec3::zeros());
+        assert_eq!(phases.first(), Some(&TriggerPhase::Enter));
+        assert_eq!(phases.last(), Some(&TriggerPhase::Exit));
+        let stays = &phases[1..phases.len() - 1];
+        assert!(!stays.is_empty() && stays.iter().all(|&p| p == TriggerPhase::Stay));
+        assert!(world.body(falling).position().y < -1.25);
+
+        // se mișcă totuși cu viteza dată
+        world.body_mut(zone).velocity = glm::vec3(1.0, 0.0, 0.0);
+        world.step(0.5);
+        assert!(glm::distance(&world.body(zone).position(), &glm::vec3(0.5, 0.0, 0.0)) < 1e-5);
+    }
+
+    fn floor() -> RigidBody {
+        RigidBody {
+            restitution: 0.0,
+            ..RigidBody::fixed(cuboid(
+                glm::vec3(0.0, -0.5, 0.0),
+                glm::vec3(10.0, 0.5, 10.0),
+            ))
+        }
+    }
+
+    #[test]
+    fn bounces_lower_each_time_and_comes_to_rest() {
+        let mut world = PhysicsWorld::new();
+        world.add_body(floor());
+        let mut dropped = ball(glm::vec3(0.0, 3.0, 0.0));
+        dropped.restitution = 0.6;
+        dropped.linear_damping = 0.0;
+        let dropped = world.add_body(dropped);
+
+        // cea mai mare înălțime dintre două ciocniri, măsurată la baza sferei
+        let mut peaks = Vec::new();
+        let mut rising = false;
+        for _ in 0..600 {
+            let before = world.body(dropped).velocity.y;
+            world.step(1.0 / 120.0);
+            let body = world.body(dropped);
+            if before < 0.0 && body.velocity.y > 0.0 {
+                rising = true;
+            } else if rising && body.velocity.y <= 0.0 {
+                rising = false;
+                peaks.push(body.position().y - 0.25);
+            }
+        }
+        assert!(peaks.len() >= 2, "{:?}", peaks);
+        // înălțimea scade cu pătratul restituției
+        let mut previous = 2.75;
+        for &peak in &peaks[..2] {
+            let ratio = peak / previous;
+            assert!((ratio - 0.36).abs() < 0.06, "{:?}", peaks);
+            previous = peak;
+        }
+        assert!(peaks.windows(2).all(|w| w[1] < w[0]), "{:?}", peaks);
+
+        let body = world.body(dropped);
+        assert!(body.is_sleeping(), "{:?}", body.velocity);
+        assert!((body.position().y - 0.25).abs() < 0.02);
+    }
+
+    // o cutie pusă pe o pantă de 20 de grade, cu frecarea dată la amândouă
+    fn slope(friction: f32) -> (PhysicsWorld, usize, glm::Vec3) {
+        let mut world = PhysicsWorld::new();
+        let rotation = crate::seb::collision::euler_to_matrix(0.0, 0.0, 20.0);
+        let mut ramp = RigidBody::fixed(cuboid(glm::Vec3::zeros(), glm::vec3(5.0, 0.5, 2.0)));
+        ramp.collider.set_rotation(&rotation);
+        ramp.friction = friction;
+        world.add_body(ramp);
+        let up = rotation * glm::vec3(0.0, 1.0, 0.0);
+        let start = up * 0.75;
+        let mut block = RigidBody::new(cuboid(start, glm::vec3(0.25, 0.25, 0.25)), 1.0);
+        block.collider.set_rotation(&rotation);
+        block.friction = friction;
+        block.restitution = 0.0;
+        let block = world.add_body(block);
+        (world, block, start)
+    }
+
+    #[test]
+    fn slides_without_friction_and_sticks_with_it() {
+        let (mut slippery, block, start) = slope(0.0);
+        let (mut rough, stuck, _) = slope(1.0);
+        for _ in 0..60 {
+            slippery.step(1.0 / 60.0);
+            rough.step(1.0 / 60.0);
+        }
+        // fără frecare: g sin 20 pe pantă, adică ~1.7 m într-o secundă, în jos pe x
+        let moved = slippery.body(block).position() - start;
+        assert!(moved.x < -1.2, "{:?}", moved);
+        // tan 20 < 1, deci rămâne pe loc
+        let moved = rough.body(stuck).position() - start;
+        assert!(glm::length(&moved) < 0.05, "{:?}", moved);
+    }
+
+    #[test]
+    fn resting_bodies_fall_asleep() {
+        let mut world = PhysicsWorld::new();
+        world.add_body(floor());
+        let cube = world.add_body(RigidBody::new(
+            cuboid(glm::vec3(0.0, 1.0, 0.0), glm::vec3(0.5, 0.5, 0.5)),
+            1.0,
+        ));
+        let mut awake = ball(glm::vec3(3.0, 1.0, 0.0));
+        awake.can_sleep = false;
+        let awake = world.add_body(awake);
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        let body = world.body(cube);
+        assert!(body.is_sleeping());
+        assert_eq!(body.velocity, glm::Vec3::zeros());
+        assert!(!world.body(awake).is_sleeping());
+
+        // un corp adormit nu se mișcă în pașii următori
+        let position = body.position();
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        assert_eq!(world.body(cube).position(), position);
+
+        world.body_mut(cube).wake_up();
+        world.body_mut(cube).velocity = glm::vec3(0.0, 2.0, 0.0);
+        world.step(1.0 / 60.0);
+        assert!(world.body(cube).position().y > position.y);
+    }
+
+    #[test]
+    fn a_stack_of_boxes_stays_stacked() {
+        let mut world = PhysicsWorld::new();
+        world.add_body(floor());
+        let boxes: Vec<usize> = (0..6)
+            .map(|i| {
+                let mut body = RigidBody::new(
+                    cuboid(
+                        glm::vec3(0.0, 0.5 + i as f32 * 1.01, 0.0),
+                        glm::vec3(0.5, 0.5, 0.5),
+                    ),
+                    1.0,
+                );
+                body.restitution = 0.0;
+                world.add_body(body)
+            })
+            .collect();
+        for _ in 0..600 {
+            world.step(1.0 / 60.0);
+        }
+        for (i, &handle) in boxes.iter().enumerate() {
+            let body = world.body(handle);
+            let p = body.position();
+            // nu a alunecat de pe cea de dedesubt și nici nu s-a afundat în ea
+            assert!(p.x.abs() < 0.05 && p.z.abs() < 0.05, "{}: {:?}", i, p);
+            assert!((p.y - (0.5 + i as f32)).abs() < 0.05, "{}: {:?}", i, p);
+            assert!((body.rotation() - glm::Mat3::identity()).norm() < 0.05);
+            assert!(body.is_sleeping(), "{} încă se mișcă", i);
+        }
+    }
+}
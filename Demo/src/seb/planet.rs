@@ -1,7 +1,8 @@
 use core::f32;
 
 use crate::seb::{
-    seb::{ToCStr, create_shader_from},
+    seb::{ShaderError, create_shader_from},
+    uniforms::UniformCache,
 };
 use nalgebra_glm as glm;
 
@@ -16,10 +17,12 @@ pub struct Planet {
     vertices: Vec<Vertex>,
     indices: Vec<u32>,
     shader_id: u32,
+    uniforms: UniformCache,
     texture_id: u32,
     vao: u32,
     ebo: u32,
     vbo: u32,
+    height_range: (f32, f32),
     pub scale: f32,
     pub position: glm::Vec3,
     pub x_angle: f32,
@@ -34,10 +37,12 @@ impl Planet {
             vertices: Vec::new(),
             indices: Vec::new(),
             shader_id: 0,
+            uniforms: UniformCache::new(),
             texture_id: 0,
             vao: 0,
             ebo: 0,
             vbo: 0,
+            height_range: (0.0, 0.0),
             scale: 1.0,
             position: glm::Vec3::zeros(),
             x_angle: 0.0,
@@ -104,6 +109,11 @@ impl Planet {
             }
         }
         self.map = map;
+        self.height_range = self
+            .map
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
 
         Ok(())
     }
@@ -319,7 +329,7 @@ impl Planet {
         self.indices = indices;
     }
 
-    pub fn init(&mut self) {
+    pub fn init(&mut self) -> Result<(), ShaderError> {
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
             gl::GenBuffers(1, &mut self.vbo);
@@ -454,7 +464,8 @@ impl Planet {
                 fragColor = vec4(final_color, 1.0);
             }
         "#;
-        self.shader_id = create_shader_from(vshader, fshader);
+        self.shader_id = create_shader_from(vshader, fshader)?.id;
+        Ok(())
     }
     pub fn load_texture(&mut self, path: &str) -> Result<(), String> {
         let result = stb_image::image::load(path);
@@ -522,25 +533,25 @@ impl Planet {
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "model".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "model"),
                 1,
                 gl::FALSE,
                 model.as_ptr(),
             );
             gl::Uniform3fv(
-                gl::GetUniformLocation(self.shader_id, "cam_pos".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "cam_pos"),
                 1,
                 cam_pos.as_ptr(),
             );
@@ -564,4 +575,9 @@ impl Planet {
     pub fn height(&self) -> usize {
         self.map.len()
     }
+
+    // cea mai mică și cea mai mare valoare din hartă
+    pub fn height_range(&self) -> (f32, f32) {
+        self.height_range
+    }
 }
@@ -1,8 +1,18 @@
 use crate::Window;
+use crate::seb::character::CharacterController;
+use crate::seb::gravity;
+use crate::seb::input::InputMap;
+use crate::seb::raycast::Target;
 
 use nalgebra as na;
 use nalgebra_glm as glm;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerMode {
+    Noclip, // zbor liber prin pereți, cu bind
+    Walk,   // mers cu gravitație și coliziuni, cu bind_walk
+}
+
 pub struct Player {
     pub projection: glm::Mat4,
     pub view: glm::Mat4,
@@ -11,10 +21,13 @@ pub struct Player {
     pub cam_up: glm::Vec3,
     yaw: f32,
     pitch: f32,
-    last_mouse_x: f64,
-    last_mouse_y: f64,
-    first_mouse: bool,
+    // axele în care sunt măsurate yaw și pitch; Y e sus, întoarsă după gravitație în bind_walk
+    frame: glm::Mat3,
     mouse_lock: bool,
+    pub mode: PlayerMode,
+    pub character: CharacterController,
+    // cât de sus sunt ochii față de centrul capsulei
+    pub eye_height: f32,
 }
 impl Player {
     pub fn new(y: f32) -> Self {
@@ -26,70 +39,87 @@ impl Player {
             cam_up: glm::vec3(0.0, 0.1, 0.0),
             yaw: 90.0, // privim înainte pe Z
             pitch: 0.0,
-            last_mouse_x: 0.0,
-            last_mouse_y: 0.0,
-            first_mouse: true,
+            frame: glm::Mat3::identity(),
             mouse_lock: false,
+            mode: PlayerMode::Noclip,
+            character: CharacterController::new(),
+            eye_height: 0.5,
         }
     }
-    pub fn bind(&mut self, window: &mut Window, speed: f32) {
-        if let Some(key) = window.keyboard.find_key(glfw::Key::Tab)
-            && key.action == glfw::Action::Press
-        {
-            if self.mouse_lock {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Normal);
-                self.mouse_lock = false;
-            } else {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Disabled);
-                self.mouse_lock = true;
-            }
+    // la trecerea în Walk capsula pornește de unde e camera acum
+    pub fn set_mode(&mut self, mode: PlayerMode) {
+        if mode == PlayerMode::Walk && self.mode != PlayerMode::Walk {
+            self.character.position = self.cam_pos - self.character.up() * self.eye_height;
+            self.character.velocity = glm::Vec3::zeros();
+        }
+        if mode == PlayerMode::Noclip && self.frame != glm::Mat3::identity() {
+            // modul liber măsoară yaw și pitch față de Y, deci păstrăm privirea în axele lumii
+            let center = self.cam_center;
+            self.frame = glm::Mat3::identity();
+            self.look_at(center);
+        }
+        self.mode = mode;
+    }
+    // întoarce camera spre `target`, cu yaw și pitch potrivite pentru mouse
+    pub fn look_at(&mut self, target: glm::Vec3) {
+        let offset = target - self.cam_pos;
+        if glm::length(&offset) < 1e-6 {
+            return;
+        }
+        let dir = glm::normalize(&offset);
+        let local = glm::transpose(&self.frame) * dir;
+        self.yaw = local.z.atan2(local.x).to_degrees();
+        self.pitch = local
+            .y
+            .clamp(-1.0, 1.0)
+            .asin()
+            .to_degrees()
+            .clamp(-89.0, 89.0);
+        self.cam_center = self.cam_pos + dir;
+    }
+    // toggle_mouse prinde cursorul pentru rotirea camerei sau îl eliberează
+    fn update_mouse_lock(&mut self, window: &mut Window, input: &InputMap) {
+        if !input.pressed("toggle_mouse") {
+            return;
         }
+        self.mouse_lock = !self.mouse_lock;
+        let mode = if self.mouse_lock {
+            glfw::CursorMode::Disabled
+        } else {
+            glfw::CursorMode::Normal
+        };
+        if let Some(glfw_window) = window.glfw_window.as_mut() {
+            glfw_window.set_cursor_mode(mode);
+        }
+    }
+    pub fn bind(&mut self, window: &mut Window, input: &InputMap, speed: f32) {
+        self.update_mouse_lock(window, input);
 
         let mut up: glm::Vec3 = glm::normalize(&self.cam_up);
         let mut dir = glm::normalize(&(self.cam_center - self.cam_pos));
         //dir.y = 0.0; // asta e daca vreau sa nu mai ma misc in sus
         let right = glm::normalize(&glm::cross(&dir, &up));
-        if window.get_key(glfw::Key::W).unwrap() == glfw::Action::Press {
+        if input.down("move_forward") {
             self.cam_pos += dir * speed;
             self.cam_center += dir * speed;
         }
-        if window.get_key(glfw::Key::S).unwrap() == glfw::Action::Press {
+        if input.down("move_back") {
             self.cam_pos -= dir * speed;
             self.cam_center -= dir * speed;
         }
-        if window.get_key(glfw::Key::A).unwrap() == glfw::Action::Press {
+        if input.down("move_left") {
             self.cam_pos -= right * speed;
             self.cam_center -= right * speed;
         }
-        if window.get_key(glfw::Key::D).unwrap() == glfw::Action::Press {
+        if input.down("move_right") {
             self.cam_pos += right * speed;
             self.cam_center += right * speed;
         }
         if self.mouse_lock {
-            let xmouse: f64 = window.mouse.x;
-            let ymouse: f64 = window.mouse.y;
-
             let sensitivity = 0.1;
 
-            if self.first_mouse {
-                self.last_mouse_x = xmouse;
-                self.last_mouse_y = ymouse;
-                self.first_mouse = false;
-            }
-
-            let xoffset = ((xmouse - self.last_mouse_x) as f32) * sensitivity;
-            let yoffset = ((self.last_mouse_y - ymouse) as f32) * sensitivity;
-
-            self.last_mouse_x = xmouse;
-            self.last_mouse_y = ymouse;
+            let xoffset = input.axis("look_x") * sensitivity;
+            let yoffset = input.axis("look_y") * sensitivity;
 
             self.yaw += xoffset;
             self.pitch += yoffset;
@@ -119,26 +149,90 @@ impl Player {
         self.projection = glm::perspective_fov(70f32.to_radians(), w, h, 0.01, 100.0);
         self.view = glm::look_at(&self.cam_pos, &self.cam_center, &up);
     }
-    pub fn bind2(&mut self, window: &mut Window, speed: f32) {
-        if let Some(key) = window.keyboard.find_key(glfw::Key::Tab)
-            && key.action == glfw::Action::Press
-        {
-            if self.mouse_lock {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Normal);
-                self.mouse_lock = false;
-            } else {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Disabled);
-                self.mouse_lock = true;
-            }
+    /*
+    Ca bind, dar camera stă pe capul lui `character`: acțiunile move_* dau viteza de mers
+    (`speed` în m/s), jump sare, iar gravitația și coliziunile cu `scene` le face controllerul.
+    Camera se întoarce după sus-ul controllerului, deci pe o planetă ajunge să stea dreaptă
+    oriunde am merge, dacă `character.gravity` e actualizată înainte.
+    */
+    pub fn bind_walk(
+        &mut self,
+        window: &mut Window,
+        input: &InputMap,
+        scene: &[Target],
+        speed: f32,
+        dt: f32,
+    ) {
+        self.update_mouse_lock(window, input);
+
+        let up = self.character.up();
+        // privirea se rotește odată cu sus-ul, ca și cum am merge pe suprafață
+        let previous = self.frame;
+        self.frame = gravity::align_up(&self.frame, &up);
+        let turn = self.frame * glm::transpose(&previous);
+        let mut dir = glm::normalize(&(turn * (self.cam_center - self.cam_pos)));
+        if self.mouse_lock {
+            let sensitivity = 0.1;
+
+            let xoffset = input.axis("look_x") * sensitivity;
+            let yoffset = input.axis("look_y") * sensitivity;
+
+            self.yaw += xoffset;
+            self.pitch = (self.pitch + yoffset).clamp(-89.0, 89.0);
+
+            let yaw_rad = self.yaw.to_radians();
+            let pitch_rad = self.pitch.to_radians();
+            dir = glm::normalize(
+                &(self.frame
+                    * glm::vec3(
+                        yaw_rad.cos() * pitch_rad.cos(),
+                        pitch_rad.sin(),
+                        yaw_rad.sin() * pitch_rad.cos(),
+                    )),
+            );
+        }
+
+        // mergem în planul solului, indiferent cât de sus sau jos privim
+        let flat = dir - up * glm::dot(&dir, &up);
+        let forward = if glm::length(&flat) > 1e-6 {
+            glm::normalize(&flat)
+        } else {
+            glm::normalize(&glm::cross(&up, &glm::cross(&dir, &up)))
+        };
+        let right = glm::normalize(&glm::cross(&forward, &up));
+        let mut wish = glm::Vec3::zeros();
+        if input.down("move_forward") {
+            wish += forward;
         }
+        if input.down("move_back") {
+            wish -= forward;
+        }
+        if input.down("move_left") {
+            wish -= right;
+        }
+        if input.down("move_right") {
+            wish += right;
+        }
+        if glm::length(&wish) > 1e-6 {
+            wish = glm::normalize(&wish) * speed;
+        }
+        if input.pressed("jump") {
+            self.character.jump();
+        }
+        self.character.update(scene, wish, dt);
+
+        self.cam_pos = self.character.position + up * self.eye_height;
+        self.cam_center = self.cam_pos + dir;
+        self.cam_up = up;
+
+        let w = window.width as f32;
+        let h = window.height as f32;
+
+        self.projection = glm::perspective_fov(70f32.to_radians(), w, h, 0.01, 100.0);
+        self.view = glm::look_at(&self.cam_pos, &self.cam_center, &up);
+    }
+    pub fn bind2(&mut self, window: &mut Window, input: &InputMap, speed: f32) {
+        self.update_mouse_lock(window, input);
 
         // Calculăm vectorii de bază folosind cam_up actualizat
         let up: glm::Vec3 = glm::normalize(&self.cam_up);
@@ -148,40 +242,28 @@ impl Player {
         // Recalculăm dir să fie perpendicular pe up (pentru consistență)
         dir = glm::normalize(&glm::cross(&up, &right));
 
-        if window.get_key(glfw::Key::W).unwrap() == glfw::Action::Press {
+        if input.down("move_forward") {
             self.cam_pos += dir * speed;
             self.cam_center += dir * speed;
         }
-        if window.get_key(glfw::Key::S).unwrap() == glfw::Action::Press {
+        if input.down("move_back") {
             self.cam_pos -= dir * speed;
             self.cam_center -= dir * speed;
         }
-        if window.get_key(glfw::Key::A).unwrap() == glfw::Action::Press {
+        if input.down("move_left") {
             self.cam_pos -= right * speed;
             self.cam_center -= right * speed;
         }
-        if window.get_key(glfw::Key::D).unwrap() == glfw::Action::Press {
+        if input.down("move_right") {
             self.cam_pos += right * speed;
             self.cam_center += right * speed;
         }
 
         if self.mouse_lock {
-            let xmouse: f64 = window.mouse.x;
-            let ymouse: f64 = window.mouse.y;
-
             let sensitivity = 0.002; // radiani per pixel
 
-            if self.first_mouse {
-                self.last_mouse_x = xmouse;
-                self.last_mouse_y = ymouse;
-                self.first_mouse = false;
-            }
-
-            let xoffset = (xmouse - self.last_mouse_x) as f32 * sensitivity;
-            let yoffset = (self.last_mouse_y - ymouse) as f32 * sensitivity;
-
-            self.last_mouse_x = xmouse;
-            self.last_mouse_y = ymouse;
+            let xoffset = input.axis("look_x") * sensitivity;
+            let yoffset = input.axis("look_y") * sensitivity;
 
             // Actualizează yaw și pitch cumulative
             self.yaw += xoffset;
@@ -225,30 +307,12 @@ impl Player {
     pub fn bind_sphere(
         &mut self,
         window: &mut crate::seb::window::Window,
+        input: &InputMap,
         speed: f32,
         planet_center: glm::Vec3,
         planet_radius: f32,
     ) {
-        // --- Toggle mouse lock
-        if let Some(key) = window.keyboard.find_key(glfw::Key::Tab)
-            && key.action == glfw::Action::Press
-        {
-            if self.mouse_lock {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Normal);
-                self.mouse_lock = false;
-            } else {
-                window
-                    .glfw_window
-                    .as_mut()
-                    .unwrap()
-                    .set_cursor_mode(glfw::CursorMode::Disabled);
-                self.mouse_lock = true;
-            }
-        }
+        self.update_mouse_lock(window, input);
 
         // --- Sistemul local
         let mut up = glm::normalize(&(self.cam_pos - planet_center));
@@ -278,20 +342,10 @@ impl Player {
 
         // --- Mouse look
         if self.mouse_lock {
-            let (xmouse, ymouse) = (window.mouse.x, window.mouse.y);
             let sensitivity = 0.1;
 
-            if self.first_mouse {
-                self.last_mouse_x = xmouse;
-                self.last_mouse_y = ymouse;
-                self.first_mouse = false;
-            }
-
-            let xoffset = ((xmouse - self.last_mouse_x) as f32) * sensitivity;
-            let yoffset = ((self.last_mouse_y - ymouse) as f32) * sensitivity;
-
-            self.last_mouse_x = xmouse;
-            self.last_mouse_y = ymouse;
+            let xoffset = input.axis("look_x") * sensitivity;
+            let yoffset = input.axis("look_y") * sensitivity;
 
             // yaw
             if xoffset.abs() > 0.0 {
@@ -325,16 +379,16 @@ impl Player {
         let right_tangent = right;
 
         let mut delta = glm::vec3(0.0, 0.0, 0.0);
-        if window.get_key(glfw::Key::W).unwrap() == glfw::Action::Press {
+        if input.down("move_forward") {
             delta += forward_tangent * speed;
         }
-        if window.get_key(glfw::Key::S).unwrap() == glfw::Action::Press {
+        if input.down("move_back") {
             delta -= forward_tangent * speed;
         }
-        if window.get_key(glfw::Key::A).unwrap() == glfw::Action::Press {
+        if input.down("move_left") {
             delta -= right_tangent * speed;
         }
-        if window.get_key(glfw::Key::D).unwrap() == glfw::Action::Press {
+        if input.down("move_right") {
             delta += right_tangent * speed;
         }
 
@@ -371,30 +425,4 @@ impl Player {
         self.projection = glm::perspective_fov(70f32.to_radians(), w, h, 0.001, 1000.0);
         self.view = glm::look_at(&self.cam_pos, &self.cam_center, &self.cam_up);
     }
-    pub fn add_height(&mut self, planet_pos: glm::Vec3, h: f32) {
-        let forward = glm::normalize(&(self.cam_center - self.cam_pos));
-
-        let dist = glm::length(&(self.cam_center - self.cam_pos));
-
-        let len = glm::length(&(self.cam_pos - planet_pos)) + h;
-        let dir = glm::normalize(&(self.cam_pos - planet_pos));
-        self.cam_pos = dir * len;
-
-        self.cam_center = self.cam_pos + forward * dist;
-    }
-    pub fn set_height(&mut self, ground_pos: glm::Vec3, planet_pos: glm::Vec3) {
-        let radial = glm::normalize(&(self.cam_pos - planet_pos));
-
-        let r_ground = glm::length(&(ground_pos - planet_pos)); // f32
-
-        let target_r = r_ground; // tot f32
-
-        let forward = glm::normalize(&(self.cam_center - self.cam_pos));
-
-        let dist = glm::length(&(self.cam_center - self.cam_pos));
-
-        self.cam_pos = planet_pos + radial * target_r;
-
-        self.cam_center = self.cam_pos + forward * dist;
-    }
 }
@@ -0,0 +1,406 @@
+use crate::seb::seb::{
+    Program, ShaderDiagnostic, ShaderError, ShaderStage, link_compute, link_program,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+// de aici se rezolvă `#include "…"`
+pub const ASSETS: &str = "./assets";
+
+// de unde vine o linie din sursa expandată
+#[derive(Clone, Debug)]
+pub struct SourceLine {
+    pub file: String,
+    pub line: usize,
+}
+
+pub struct Source {
+    pub code: String,
+    // o intrare pentru fiecare linie din `code`
+    pub lines: Vec<SourceLine>,
+    // toate fișierele citite, cu cel principal primul
+    pub files: Vec<String>,
+}
+
+impl Source {
+    // erorile driverului se referă la sursa expandată, le mutăm în fișierele originale
+    pub fn remap(&self, mut error: ShaderError) -> ShaderError {
+        for diagnostic in &mut error.diagnostics {
+            let Some(origin) = diagnostic
+                .line
+                .and_then(|line| self.lines.get(line.checked_sub(1)?))
+            else {
+                continue;
+            };
+            if origin.file != error.file {
+                diagnostic.file = Some(origin.file.clone());
+            }
+            diagnostic.line = Some(origin.line);
+        }
+        error
+    }
+}
+
+/*
+Preprocesor peste GLSL, înainte de driver:
+    #include "shaders/shadow.glsl"   relativ la `root`, fiecare fișier o singură dată
+                                     (gardă automată, deci și fără #ifndef)
+    defines                          puse după #version, pentru variante:
+                                     [("SHADOWS", "1"), ("HAS_BASE_TEXTURE", "")]
+Programele din `program` sunt păstrate după fișiere și defines, așa că aceeași variantă
+se compilează o singură dată.
+*/
+pub struct Preprocessor {
+    pub root: String,
+    programs: HashMap<String, Program>,
+}
+
+impl Drop for Preprocessor {
+    fn drop(&mut self) {
+        for program in self.programs.values() {
+            unsafe {
+                gl::DeleteProgram(program.id);
+            }
+        }
+    }
+}
+
+impl Preprocessor {
+    pub fn new(root: &str) -> Self {
+        Self {
+            root: root.to_string(),
+            programs: HashMap::new(),
+        }
+    }
+    pub fn expand(
+        &self,
+        stage: ShaderStage,
+        path: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Source, ShaderError> {
+        let code = std::fs::read_to_string(path)
+            .map_err(|e| ShaderError::new(stage, path, &e.to_string(), None))?;
+        self.expand_str(stage, path, &code, defines)
+    }
+    // pentru shaderele scrise în cod; `name` apare în erori în locul fișierului
+    pub fn expand_str(
+        &self,
+        stage: ShaderStage,
+        name: &str,
+        code: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Source, ShaderError> {
+        let mut source = Source {
+            code: String::new(),
+            lines: Vec::new(),
+            files: vec![name.to_string()],
+        };
+        let mut included = HashSet::new();
+        // #version trebuie să rămână primul, defines vin imediat după el
+        let version = code
+            .lines()
+            .position(|l| l.trim_start().starts_with("#version"));
+        if version.is_none() {
+            push_defines(&mut source, defines, name, 1);
+        }
+        for (index, line) in code.lines().enumerate() {
+            if Some(index) == version {
+                push_line(&mut source, line, name, index + 1);
+                push_defines(&mut source, defines, name, index + 1);
+                continue;
+            }
+            self.expand_line(stage, &mut source, &mut included, name, index + 1, line)?;
+        }
+        Ok(source)
+    }
+    fn expand_line(
+        &self,
+        stage: ShaderStage,
+        source: &mut Source,
+        included: &mut HashSet<String>,
+        file: &str,
+        number: usize,
+        line: &str,
+    ) -> Result<(), ShaderError> {
+        let Some(rest) = line.trim_start().strip_prefix("#include") else {
+            push_line(source, line, file, number);
+            return Ok(());
+        };
+        let main = source.files[0].clone();
+        let error = |message: String| ShaderError {
+            stage,
+            file: main.clone(),
+            log: message.clone(),
+            diagnostics: vec![ShaderDiagnostic {
+                file: Some(file.to_string()),
+                line: Some(number),
+                column: None,
+                message,
+                source_line: Some(line.to_string()),
+            }],
+        };
+        let name = rest
+            .trim()
+            .strip_prefix('"')
+            .and_then(|r| r.strip_suffix('"'))
+            .ok_or_else(|| error("#include așteaptă \"fișier\"".to_string()))?;
+        let path = Path::new(&self.root).join(name);
+        let path = path.to_string_lossy().into_owned();
+        if !included.insert(path.clone()) {
+            // deja inclus, linia rămâne goală ca numerotarea să nu se schimbe
+            push_line(source, "", file, number);
+            return Ok(());
+        }
+        let code = std::fs::read_to_string(&path)
+            .map_err(|e| error(format!("#include \"{}\": {}", name, e)))?;
+        source.files.push(path.clone());
+        for (index, included_line) in code.lines().enumerate() {
+            self.expand_line(stage, source, included, &path, index + 1, included_line)?;
+        }
+        Ok(())
+    }
+    // programul e al celui care îl cere
+    pub fn compile(
+        &self,
+        vertex: &str,
+        fragment: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Program, ShaderError> {
+        let vsource = self.expand(ShaderStage::Vertex, vertex, defines)?;
+        let fsource = self.expand(ShaderStage::Fragment, fragment, defines)?;
+        compile_sources(&vsource, &fsource)
+    }
+    pub fn compile_compute(
+        &self,
+        compute: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Program, ShaderError> {
+        let source = self.expand(ShaderStage::Compute, compute, defines)?;
+        link_compute(&source.files[0], &source.code).map_err(|e| match e.stage {
+            ShaderStage::Compute => source.remap(e),
+            _ => e,
+        })
+    }
+    // varianta păstrată, compilată doar prima dată
+    pub fn program(
+        &mut self,
+        vertex: &str,
+        fragment: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<u32, ShaderError> {
+        let key = permutation_key(vertex, fragment, defines);
+        if let Some(program) = self.programs.get(&key) {
+            return Ok(program.id);
+        }
+        let program = self.compile(vertex, fragment, defines)?;
+        self.programs.insert(key, program);
+        Ok(program.id)
+    }
+}
+
+pub fn compile_sources(vertex: &Source, fragment: &Source) -> Result<Program, ShaderError> {
+    link_program(&[
+        (ShaderStage::Vertex, &vertex.files[0], &vertex.code),
+        (ShaderStage::Fragment, &fragment.files[0], &fragment.code),
+    ])
+    .map_err(|e| match e.stage {
+        ShaderStage::Vertex => vertex.remap(e),
+        ShaderStage::Fragment => fragment.remap(e),
+        ShaderStage::Compute | ShaderStage::Link => e,
+    })
+}
+
+// ordinea în care sunt date defines nu contează
+pub fn permutation_key(vertex: &str, fragment: &str, defines: &[(&str, &str)]) -> String {
+    let mut defines: Vec<String> = defines
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    defines.sort();
+    format!("{}|{}|{}", vertex, fragment, defines.join(","))
+}
+
+fn push_line(source: &mut Source, line: &str, file: &str, number: usize) {
+    source.code.push_str(line);
+    source.code.push('\n');
+    source.lines.push(SourceLine {
+        file: file.to_string(),
+        line: number,
+    });
+}
+
+fn push_defines(source: &mut Source, defines: &[(&str, &str)], file: &str, number: usize) {
+    for (name, value) in defines {
+        push_line(source, &format!("#define {} {}", name, value), file, number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // un director temporar cu fișierele date, unul pentru fiecare test
+    fn root(test: &str, files: &[(&str, &str)]) -> String {
+        let dir =
+            std::env::temp_dir().join(format!("preprocessor-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, code) in files {
+            std::fs::write(dir.join(name), code).unwrap();
+        }
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn origins(source: &Source) -> Vec<(String, usize)> {
+        source
+            .lines
+            .iter()
+            .map(|l| (l.file.clone(), l.line))
+            .collect()
+    }
+
+    const MAIN: &str = "// înainte de versiune\n\
+                        #version 330 core\n\
+                        #include \"a.glsl\"\n\
+                        #include \"b.glsl\"\n\
+                        void main() {}";
+
+    #[test]
+    fn includes_once_and_defines_after_version() {
+        let root = root(
+            "once",
+            &[
+                ("a.glsl", "float a() { return b(); }\n#include \"b.glsl\""),
+                ("b.glsl", "float b();"),
+            ],
+        );
+        let preprocessor = Preprocessor::new(&root);
+        let defines = [("SHADOWS", "1"), ("HAS_BASE_TEXTURE", "")];
+        let source = preprocessor
+            .expand_str(ShaderStage::Fragment, "main.frag", MAIN, &defines)
+            .unwrap();
+        let a = Path::new(&root)
+            .join("a.glsl")
+            .to_string_lossy()
+            .into_owned();
+        let b = Path::new(&root)
+            .join("b.glsl")
+            .to_string_lossy()
+            .into_owned();
+
+        let lines: Vec<&str> = source.code.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "// înainte de versiune",
+                "#version 330 core",
+                "#define SHADOWS 1",
+                "#define HAS_BASE_TEXTURE ",
+                "float a() { return b(); }",
+                "float b();",
+                "",
+                "void main() {}",
+            ]
+        );
+        let main = "main.frag".to_string();
+        assert_eq!(
+            origins(&source),
+            [
+                (main.clone(), 1),
+                (main.clone(), 2),
+                (main.clone(), 2),
+                (main.clone(), 2),
+                (a.clone(), 1),
+                (b.clone(), 1),
+                // al doilea #include "b.glsl" rămâne o linie goală
+                (main.clone(), 4),
+                (main.clone(), 5),
+            ]
+        );
+        assert_eq!(source.files, [main, a, b]);
+    }
+
+    #[test]
+    fn defines_first_without_version() {
+        let preprocessor = Preprocessor::new(&root("noversion", &[]));
+        let source = preprocessor
+            .expand_str(ShaderStage::Vertex, "v", "void main() {}", &[("A", "2")])
+            .unwrap();
+        assert_eq!(source.code, "#define A 2\nvoid main() {}\n");
+        assert_eq!(source.lines.len(), 2);
+    }
+
+    #[test]
+    fn include_errors_point_at_the_line() {
+        let root = root("errors", &[("bad.glsl", "\n#include <missing.glsl>")]);
+        let preprocessor = Preprocessor::new(&root);
+
+        let error = preprocessor
+            .expand_str(ShaderStage::Vertex, "v", "#include \"nu_exista.glsl\"", &[])
+            .err()
+            .unwrap();
+        assert_eq!(error.file, "v");
+        assert_eq!(error.diagnostics[0].line, Some(1));
+        assert!(error.log.contains("nu_exista.glsl"));
+
+        let error = preprocessor
+            .expand_str(ShaderStage::Vertex, "v", "\n\n#include \"bad.glsl\"", &[])
+            .err()
+            .unwrap();
+        let bad = Path::new(&root)
+            .join("bad.glsl")
+            .to_string_lossy()
+            .into_owned();
+        assert_eq!(error.diagnostics[0].file, Some(bad));
+        assert_eq!(error.diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn remap_moves_errors_to_the_original_files() {
+        let root = root(
+            "remap",
+            &[
+                ("a.glsl", "float a() { return b(); }\n#include \"b.glsl\""),
+                ("b.glsl", "float b();"),
+            ],
+        );
+        let preprocessor = Preprocessor::new(&root);
+        let source = preprocessor
+            .expand_str(
+                ShaderStage::Fragment,
+                "main.frag",
+                MAIN,
+                &[("SHADOWS", "1")],
+            )
+            .unwrap();
+        // liniile 5 și 7 din sursa expandată: b.glsl:1 și main.frag:5
+        let log = "0:5(7): error: `b' undeclared\n0:7(1): error: syntax error\nfără poziție";
+        let error = source.remap(ShaderError::new(
+            ShaderStage::Fragment,
+            "main.frag",
+            log,
+            Some(&source.code),
+        ));
+        let b = Path::new(&root)
+            .join("b.glsl")
+            .to_string_lossy()
+            .into_owned();
+        let d = &error.diagnostics;
+        assert_eq!(
+            (d[0].file.clone(), d[0].line, d[0].column),
+            (Some(b), Some(1), Some(7))
+        );
+        // erorile din fișierul principal nu își repetă numele
+        assert_eq!((d[1].file.clone(), d[1].line), (None, Some(5)));
+        assert_eq!(d[1].source_line.as_deref(), Some("void main() {}"));
+        assert_eq!((d[2].file.clone(), d[2].line), (None, None));
+    }
+
+    #[test]
+    fn permutation_key_ignores_define_order() {
+        let a = permutation_key("v", "f", &[("A", "1"), ("B", "")]);
+        let b = permutation_key("v", "f", &[("B", ""), ("A", "1")]);
+        assert_eq!(a, b);
+        assert_ne!(a, permutation_key("v", "f", &[("A", "2"), ("B", "")]));
+        assert_ne!(a, permutation_key("f", "v", &[("A", "1"), ("B", "")]));
+    }
+}
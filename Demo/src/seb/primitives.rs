@@ -1,4 +1,8 @@
-use crate::seb::seb::{ToCStr, create_shader_from};
+use crate::seb::collision;
+use crate::seb::gltfmodel::Mesh;
+use crate::seb::hull;
+use crate::seb::seb::{ShaderError, create_shader_from};
+use crate::seb::uniforms::UniformCache;
 use nalgebra_glm as glm;
 
 #[repr(C)]
@@ -30,9 +34,10 @@ pub struct LineRenderer {
     vao: u32,
     ivbo: u32,
     shader: u32,
+    uniforms: UniformCache,
 }
 impl LineRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut shader = 0;
         let mut ivbo = 0;
@@ -111,26 +116,27 @@ impl LineRenderer {
                         fragColor = color;
                     }
                 "#;
-            shader = create_shader_from(vshader, fshader);
+            shader = create_shader_from(vshader, fshader)?.id;
         }
 
-        Self {
+        Ok(Self {
             vao: vao,
             ivbo: ivbo,
             shader: shader,
-        }
+            uniforms: UniformCache::new(),
+        })
     }
     pub fn draw(&self, projection: glm::Mat4, view: glm::Mat4, lines: &mut [Line]) {
         unsafe {
             gl::UseProgram(self.shader);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
@@ -190,9 +196,10 @@ pub struct VectorRenderer {
     ivbo: u32,
     indices_len: i32,
     shader: u32,
+    uniforms: UniformCache,
 }
 impl VectorRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut vbo = 0;
         let mut ivbo = 0;
@@ -311,15 +318,16 @@ impl VectorRenderer {
                         fragColor = col;
                     }
                 "#;
-            shader = create_shader_from(vshader, fshader);
+            shader = create_shader_from(vshader, fshader)?.id;
         }
 
-        Self {
+        Ok(Self {
             vao: vao,
             ivbo: ivbo,
             indices_len: indices.len() as i32,
             shader: shader,
-        }
+            uniforms: UniformCache::new(),
+        })
     }
     fn get_vector_model(vec: &Vector) -> glm::Mat4 {
         let start = vec.position;
@@ -377,13 +385,13 @@ impl VectorRenderer {
         unsafe {
             gl::UseProgram(self.shader);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
@@ -477,9 +485,10 @@ pub struct RectangleRenderer {
     ivbo: u32,
     indices_len: i32,
     shader: u32,
+    uniforms: UniformCache,
 }
 impl RectangleRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut vbo = 0;
         let mut ivbo = 0;
@@ -579,15 +588,16 @@ impl RectangleRenderer {
                         fragColor = col;
                     }
                 "#;
-            shader = create_shader_from(vshader, fshader);
+            shader = create_shader_from(vshader, fshader)?.id;
         }
 
-        Self {
+        Ok(Self {
             vao: vao,
             ivbo: ivbo,
             indices_len: indices.len() as i32,
             shader: shader,
-        }
+            uniforms: UniformCache::new(),
+        })
     }
     pub fn draw(&self, projection: glm::Mat4, view: glm::Mat4, rectangles: &mut [Rectangle]) {
         let mut irectangles: Vec<RectangleInstance> = Vec::new();
@@ -602,13 +612,13 @@ impl RectangleRenderer {
         unsafe {
             gl::UseProgram(self.shader);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
@@ -682,10 +692,11 @@ pub struct SphereRenderer {
     pub ivbo: u32,
     pub indices_len: i32,
     pub shader: u32,
+    uniforms: UniformCache,
 }
 
 impl SphereRenderer {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut vbo = 0;
         let mut ivbo = 0;
@@ -810,15 +821,16 @@ impl SphereRenderer {
                 }
             "#;
 
-            shader = create_shader_from(vshader, fshader);
+            shader = create_shader_from(vshader, fshader)?.id;
         }
 
-        Self {
+        Ok(Self {
             vao,
             ivbo,
             indices_len: indices.len() as i32,
             shader,
-        }
+            uniforms: UniformCache::new(),
+        })
     }
 
     pub fn draw(&self, projection: glm::Mat4, view: glm::Mat4, spheres: &mut [Sphere]) {
@@ -834,13 +846,13 @@ impl SphereRenderer {
         unsafe {
             gl::UseProgram(self.shader);
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
@@ -870,3 +882,249 @@ impl SphereRenderer {
         }
     }
 }
+
+// Formele de mai jos sunt doar pentru coliziuni (nu au încă renderer).
+// Capsula și cilindrul sunt pe axa Y locală, rotite cu aceleași unghiuri ca Rectangle.
+// rotația e cea din collision::euler_to_matrix, ca forma desenată să fie cea din coliziuni
+fn euler_model(position: &glm::Vec3, x_angle: f32, y_angle: f32, z_angle: f32) -> glm::Mat4 {
+    let rotation = glm::mat3_to_mat4(&collision::euler_to_matrix(x_angle, y_angle, z_angle));
+    glm::translate(&glm::Mat4::identity(), position) * rotation
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Capsule {
+    pub position: glm::Vec3,
+    // jumătate din lungimea segmentului din mijloc, fără capete
+    pub half_height: f32,
+    pub radius: f32,
+    pub x_angle: f32,
+    pub y_angle: f32,
+    pub z_angle: f32,
+    pub color: glm::Vec4,
+}
+impl Capsule {
+    pub fn new() -> Self {
+        Self {
+            position: glm::Vec3::zeros(),
+            half_height: 0.5,
+            radius: 0.5,
+            x_angle: 0f32,
+            y_angle: 0f32,
+            z_angle: 0f32,
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+    // fără scală, doar poziție și rotație
+    pub fn get_model(&self) -> glm::Mat4 {
+        euler_model(&self.position, self.x_angle, self.y_angle, self.z_angle)
+    }
+    // capetele segmentului din mijloc
+    pub fn segment(&self) -> (glm::Vec3, glm::Vec3) {
+        let up = (self.get_model() * glm::vec4(0.0, self.half_height, 0.0, 0.0)).xyz();
+        (self.position - up, self.position + up)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cylinder {
+    pub position: glm::Vec3,
+    pub half_height: f32,
+    pub radius: f32,
+    pub x_angle: f32,
+    pub y_angle: f32,
+    pub z_angle: f32,
+    pub color: glm::Vec4,
+}
+impl Cylinder {
+    pub fn new() -> Self {
+        Self {
+            position: glm::Vec3::zeros(),
+            half_height: 0.5,
+            radius: 0.5,
+            x_angle: 0f32,
+            y_angle: 0f32,
+            z_angle: 0f32,
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+    pub fn get_model(&self) -> glm::Mat4 {
+        euler_model(&self.position, self.x_angle, self.y_angle, self.z_angle)
+    }
+}
+
+// Plan infinit: punctele cu dot(normal, p) <= distance sunt "sub" plan (solide)
+#[derive(Clone, Copy, Debug)]
+pub struct Plane {
+    pub normal: glm::Vec3,
+    pub distance: f32,
+    pub color: glm::Vec4,
+}
+impl Plane {
+    pub fn new() -> Self {
+        Self {
+            normal: glm::vec3(0.0, 1.0, 0.0),
+            distance: 0.0,
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+    pub fn from_point(point: glm::Vec3, normal: glm::Vec3) -> Self {
+        let normal = glm::normalize(&normal);
+        Self {
+            normal,
+            distance: glm::dot(&normal, &point),
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+    // pozitiv deasupra planului, negativ sub
+    pub fn signed_distance(&self, point: &glm::Vec3) -> f32 {
+        glm::dot(&self.normal, point) - self.distance
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ConvexHull {
+    pub position: glm::Vec3,
+    // vârfurile în coordonate locale, față de `position`
+    pub points: Vec<glm::Vec3>,
+    // triunghiurile înfășurătorii, cu normala în afară
+    pub faces: Vec<[u32; 3]>,
+    pub x_angle: f32,
+    pub y_angle: f32,
+    pub z_angle: f32,
+    pub color: glm::Vec4,
+}
+impl ConvexHull {
+    pub fn new() -> Self {
+        Self {
+            position: glm::Vec3::zeros(),
+            points: Vec::new(),
+            faces: Vec::new(),
+            x_angle: 0f32,
+            y_angle: 0f32,
+            z_angle: 0f32,
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+    // calculează înfășurătoarea și mută originea în centrul ei
+    pub fn from_points(points: &[glm::Vec3]) -> Self {
+        let (mut points, faces) = hull::quickhull(points);
+        let center = if points.is_empty() {
+            glm::Vec3::zeros()
+        } else {
+            points.iter().sum::<glm::Vec3>() / points.len() as f32
+        };
+        for p in &mut points {
+            *p -= center;
+        }
+        Self {
+            position: center,
+            points,
+            faces,
+            ..Self::new()
+        }
+    }
+    // folosește transformarea nodului, la fel ca la desenare
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let model = mesh.translation * mesh.rotation * mesh.scale;
+        let points: Vec<glm::Vec3> = mesh
+            .position_coords
+            .chunks_exact(3)
+            .map(|p| (model * glm::vec4(p[0], p[1], p[2], 1.0)).xyz())
+            .collect();
+        Self::from_points(&points)
+    }
+    pub fn get_model(&self) -> glm::Mat4 {
+        euler_model(&self.position, self.x_angle, self.y_angle, self.z_angle)
+    }
+    pub fn world_points(&self) -> Vec<glm::Vec3> {
+        let model = self.get_model();
+        self.points
+            .iter()
+            .map(|p| (model * glm::vec4(p.x, p.y, p.z, 1.0)).xyz())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::Collider;
+
+    fn mesh(position_coords: Vec<f32>, translation: glm::Vec3, scale: f32) -> Mesh {
+        Mesh {
+            index: 0,
+            name: String::new(),
+            position_coords,
+            texture_coords: Vec::new(),
+            normal_coords: Vec::new(),
+            indices: Vec::new(),
+            material: None,
+            vao: 0,
+            vbo: 0,
+            nbo: 0,
+            tbo: 0,
+            ebo: 0,
+            translation: glm::translation(&translation),
+            rotation: glm::Mat4::identity(),
+            scale: glm::scaling(&glm::vec3(scale, scale, scale)),
+        }
+    }
+
+    #[test]
+    fn hull_from_mesh_uses_the_node_transform() {
+        // colțurile unui cub, un punct din mijloc și unul repetat
+        let mut coords = Vec::new();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    coords.extend([x, y, z]);
+                }
+            }
+        }
+        coords.extend([0.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let hull = ConvexHull::from_mesh(&mesh(coords, glm::vec3(5.0, 0.0, -2.0), 2.0));
+
+        assert!(glm::distance(&hull.position, &glm::vec3(5.0, 0.0, -2.0)) < 1e-5);
+        assert_eq!(hull.points.len(), 8);
+        assert_eq!(hull.faces.len(), 12);
+        for p in hull.world_points() {
+            let local = p - hull.position;
+            assert!((local.abs() - glm::vec3(2.0, 2.0, 2.0)).norm() < 1e-5);
+        }
+        // normalele fețelor ies din centru
+        for &[a, b, c] in &hull.faces {
+            let (a, b, c) = (
+                hull.points[a as usize],
+                hull.points[b as usize],
+                hull.points[c as usize],
+            );
+            assert!(glm::dot(&glm::cross(&(b - a), &(c - a)), &a) > 0.0);
+        }
+    }
+
+    #[test]
+    fn drawn_rotation_matches_the_collider() {
+        let mut hull = ConvexHull::from_points(&[
+            glm::vec3(0.0, 0.0, 0.0),
+            glm::vec3(1.0, 0.0, 0.0),
+            glm::vec3(0.0, 2.0, 0.0),
+            glm::vec3(0.0, 0.0, 3.0),
+        ]);
+        hull.x_angle = 30.0;
+        hull.y_angle = -50.0;
+        hull.z_angle = 70.0;
+        let mut capsule = Capsule::new();
+        capsule.x_angle = hull.x_angle;
+        capsule.y_angle = hull.y_angle;
+        capsule.z_angle = hull.z_angle;
+
+        let expected = collision::euler_to_matrix(30.0, -50.0, 70.0);
+        for (model, collider) in [
+            (hull.get_model(), Collider::ConvexHull(hull.clone())),
+            (capsule.get_model(), Collider::Capsule(capsule)),
+        ] {
+            assert!((glm::mat4_to_mat3(&model) - expected).norm() < 1e-5);
+            assert!((collider.rotation() - expected).norm() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,987 @@
+use crate::seb::broadphase::Aabb;
+use crate::seb::bvh::MeshBvh;
+use crate::seb::collision::{Collider, euler_to_matrix};
+use crate::seb::gjk::gjk_distance;
+use crate::seb::gltfmodel::GLTFModel;
+use crate::seb::model::Map;
+use crate::seb::planet::Planet;
+use nalgebra_glm as glm;
+
+const CAST_ITERATIONS: usize = 64;
+const CAST_TOLERANCE: f32 = 1e-4;
+
+// ce anume din obiect a fost lovit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitPart {
+    Shape,
+    // pentru hărți și planete mesh e mereu 0, iar index = celulă * 2 + triunghi
+    Triangle { mesh: usize, index: usize },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub distance: f32,
+    // punctul de pe suprafață atins (la spherecast e punctul de contact, nu centrul sferei)
+    pub point: glm::Vec3,
+    pub normal: glm::Vec3,
+    // indexul în lista de ținte dată la raycast
+    pub object: usize,
+    pub part: HitPart,
+}
+
+pub enum Target<'a> {
+    Collider(&'a Collider),
+    Map(&'a Map),
+    Planet(&'a Planet),
+    Model(&'a GLTFModel),
+    // varianta cu BVH a lui Model, mult mai rapidă pentru hărți mari
+    Mesh(&'a MeshBvh),
+}
+impl<'a> From<&'a Collider> for Target<'a> {
+    fn from(value: &'a Collider) -> Self {
+        Target::Collider(value)
+    }
+}
+impl<'a> From<&'a Map> for Target<'a> {
+    fn from(value: &'a Map) -> Self {
+        Target::Map(value)
+    }
+}
+impl<'a> From<&'a Planet> for Target<'a> {
+    fn from(value: &'a Planet) -> Self {
+        Target::Planet(value)
+    }
+}
+impl<'a> From<&'a GLTFModel> for Target<'a> {
+    fn from(value: &'a GLTFModel) -> Self {
+        Target::Model(value)
+    }
+}
+impl<'a> From<&'a MeshBvh> for Target<'a> {
+    fn from(value: &'a MeshBvh) -> Self {
+        Target::Mesh(value)
+    }
+}
+
+// lovitura internă, fără obiect: (distanță, normală, parte)
+type Cast = (f32, glm::Vec3, HitPart);
+
+fn closer(best: Option<Cast>, candidate: Option<Cast>) -> Option<Cast> {
+    match (best, candidate) {
+        (Some(b), Some(c)) if c.0 < b.0 => Some(c),
+        (None, c) => c,
+        (b, _) => b,
+    }
+}
+
+/*
+Cea mai apropiată lovitură a razei origin + dir * t, cu t în [0, max_distance].
+Dacă originea e deja în interiorul unui obiect lovitura e la distanța 0.
+*/
+pub fn raycast(
+    targets: &[Target],
+    origin: glm::Vec3,
+    dir: glm::Vec3,
+    max_distance: f32,
+) -> Option<RayHit> {
+    spherecast(targets, origin, dir, 0.0, max_distance)
+}
+
+// ca raycast, dar mută o sferă de rază `radius` de-a lungul razei
+pub fn spherecast(
+    targets: &[Target],
+    origin: glm::Vec3,
+    dir: glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<RayHit> {
+    if glm::length2(&dir) < 1e-12 || max_distance < 0.0 {
+        return None;
+    }
+    let dir = glm::normalize(&dir);
+    let radius = radius.max(0.0);
+    let mut best: Option<(usize, Cast)> = None;
+    for (object, target) in targets.iter().enumerate() {
+        let limit = best.map_or(max_distance, |(_, b)| b.0);
+        let cast = match target {
+            Target::Collider(c) => cast_collider(c, &origin, &dir, radius, limit),
+            Target::Map(m) => cast_map(m, &origin, &dir, radius, limit),
+            Target::Planet(p) => cast_planet(p, &origin, &dir, radius, limit),
+            Target::Model(m) => cast_model(m, &origin, &dir, radius, limit),
+            Target::Mesh(m) => m
+                .cast(&origin, &dir, radius, limit)
+                .map(|(t, n, triangle)| {
+                    let (mesh, index) = m.sources[triangle];
+                    (t, n, HitPart::Triangle { mesh, index })
+                }),
+        };
+        if let Some(c) = cast
+            && c.0 <= limit
+        {
+            best = Some((object, c));
+        }
+    }
+    best.map(|(object, (distance, normal, part))| RayHit {
+        distance,
+        point: origin + dir * distance - normal * radius,
+        normal,
+        object,
+        part,
+    })
+}
+
+// raza care pleacă din cameră prin pixelul (x, y) al ferestrei, pentru selecția cu mouse-ul
+pub fn screen_ray(
+    x: f64,
+    y: f64,
+    width: u32,
+    height: u32,
+    projection: &glm::Mat4,
+    view: &glm::Mat4,
+) -> (glm::Vec3, glm::Vec3) {
+    let ndc_x = (2.0 * x / width as f64 - 1.0) as f32;
+    let ndc_y = (1.0 - 2.0 * y / height as f64) as f32;
+    let inverse = glm::inverse(&(projection * view));
+    let unproject = |z: f32| {
+        let p = inverse * glm::vec4(ndc_x, ndc_y, z, 1.0);
+        glm::vec3(p.x, p.y, p.z) / p.w
+    };
+    let near = unproject(-1.0);
+    let far = unproject(1.0);
+    (near, glm::normalize(&(far - near)))
+}
+
+pub fn cast_collider(
+    collider: &Collider,
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<Cast> {
+    let hit = match collider {
+        // planul e un semispațiu plin
+        Collider::Plane(p) => {
+            let start = p.signed_distance(origin) - radius;
+            let speed = glm::dot(&p.normal, dir);
+            if start <= 0.0 {
+                Some((0.0, p.normal))
+            } else if speed >= 0.0 {
+                None
+            } else {
+                Some((start / -speed, p.normal))
+            }
+        }
+        _ => {
+            let (t_enter, _) = collider.aabb().expanded(radius).ray_interval(origin, dir)?;
+            if t_enter > max_distance {
+                return None;
+            }
+            match collider {
+                Collider::Sphere(s) => {
+                    ray_sphere(origin, dir, &s.position, s.scale + radius, max_distance)
+                }
+                Collider::Capsule(c) => {
+                    let (a, b) = c.segment();
+                    ray_capsule(origin, dir, &a, &b, c.radius + radius, max_distance)
+                }
+                _ => {
+                    let (shape, margin) = collider.convex_shape();
+                    cast_convex(
+                        &|d: &glm::Vec3| shape.support(d),
+                        margin + radius,
+                        origin,
+                        dir,
+                        max_distance,
+                    )
+                }
+            }
+        }
+    };
+    hit.filter(|(t, _)| *t <= max_distance)
+        .map(|(t, n)| (t, n, HitPart::Shape))
+}
+
+/*
+Avansare conservativă cu GJK (van den Bergen): la fiecare pas distanța până la formă
+ne spune cât putem înainta pe rază fără s-o atingem.
+*/
+fn cast_convex(
+    support: &dyn Fn(&glm::Vec3) -> glm::Vec3,
+    margin: f32,
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    max_distance: f32,
+) -> Option<(f32, glm::Vec3)> {
+    let mut t = 0.0;
+    let mut normal = -dir;
+    for _ in 0..CAST_ITERATIONS {
+        let p = origin + dir * t;
+        let result = gjk_distance(support, &|_: &glm::Vec3| p, *dir);
+        if result.distance > 1e-6 {
+            normal = (p - result.point_a) / result.distance;
+        }
+        let gap = result.distance - margin;
+        if gap <= CAST_TOLERANCE {
+            return Some((t, normal));
+        }
+        let speed = -glm::dot(dir, &normal);
+        if speed <= 1e-6 {
+            return None;
+        }
+        t += gap / speed;
+        if t > max_distance {
+            return None;
+        }
+    }
+    Some((t, normal))
+}
+
+pub fn ray_sphere(
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    center: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<(f32, glm::Vec3)> {
+    let oc = origin - center;
+    let b = glm::dot(&oc, dir);
+    let c = glm::dot(&oc, &oc) - radius * radius;
+    if c <= 0.0 {
+        return Some((0.0, normal_or(&oc, dir)));
+    }
+    let disc = b * b - c;
+    if b > 0.0 || disc < 0.0 {
+        return None;
+    }
+    let t = -b - disc.sqrt();
+    if t > max_distance {
+        return None;
+    }
+    Some((t, (origin + dir * t - center) / radius))
+}
+
+// capsula e reuniunea cilindrului cu cele două sfere, deci prima intrare e minimul intrărilor
+pub fn ray_capsule(
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    a: &glm::Vec3,
+    b: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<(f32, glm::Vec3)> {
+    let ab = b - a;
+    let ab2 = glm::dot(&ab, &ab);
+    if ab2 < 1e-12 {
+        return ray_sphere(origin, dir, a, radius, max_distance);
+    }
+    let ao = origin - a;
+    let s = (glm::dot(&ao, &ab) / ab2).clamp(0.0, 1.0);
+    let inside = origin - (a + ab * s);
+    if glm::length2(&inside) <= radius * radius {
+        return Some((0.0, normal_or(&inside, dir)));
+    }
+
+    let d_perp = dir - ab * (glm::dot(dir, &ab) / ab2);
+    let o_perp = ao - ab * (glm::dot(&ao, &ab) / ab2);
+    let qa = glm::dot(&d_perp, &d_perp);
+    let qb = glm::dot(&d_perp, &o_perp);
+    let qc = glm::dot(&o_perp, &o_perp) - radius * radius;
+    let disc = qb * qb - qa * qc;
+    if qa > 1e-12 && disc >= 0.0 {
+        let t = (-qb - disc.sqrt()) / qa;
+        let p = origin + dir * t;
+        let s = glm::dot(&(p - a), &ab) / ab2;
+        if t >= 0.0 && (0.0..=1.0).contains(&s) {
+            return (t <= max_distance).then(|| (t, (p - (a + ab * s)) / radius));
+        }
+    }
+    let hit_a = ray_sphere(origin, dir, a, radius, max_distance);
+    let hit_b = ray_sphere(origin, dir, b, radius, max_distance);
+    match (hit_a, hit_b) {
+        (Some(x), Some(y)) => Some(if x.0 <= y.0 { x } else { y }),
+        (x, y) => x.or(y),
+    }
+}
+
+// Möller–Trumbore, pe ambele fețe; normala e întoarsă spre rază
+pub fn ray_triangle(
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    [a, b, c]: &[glm::Vec3; 3],
+    max_distance: f32,
+) -> Option<(f32, glm::Vec3)> {
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = glm::cross(dir, &e2);
+    let det = glm::dot(&e1, &p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    let s = origin - a;
+    let u = glm::dot(&s, &p) * inv;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = glm::cross(&s, &e1);
+    let v = glm::dot(dir, &q) * inv;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = glm::dot(&e2, &q) * inv;
+    if t < 0.0 || t > max_distance {
+        return None;
+    }
+    let normal = glm::normalize(&glm::cross(&e1, &e2));
+    Some((
+        t,
+        if glm::dot(&normal, dir) > 0.0 {
+            -normal
+        } else {
+            normal
+        },
+    ))
+}
+
+/*
+Sferă mișcată contra unui triunghi: întâi fața (planul împins cu raza),
+apoi muchiile ca niște capsule, care acoperă și vârfurile.
+*/
+pub fn sphere_triangle(
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    radius: f32,
+    triangle: &[glm::Vec3; 3],
+    max_distance: f32,
+) -> Option<(f32, glm::Vec3)> {
+    if radius <= 0.0 {
+        return ray_triangle(origin, dir, triangle, max_distance);
+    }
+    let [a, b, c] = triangle;
+    let cross = glm::cross(&(b - a), &(c - a));
+    if glm::length2(&cross) > 1e-12 {
+        let mut normal = glm::normalize(&cross);
+        let mut start = glm::dot(&normal, &(origin - a));
+        if start < 0.0 {
+            normal = -normal;
+            start = -start;
+        }
+        if start <= radius {
+            let closest = closest_point_triangle(origin, triangle);
+            let offset = origin - closest;
+            if glm::length2(&offset) <= radius * radius {
+                return Some((0.0, normal_or(&offset, dir)));
+            }
+        } else {
+            let speed = -glm::dot(&normal, dir);
+            if speed > 1e-9 {
+                let t = (start - radius) / speed;
+                let contact = origin + dir * t - normal * radius;
+                if t <= max_distance && inside_triangle(&contact, triangle) {
+                    return Some((t, normal));
+                }
+            }
+        }
+    }
+    let mut best = None;
+    for (p, q) in [(a, b), (b, c), (c, a)] {
+        if let Some(hit) = ray_capsule(origin, dir, p, q, radius, max_distance)
+            && best.is_none_or(|(t, _)| hit.0 < t)
+        {
+            best = Some(hit);
+        }
+    }
+    best
+}
+
+fn inside_triangle(p: &glm::Vec3, [a, b, c]: &[glm::Vec3; 3]) -> bool {
+    let n = glm::cross(&(b - a), &(c - a));
+    glm::dot(&glm::cross(&(b - a), &(p - a)), &n) >= 0.0
+        && glm::dot(&glm::cross(&(c - b), &(p - b)), &n) >= 0.0
+        && glm::dot(&glm::cross(&(a - c), &(p - c)), &n) >= 0.0
+}
+
+// Ericson, Real-Time Collision Detection 5.1.5
+pub fn closest_point_triangle(p: &glm::Vec3, [a, b, c]: &[glm::Vec3; 3]) -> glm::Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = glm::dot(&ab, &ap);
+    let d2 = glm::dot(&ac, &ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+    let bp = p - b;
+    let d3 = glm::dot(&ab, &bp);
+    let d4 = glm::dot(&ac, &bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = glm::dot(&ab, &cp);
+    let d6 = glm::dot(&ac, &cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+fn normal_or(v: &glm::Vec3, dir: &glm::Vec3) -> glm::Vec3 {
+    let len = glm::length(v);
+    if len > 1e-9 { v / len } else { -dir }
+}
+
+/*
+Harta e un grid de înălțimi cu pasul 1 scalat uniform cu `scale`.
+Mergem pe rază bucată cu bucată (câte o celulă) și testăm doar celulele de sub bucata curentă;
+ne oprim când cea mai bună lovitură e înaintea capătului bucății.
+*/
+pub fn cast_map(
+    map: &Map,
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<Cast> {
+    if map.height() < 2 || map.width() < 2 {
+        return None;
+    }
+    let scale = map.scale();
+    let origin = origin / scale;
+    let radius = radius / scale;
+    let max_distance = max_distance / scale;
+    let (width, height) = (map.width(), map.height());
+    let (low, high) = map.height_range();
+
+    // puțin mai mare, ca un teren plat la marginea cutiei să nu scape pe erori de rotunjire
+    let bounds = Aabb::new(
+        glm::vec3(0.0, low, 0.0),
+        glm::vec3((width - 1) as f32, high, (height - 1) as f32),
+    )
+    .expanded(radius + 1e-3);
+    let (enter, exit) = bounds.ray_interval(&origin, dir)?;
+    let exit = exit.min(max_distance);
+    let vertex = |x: usize, z: usize| glm::vec3(x as f32, map.get(x, z).unwrap_or(0.0), z as f32);
+
+    let mut best: Option<Cast> = None;
+    let mut t0 = enter.max(0.0);
+    while t0 <= exit {
+        let t1 = (t0 + 1.0).min(exit);
+        let p0 = origin + dir * t0;
+        let p1 = origin + dir * t1;
+        let lo = glm::min2(&p0, &p1).add_scalar(-radius);
+        let hi = glm::max2(&p0, &p1).add_scalar(radius);
+        let cells = |a: f32, b: f32, n: usize| {
+            let a = a.floor().clamp(0.0, (n - 2) as f32) as usize;
+            let b = b.floor().clamp(0.0, (n - 2) as f32) as usize;
+            a..=b
+        };
+        for z in cells(lo.z, hi.z, height) {
+            for x in cells(lo.x, hi.x, width) {
+                let corners = [
+                    vertex(x, z),
+                    vertex(x + 1, z),
+                    vertex(x, z + 1),
+                    vertex(x + 1, z + 1),
+                ];
+                let cell_low = corners.iter().map(|c| c.y).fold(f32::MAX, f32::min);
+                let cell_high = corners.iter().map(|c| c.y).fold(f32::MIN, f32::max);
+                if cell_low > hi.y || cell_high < lo.y {
+                    continue;
+                }
+                // aceeași împărțire ca în Map::generate_terrain
+                let [tl, tr, bl, br] = corners;
+                let cell = z * (width - 1) + x;
+                for (k, triangle) in [[tl, bl, tr], [tr, bl, br]].iter().enumerate() {
+                    let hit = sphere_triangle(&origin, dir, radius, triangle, exit);
+                    best = closer(
+                        best,
+                        hit.map(|(t, n)| {
+                            let part = HitPart::Triangle {
+                                mesh: 0,
+                                index: cell * 2 + k,
+                            };
+                            (t, n, part)
+                        }),
+                    );
+                }
+            }
+        }
+        if best.is_some_and(|b| b.0 <= t1) || t1 >= exit {
+            break;
+        }
+        t0 = t1;
+    }
+    best.map(|(t, n, part)| (t * scale, n, part))
+}
+
+/*
+Planeta e un grid pe sferă (theta pe lățime, phi pe înălțime) cu raza 1 + înălțimea,
+rotită, scalată și mutată ca în Planet::draw. Lucrăm în spațiul ei local și, ca la hartă,
+mergem pe rază bucată cu bucată; celulele de sub o bucată le găsim din coordonatele sferice.
+*/
+pub fn cast_planet(
+    planet: &Planet,
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<Cast> {
+    if planet.height() < 2 || planet.width() < 1 || planet.scale <= 0.0 {
+        return None;
+    }
+    let (width, height) = (planet.width(), planet.height());
+    let rotation = euler_to_matrix(planet.x_angle, planet.y_angle, planet.z_angle);
+    let to_local = glm::transpose(&rotation);
+    let scale = planet.scale;
+    let origin = to_local * (origin - planet.position) / scale;
+    let dir = to_local * dir;
+    let radius = radius / scale;
+    let max_distance = max_distance / scale;
+    let (low, high) = planet.height_range();
+    let inner = 1.0 + low - radius;
+    let outer = 1.0 + high + radius + 1e-3;
+
+    let (enter, _) = ray_sphere(&origin, &dir, &glm::Vec3::zeros(), outer, max_distance)?;
+    // ieșirea din sfera exterioară
+    let b = glm::dot(&origin, &dir);
+    let exit = (-b
+        + (b * b - glm::dot(&origin, &origin) + outer * outer)
+            .max(0.0)
+            .sqrt())
+    .min(max_distance);
+
+    let pi = std::f32::consts::PI;
+    let d_theta = 2.0 * pi / width as f32;
+    let d_phi = pi / (height - 1) as f32;
+    let base = (1.0 + low).max(1e-3);
+    let step = base * d_theta.min(d_phi);
+    let vertex = |x: usize, z: usize| {
+        let theta = x as f32 * d_theta;
+        let phi = z as f32 * d_phi;
+        let r = 1.0 + planet.get(x % width, z).unwrap_or(0.0);
+        glm::vec3(
+            r * phi.sin() * theta.cos(),
+            r * phi.cos(),
+            r * phi.sin() * theta.sin(),
+        )
+    };
+    let grid = |p: &glm::Vec3| {
+        let r = glm::length(p).max(1e-9);
+        let phi = (p.y / r).clamp(-1.0, 1.0).acos();
+        let theta = p.z.atan2(p.x).rem_euclid(2.0 * pi);
+        (theta / d_theta, phi / d_phi)
+    };
+
+    let mut best: Option<Cast> = None;
+    let mut t0 = enter;
+    while t0 <= exit {
+        let t1 = (t0 + step).min(exit);
+        let p0 = origin + dir * t0;
+        let p1 = origin + dir * t1;
+        // bucata e toată sub cel mai jos punct al terenului
+        if glm::length(&p0).max(glm::length(&p1)) >= inner {
+            let samples = [grid(&p0), grid(&((p0 + p1) * 0.5)), grid(&p1)];
+            // cât se poate abate bucata (și sfera) de la puncte, în unghi
+            let slack = (step * 0.5 + radius) / base;
+            let z_lo = samples.iter().map(|s| s.1).fold(f32::MAX, f32::min) - slack / d_phi - 1.0;
+            let z_hi = samples.iter().map(|s| s.1).fold(f32::MIN, f32::max) + slack / d_phi + 1.0;
+            let z_lo = z_lo.floor().clamp(0.0, (height - 2) as f32) as usize;
+            let z_hi = z_hi.floor().clamp(0.0, (height - 2) as f32) as usize;
+
+            let sin_min = (z_lo..=z_hi + 1)
+                .map(|z| (z as f32 * d_phi).sin())
+                .fold(f32::MAX, f32::min);
+            // longitudinea relativă la primul punct, ca să nu sară la 0 / 2pi
+            let x0 = samples[0].0;
+            let unwrap = |x: f32| {
+                x0 + (x - x0 + width as f32 * 0.5).rem_euclid(width as f32) - width as f32 * 0.5
+            };
+            let spread = slack / (sin_min.max(1e-6) * d_theta) + 1.0;
+            let x_lo = samples.iter().map(|s| unwrap(s.0)).fold(f32::MAX, f32::min) - spread;
+            let x_hi = samples.iter().map(|s| unwrap(s.0)).fold(f32::MIN, f32::max) + spread;
+            let columns: Vec<usize> = if x_hi - x_lo >= width as f32 {
+                (0..width).collect()
+            } else {
+                (x_lo.floor() as i64..=x_hi.floor() as i64)
+                    .map(|x| x.rem_euclid(width as i64) as usize)
+                    .collect()
+            };
+
+            for z in z_lo..=z_hi {
+                for &x in &columns {
+                    let [tl, tr, bl, br] = [
+                        vertex(x, z),
+                        vertex(x + 1, z),
+                        vertex(x, z + 1),
+                        vertex(x + 1, z + 1),
+                    ];
+                    // aceeași împărțire ca în Planet::generate_terrain
+                    let cell = z * width + x;
+                    for (k, triangle) in [[tl, tr, bl], [tr, br, bl]].iter().enumerate() {
+                        let hit = sphere_triangle(&origin, &dir, radius, triangle, exit);
+                        best = closer(
+                            best,
+                            hit.map(|(t, n)| {
+                                let part = HitPart::Triangle {
+                                    mesh: 0,
+                                    index: cell * 2 + k,
+                                };
+                                (t, n, part)
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+        if best.is_some_and(|b| b.0 <= t1) || t1 >= exit {
+            break;
+        }
+        t0 = t1;
+    }
+    best.map(|(t, n, part)| (t * scale, rotation * n, part))
+}
+
+// toate triunghiurile din toate mesh-urile, cu transformarea fiecărui mesh
+pub fn cast_model(
+    model: &GLTFModel,
+    origin: &glm::Vec3,
+    dir: &glm::Vec3,
+    radius: f32,
+    max_distance: f32,
+) -> Option<Cast> {
+    let mut best: Option<Cast> = None;
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        let transform = mesh.translation * mesh.rotation * mesh.scale;
+        let points: Vec<glm::Vec3> = mesh
+            .position_coords
+            .chunks_exact(3)
+            .map(|p| {
+                let w = transform * glm::vec4(p[0], p[1], p[2], 1.0);
+                glm::vec3(w.x, w.y, w.z)
+            })
+            .collect();
+        let Some(first) = points.first() else {
+            continue;
+        };
+        let (min, max) = points.iter().fold((*first, *first), |(min, max), p| {
+            (glm::min2(&min, p), glm::max2(&max, p))
+        });
+        let limit = best.map_or(max_distance, |b| b.0);
+        match Aabb::new(min, max)
+            .expanded(radius)
+            .ray_interval(origin, dir)
+        {
+            Some((enter, exit)) if exit >= 0.0 && enter <= limit => {}
+            _ => continue,
+        }
+        for (index, face) in mesh.indices.chunks_exact(3).enumerate() {
+            let Some(triangle) = face
+                .iter()
+                .map(|&i| points.get(i as usize).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let limit = best.map_or(max_distance, |b| b.0);
+            let triangle = [triangle[0], triangle[1], triangle[2]];
+            let hit = sphere_triangle(origin, dir, radius, &triangle, limit);
+            best = closer(
+                best,
+                hit.map(|(t, n)| {
+                    let part = HitPart::Triangle {
+                        mesh: mesh_index,
+                        index,
+                    };
+                    (t, n, part)
+                }),
+            );
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::CollisionFilter;
+    use crate::seb::physics::{PhysicsWorld, RigidBody};
+    use crate::seb::primitives::{Capsule, ConvexHull, Cylinder, Plane, Rectangle, Sphere};
+
+    fn sphere(position: glm::Vec3, radius: f32) -> Collider {
+        let mut s = Sphere::new();
+        s.position = position;
+        s.scale = radius;
+        Collider::Sphere(s)
+    }
+
+    /*
+    Câte o formă din fiecare fel, toate în origine cu fața spre -x la x = -1
+    (capsula la -0.5), și distanța la care le lovește raza din (-5, 0, 0) spre +x.
+    */
+    fn shapes() -> Vec<(Collider, f32)> {
+        let mut rect = Rectangle::new();
+        rect.scale = glm::vec3(1.0, 1.0, 1.0);
+        let mut capsule = Capsule::new();
+        capsule.radius = 0.5;
+        capsule.half_height = 1.0;
+        let mut cylinder = Cylinder::new();
+        cylinder.radius = 1.0;
+        cylinder.half_height = 1.0;
+        let corners: Vec<glm::Vec3> = (0..8)
+            .map(|i| {
+                let sign = |bit: i32| if i & bit == 0 { -1.0 } else { 1.0 };
+                glm::vec3(sign(1), sign(2), sign(4))
+            })
+            .collect();
+        vec![
+            (sphere(glm::Vec3::zeros(), 1.0), 4.0),
+            (Collider::Rectangle(rect), 4.0),
+            (Collider::Capsule(capsule), 4.5),
+            (Collider::Cylinder(cylinder), 4.0),
+            (Collider::ConvexHull(ConvexHull::from_points(&corners)), 4.0),
+            // semispațiul x > -1
+            (
+                Collider::Plane(Plane::from_point(
+                    glm::vec3(-1.0, 0.0, 0.0),
+                    glm::vec3(-1.0, 0.0, 0.0),
+                )),
+                4.0,
+            ),
+        ]
+    }
+
+    fn cast(collider: &Collider, origin: glm::Vec3, dir: glm::Vec3, max: f32) -> Option<RayHit> {
+        raycast(&[Target::Collider(collider)], origin, dir, max)
+    }
+
+    #[test]
+    fn hits_each_shape() {
+        let x = glm::vec3(1.0, 0.0, 0.0);
+        for (collider, expected) in shapes() {
+            let hit = cast(&collider, glm::vec3(-5.0, 0.0, 0.0), x, 100.0)
+                .unwrap_or_else(|| panic!("{:?} nu e lovit", collider));
+            assert!(
+                (hit.distance - expected).abs() < 1e-3,
+                "{:?}: {}",
+                collider,
+                hit.distance
+            );
+            assert!(
+                glm::distance(&hit.normal, &-x) < 1e-2,
+                "{:?}: {:?}",
+                collider,
+                hit.normal
+            );
+            assert!((hit.point.x - (expected - 5.0)).abs() < 1e-3);
+            assert_eq!((hit.object, hit.part), (0, HitPart::Shape));
+        }
+    }
+
+    #[test]
+    fn misses_each_shape() {
+        for (collider, _) in shapes() {
+            // pe lângă formă, iar planul e ratat doar de o rază care pleacă de la el
+            let (origin, dir) = match collider {
+                Collider::Plane(_) => (glm::vec3(-5.0, 0.0, 0.0), glm::vec3(-1.0, 0.0, 0.0)),
+                _ => (glm::vec3(-5.0, 3.0, 0.0), glm::vec3(1.0, 0.0, 0.0)),
+            };
+            assert!(
+                cast(&collider, origin, dir, 100.0).is_none(),
+                "{:?}",
+                collider
+            );
+        }
+    }
+
+    #[test]
+    fn ray_starting_inside_hits_at_zero() {
+        for (collider, _) in shapes() {
+            let hit = cast(
+                &collider,
+                glm::vec3(0.1, 0.2, 0.0),
+                glm::vec3(1.0, 0.0, 0.0),
+                100.0,
+            )
+            .unwrap_or_else(|| panic!("{:?} nu e lovit din interior", collider));
+            assert_eq!(hit.distance, 0.0, "{:?}", collider);
+        }
+    }
+
+    #[test]
+    fn max_distance_limits_hits() {
+        let x = glm::vec3(1.0, 0.0, 0.0);
+        let origin = glm::vec3(-5.0, 0.0, 0.0);
+        for (collider, expected) in shapes() {
+            assert!(
+                cast(&collider, origin, x, expected - 0.1).is_none(),
+                "{:?}",
+                collider
+            );
+            assert!(
+                cast(&collider, origin, x, expected + 0.1).is_some(),
+                "{:?}",
+                collider
+            );
+        }
+        assert!(cast(&sphere(glm::Vec3::zeros(), 1.0), origin, x, -1.0).is_none());
+    }
+
+    #[test]
+    fn nearest_target_wins() {
+        let far = sphere(glm::vec3(5.0, 0.0, 0.0), 1.0);
+        let near = sphere(glm::vec3(2.0, 0.0, 0.0), 0.5);
+        let targets = [Target::Collider(&far), Target::Collider(&near)];
+        let hit = raycast(
+            &targets,
+            glm::Vec3::zeros(),
+            glm::vec3(2.0, 0.0, 0.0),
+            100.0,
+        )
+        .unwrap();
+        assert_eq!(hit.object, 1);
+        assert!((hit.distance - 1.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn spherecast_reports_contact_point() {
+        let mut rect = Rectangle::new();
+        rect.scale = glm::vec3(1.0, 1.0, 1.0);
+        let rect = Collider::Rectangle(rect);
+        let hit = spherecast(
+            &[Target::Collider(&rect)],
+            glm::vec3(-5.0, 0.0, 0.0),
+            glm::vec3(1.0, 0.0, 0.0),
+            0.5,
+            100.0,
+        )
+        .unwrap();
+        assert!((hit.distance - 3.5).abs() < 1e-3);
+        assert!(glm::distance(&hit.point, &glm::vec3(-1.0, 0.0, 0.0)) < 1e-3);
+    }
+
+    #[test]
+    fn hits_mesh_triangles() {
+        let a = glm::vec3(-1.0, 0.0, -1.0);
+        let b = glm::vec3(1.0, 0.0, -1.0);
+        let c = glm::vec3(1.0, 0.0, 1.0);
+        let d = glm::vec3(-1.0, 0.0, 1.0);
+        let mesh = MeshBvh::new(vec![[a, b, c], [a, c, d]], vec![(3, 10), (3, 11)]);
+        let down = glm::vec3(0.0, -1.0, 0.0);
+
+        let hit = raycast(
+            &[Target::Mesh(&mesh)],
+            glm::vec3(-0.5, 2.0, 0.5),
+            down,
+            10.0,
+        )
+        .unwrap();
+        assert!((hit.distance - 2.0).abs() < 1e-5);
+        assert_eq!(hit.part, HitPart::Triangle { mesh: 3, index: 11 });
+        // fața e lovită și de dedesubt, cu normala spre rază
+        let hit = raycast(
+            &[Target::Mesh(&mesh)],
+            glm::vec3(0.5, -1.0, -0.5),
+            -down,
+            10.0,
+        )
+        .unwrap();
+        assert!(hit.normal.y < -0.99);
+        assert!(raycast(&[Target::Mesh(&mesh)], glm::vec3(2.0, 2.0, 0.0), down, 10.0).is_none());
+    }
+
+    #[test]
+    fn hits_map_and_planet() {
+        let mut map = Map::new();
+        map.from_height_map("./assets/heightmap2.jpg", 5.0).unwrap();
+        let ground = map.get(10, 12).unwrap();
+        let down = glm::vec3(0.0, -1.0, 0.0);
+        let hit = raycast(
+            &[Target::Map(&map)],
+            glm::vec3(10.0, 20.0, 12.0),
+            down,
+            100.0,
+        )
+        .unwrap();
+        assert!((hit.distance - (20.0 - ground)).abs() < 1e-3);
+        assert!(
+            raycast(
+                &[Target::Map(&map)],
+                glm::vec3(10.0, 20.0, 12.0),
+                -down,
+                100.0
+            )
+            .is_none()
+        );
+
+        let mut planet = Planet::new();
+        planet
+            .from_map("./assets/planet/worldgen1.jpg", 1.0, 2.0)
+            .unwrap();
+        planet.position = glm::vec3(0.0, 0.0, 30.0);
+        let (low, high) = planet.height_range();
+        let toward = glm::vec3(0.0, 0.0, 1.0);
+        let hit = raycast(
+            &[Target::Planet(&planet)],
+            glm::Vec3::zeros(),
+            toward,
+            100.0,
+        )
+        .unwrap();
+        // suprafața e între raza minimă și cea maximă a reliefului
+        assert!(hit.distance >= 30.0 - 2.0 * (1.0 + high) - 1e-3);
+        assert!(hit.distance <= 30.0 - 2.0 * (1.0 + low) + 1e-3);
+        assert!(hit.normal.z < 0.0);
+        let beside = glm::vec3(0.0, 3.0, 0.0);
+        assert!(raycast(&[Target::Planet(&planet)], beside, toward, 100.0).is_none());
+    }
+
+    #[test]
+    fn world_raycast_filters_layers() {
+        let mut world = PhysicsWorld::new();
+        let add = |world: &mut PhysicsWorld, x: f32, layer: u32| {
+            let mut body = RigidBody::new(sphere(glm::vec3(x, 0.0, 0.0), 0.5), 0.0);
+            body.filter = CollisionFilter::new(layer, u32::MAX);
+            world.add_body(body)
+        };
+        let near = add(&mut world, 2.0, 1);
+        let far = add(&mut world, 5.0, 2);
+        let x = glm::vec3(1.0, 0.0, 0.0);
+
+        assert_eq!(
+            world
+                .raycast(glm::Vec3::zeros(), x, 100.0, u32::MAX)
+                .unwrap()
+                .object,
+            near
+        );
+        // stratul 1 e sărit, deci lovim corpul din spatele lui
+        let hit = world.raycast(glm::Vec3::zeros(), x, 100.0, 2).unwrap();
+        assert_eq!(hit.object, far);
+        assert!((hit.distance - 4.5).abs() < 1e-4);
+        assert!(world.raycast(glm::Vec3::zeros(), x, 100.0, 4).is_none());
+
+        // triggerele nu sunt lovite niciodată
+        world.body_mut(near).trigger = true;
+        assert_eq!(
+            world
+                .raycast(glm::Vec3::zeros(), x, 100.0, 1 | 2)
+                .unwrap()
+                .object,
+            far
+        );
+    }
+}
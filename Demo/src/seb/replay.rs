@@ -0,0 +1,380 @@
+use crate::seb::input::KEYS;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+// un apel de poll_events: când a fost și ce evenimente a primit, fiecare cu timpul lui
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub time: f64,
+    pub events: Vec<(f64, glfw::WindowEvent)>,
+}
+
+/*
+Tot ce a primit Window de la GLFW, frame cu frame, așa cum l-a văzut poll_events: taste,
+caractere, mouse, rotiță, redimensionări și fișiere trase în fereastră. Se salvează ca text,
+o linie pe eveniment, și se poate reda în locul lui GLFW cu `Window::replay`.
+
+    size 1000 800
+    frame 12.5
+    key 12.49 W 17 Press 0
+    cursor 12.49 500.5 400.25
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    // dimensiunea ferestrei la pornire, ca proiecțiile să iasă la fel la redare
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            frames: Vec::new(),
+        }
+    }
+    // doar evenimentele pe care le folosește poll_events merită ținute
+    pub fn is_recorded(event: &glfw::WindowEvent) -> bool {
+        matches!(
+            event,
+            glfw::WindowEvent::FramebufferSize(..)
+                | glfw::WindowEvent::CursorPos(..)
+                | glfw::WindowEvent::MouseButton(..)
+                | glfw::WindowEvent::Scroll(..)
+                | glfw::WindowEvent::Char(..)
+                | glfw::WindowEvent::FileDrop(..)
+                | glfw::WindowEvent::Key(..)
+        )
+    }
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_text()).map_err(|e| format!("{}: {}", path, e))
+    }
+    pub fn to_text(&self) -> String {
+        let mut text = format!("size {} {}\n", self.width, self.height);
+        for frame in &self.frames {
+            text.push_str(&format!("frame {}\n", frame.time));
+            for (time, event) in &frame.events {
+                let line = match event {
+                    glfw::WindowEvent::Key(key, scancode, action, modifiers) => format!(
+                        "key {} {:?} {} {:?} {}",
+                        time,
+                        key,
+                        scancode,
+                        action,
+                        modifiers.bits()
+                    ),
+                    glfw::WindowEvent::Char(c) => format!("char {} {}", time, *c as u32),
+                    glfw::WindowEvent::CursorPos(x, y) => format!("cursor {} {} {}", time, x, y),
+                    glfw::WindowEvent::MouseButton(button, action, modifiers) => format!(
+                        "button {} {} {:?} {}",
+                        time,
+                        *button as i32,
+                        action,
+                        modifiers.bits()
+                    ),
+                    glfw::WindowEvent::Scroll(x, y) => format!("scroll {} {} {}", time, x, y),
+                    glfw::WindowEvent::FramebufferSize(w, h) => {
+                        format!("resize {} {} {}", time, w, h)
+                    }
+                    // căile pot avea spații, așa că stau fiecare pe linia ei
+                    glfw::WindowEvent::FileDrop(paths) => {
+                        let mut line = format!("drop {} {}", time, paths.len());
+                        for path in paths {
+                            line.push_str(&format!("\npath {}", path.display()));
+                        }
+                        line
+                    }
+                    _ => continue,
+                };
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut recording = Self::default();
+        let mut lines = text.lines().enumerate();
+        while let Some((number, line)) = lines.next() {
+            let mut words = line.split_whitespace();
+            let Some(kind) = words.next() else {
+                continue;
+            };
+            let error = |e: String| format!("linia {}: {}", number + 1, e);
+            let mut next = || {
+                words
+                    .next()
+                    .ok_or_else(|| error("linie incompletă".to_string()))
+            };
+            match kind {
+                "size" => {
+                    recording.width = parse_number(next()?).map_err(error)?;
+                    recording.height = parse_number(next()?).map_err(error)?;
+                    continue;
+                }
+                "frame" => {
+                    let time = parse_number(next()?).map_err(error)?;
+                    recording.frames.push(Frame {
+                        time,
+                        events: Vec::new(),
+                    });
+                    continue;
+                }
+                _ => {}
+            }
+            let time: f64 = parse_number(next()?).map_err(error)?;
+            let event = match kind {
+                "key" => glfw::WindowEvent::Key(
+                    parse_key(next()?).map_err(error)?,
+                    parse_number(next()?).map_err(error)?,
+                    parse_action(next()?).map_err(error)?,
+                    parse_modifiers(next()?).map_err(error)?,
+                ),
+                "char" => {
+                    let code: u32 = parse_number(next()?).map_err(error)?;
+                    glfw::WindowEvent::Char(
+                        char::from_u32(code).ok_or_else(|| error(format!("caracter {}", code)))?,
+                    )
+                }
+                "cursor" => glfw::WindowEvent::CursorPos(
+                    parse_number(next()?).map_err(error)?,
+                    parse_number(next()?).map_err(error)?,
+                ),
+                "button" => {
+                    let code: i32 = parse_number(next()?).map_err(error)?;
+                    glfw::WindowEvent::MouseButton(
+                        glfw::MouseButton::from_i32(code)
+                            .ok_or_else(|| error(format!("buton {}", code)))?,
+                        parse_action(next()?).map_err(error)?,
+                        parse_modifiers(next()?).map_err(error)?,
+                    )
+                }
+                "scroll" => glfw::WindowEvent::Scroll(
+                    parse_number(next()?).map_err(error)?,
+                    parse_number(next()?).map_err(error)?,
+                ),
+                "resize" => glfw::WindowEvent::FramebufferSize(
+                    parse_number(next()?).map_err(error)?,
+                    parse_number(next()?).map_err(error)?,
+                ),
+                "drop" => {
+                    let count: usize = parse_number(next()?).map_err(error)?;
+                    let mut paths = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let path = lines
+                            .next()
+                            .and_then(|(_, line)| line.strip_prefix("path "))
+                            .ok_or_else(|| error("lipsesc căile fișierelor".to_string()))?;
+                        paths.push(PathBuf::from(path));
+                    }
+                    glfw::WindowEvent::FileDrop(paths)
+                }
+                kind => return Err(error(format!("eveniment necunoscut '{}'", kind))),
+            };
+            recording
+                .frames
+                .last_mut()
+                .ok_or_else(|| error("eveniment înainte de primul frame".to_string()))?
+                .events
+                .push((time, event));
+        }
+        Ok(recording)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.parse().map_err(|_| format!("număr greșit '{}'", word))
+}
+// în înregistrări tastele sunt scrise cu numele din glfw (Num1, nu 1 ca în input.cfg)
+fn parse_key(word: &str) -> Result<glfw::Key, String> {
+    KEYS.iter()
+        .map(|&(_, key)| key)
+        .find(|key| format!("{:?}", key) == word)
+        .ok_or_else(|| format!("tastă necunoscută '{}'", word))
+}
+fn parse_action(word: &str) -> Result<glfw::Action, String> {
+    match word {
+        "Press" => Ok(glfw::Action::Press),
+        "Release" => Ok(glfw::Action::Release),
+        "Repeat" => Ok(glfw::Action::Repeat),
+        word => Err(format!("acțiune necunoscută '{}'", word)),
+    }
+}
+fn parse_modifiers(word: &str) -> Result<glfw::Modifiers, String> {
+    Ok(glfw::Modifiers::from_bits_truncate(parse_number(word)?))
+}
+
+/*
+Redarea unei înregistrări: `next_frame` dă frame-urile pe rând și ține minte ce taste și
+butoane au rămas apăsate, ca `Window::get_key` să răspundă ca în timpul înregistrării.
+*/
+pub struct Replay {
+    recording: Recording,
+    next: usize,
+    keys: HashSet<glfw::Key>,
+    buttons: HashSet<glfw::MouseButton>,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            keys: HashSet::new(),
+            buttons: HashSet::new(),
+        }
+    }
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.frames.len()
+    }
+    pub fn next_frame(&mut self) -> Option<Frame> {
+        let frame = self.recording.frames.get(self.next)?.clone();
+        self.next += 1;
+        for (_, event) in &frame.events {
+            match *event {
+                glfw::WindowEvent::Key(key, _, glfw::Action::Press, _) => {
+                    self.keys.insert(key);
+                }
+                glfw::WindowEvent::Key(key, _, glfw::Action::Release, _) => {
+                    self.keys.remove(&key);
+                }
+                glfw::WindowEvent::MouseButton(button, glfw::Action::Press, _) => {
+                    self.buttons.insert(button);
+                }
+                glfw::WindowEvent::MouseButton(button, glfw::Action::Release, _) => {
+                    self.buttons.remove(&button);
+                }
+                _ => {}
+            }
+        }
+        Some(frame)
+    }
+    pub fn key(&self, key: glfw::Key) -> glfw::Action {
+        if self.keys.contains(&key) {
+            glfw::Action::Press
+        } else {
+            glfw::Action::Release
+        }
+    }
+    pub fn mouse_button(&self, button: glfw::MouseButton) -> glfw::Action {
+        if self.buttons.contains(&button) {
+            glfw::Action::Press
+        } else {
+            glfw::Action::Release
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Recording {
+        let mut recording = Recording::new(1000, 800);
+        recording.frames.push(Frame {
+            time: 0.1 + 0.2,
+            events: vec![
+                (
+                    0.29,
+                    glfw::WindowEvent::Key(
+                        glfw::Key::W,
+                        17,
+                        glfw::Action::Press,
+                        glfw::Modifiers::Shift | glfw::Modifiers::Control,
+                    ),
+                ),
+                (0.29, glfw::WindowEvent::Char('ș')),
+                (0.295, glfw::WindowEvent::CursorPos(500.5, -1.0 / 3.0)),
+            ],
+        });
+        // un frame fără evenimente trebuie și el păstrat, altfel timpii se decalează
+        recording.frames.push(Frame {
+            time: 0.35,
+            events: Vec::new(),
+        });
+        recording.frames.push(Frame {
+            time: 0.4,
+            events: vec![
+                (
+                    0.39,
+                    glfw::WindowEvent::MouseButton(
+                        glfw::MouseButton::Button2,
+                        glfw::Action::Release,
+                        glfw::Modifiers::empty(),
+                    ),
+                ),
+                (0.39, glfw::WindowEvent::Scroll(0.0, -1.5)),
+                (0.39, glfw::WindowEvent::FramebufferSize(640, 480)),
+                (
+                    0.4,
+                    glfw::WindowEvent::FileDrop(vec![
+                        PathBuf::from("assets/model/map.glb"),
+                        PathBuf::from("/tmp/cu spații/a b.png"),
+                    ]),
+                ),
+            ],
+        });
+        recording
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let recording = sample();
+        let text = recording.to_text();
+        let parsed = Recording::parse(&text).unwrap();
+        assert_eq!((parsed.width, parsed.height), (1000, 800));
+        assert_eq!(parsed.frames.len(), recording.frames.len());
+        for (a, b) in parsed.frames.iter().zip(&recording.frames) {
+            // timpii trebuie să iasă exact, redarea depinde de ei
+            assert_eq!(a.time, b.time);
+            assert_eq!(a.events, b.events);
+        }
+        assert_eq!(parsed.to_text(), text);
+    }
+
+    #[test]
+    fn every_key_round_trips() {
+        for &(_, key) in KEYS {
+            assert_eq!(parse_key(&format!("{:?}", key)), Ok(key));
+        }
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let error = |text: &str| Recording::parse(text).err().unwrap();
+        assert!(error("size 10 10\nkey 0.5 W 1 Press 0").starts_with("linia 2:"));
+        assert!(error("frame 0\nkey 0.5 Hyper 1 Press 0").contains("Hyper"));
+        assert!(error("frame 0\nkey 0.5 W 1 Hold 0").contains("Hold"));
+        assert!(error("frame 0\ncursor 0.5 1").contains("incompletă"));
+        assert!(error("frame 0\ndrop 0.5 2\npath a").contains("căile"));
+        assert!(error("frame 0\njoystick 0.5").contains("joystick"));
+        // liniile goale sunt sărite
+        assert!(Recording::parse("\nsize 1 1\n\nframe 0\n").is_ok());
+    }
+
+    #[test]
+    fn replay_tracks_held_keys() {
+        let mut replay = Replay::new(sample());
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.events.len(), 3);
+        assert_eq!(replay.key(glfw::Key::W), glfw::Action::Press);
+        replay.next_frame();
+        replay.next_frame();
+        assert_eq!(replay.key(glfw::Key::W), glfw::Action::Press);
+        assert_eq!(
+            replay.mouse_button(glfw::MouseButton::Button2),
+            glfw::Action::Release
+        );
+        assert!(replay.is_finished());
+        assert!(replay.next_frame().is_none());
+    }
+}
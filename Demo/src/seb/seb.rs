@@ -1,3 +1,5 @@
+use crate::seb::preprocessor::Preprocessor;
+use crate::seb::uniforms::{self, UniformCache};
 use nalgebra_glm as glm;
 
 pub trait ToCStr {
@@ -9,90 +11,334 @@ impl ToCStr for str {
     }
 }
 
-fn check_shader_compile_status(shader: u32, shader_type: &str) {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+    Link,
+}
+
+impl ShaderStage {
+    fn gl_type(self) -> u32 {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Compute => gl::COMPUTE_SHADER,
+            ShaderStage::Link => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            ShaderStage::Vertex => "vertex shader",
+            ShaderStage::Fragment => "fragment shader",
+            ShaderStage::Compute => "compute shader",
+            ShaderStage::Link => "link program",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// o linie din logul driverului; line și column lipsesc când driverul nu le dă
+#[derive(Clone, Debug)]
+pub struct ShaderDiagnostic {
+    // fișierul inclus în care e linia, când nu e chiar `ShaderError::file`
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    // linia din sursă la care se referă, pentru context
+    pub source_line: Option<String>,
+}
+
+/*
+Eroare de compilare sau de link. `file` e fișierul sursă, sau locul din cod unde e scris
+shaderul când sursa e inline; `log` e logul driverului neatins.
+*/
+#[derive(Clone)]
+pub struct ShaderError {
+    pub stage: ShaderStage,
+    pub file: String,
+    pub log: String,
+    pub diagnostics: Vec<ShaderDiagnostic>,
+}
+
+impl ShaderError {
+    pub fn new(stage: ShaderStage, file: &str, log: &str, source: Option<&str>) -> Self {
+        Self {
+            stage,
+            file: file.to_string(),
+            log: log.to_string(),
+            diagnostics: parse_log(log, source),
+        }
+    }
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}:", self.stage, self.file)?;
+        if self.diagnostics.is_empty() {
+            return write!(f, " {}", self.log.trim());
+        }
+        for diagnostic in &self.diagnostics {
+            writeln!(f)?;
+            write!(f, "  ")?;
+            if let Some(ref file) = diagnostic.file {
+                write!(f, "{}:", file)?;
+            }
+            match (diagnostic.line, diagnostic.column) {
+                (Some(line), Some(column)) => write!(f, "{}:{}: ", line, column)?,
+                (Some(line), None) => write!(f, "{}: ", line)?,
+                _ => {}
+            }
+            write!(f, "{}", diagnostic.message)?;
+            if let Some(ref source_line) = diagnostic.source_line {
+                write!(f, "\n    | {}", source_line)?;
+                if let Some(column) = diagnostic.column.filter(|&c| c > 0) {
+                    write!(f, "\n    | {}^", " ".repeat(column - 1))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ca `unwrap()` să afișeze mesajul formatat, nu structura
+impl std::fmt::Debug for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+// restul codului folosește String pentru erori
+impl From<ShaderError> for String {
+    fn from(error: ShaderError) -> Self {
+        error.to_string()
+    }
+}
+
+/*
+Fiecare driver își scrie altfel pozițiile:
+    Mesa:       0:12(5): error: ...
+    NVIDIA:     0(12) : error C1008: ...
+    AMD, Intel: ERROR: 0:12: ...
+Primul număr e indexul sursei, îl ignorăm. Liniile fără poziție rămân ca mesaj simplu.
+*/
+fn parse_log(log: &str, source: Option<&str>) -> Vec<ShaderDiagnostic> {
+    let mut diagnostics = Vec::new();
+    for text in log.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (line, column, message) =
+            parse_position(text).unwrap_or((None, None, text.to_string()));
+        let source_line = line
+            .and_then(|line| source?.lines().nth(line.checked_sub(1)?))
+            .map(|l| l.trim_end().to_string());
+        diagnostics.push(ShaderDiagnostic {
+            file: None,
+            line,
+            column,
+            message,
+            source_line,
+        });
+    }
+    diagnostics
+}
+
+fn parse_position(text: &str) -> Option<(Option<usize>, Option<usize>, String)> {
+    // prefixul AMD/Intel îl punem înapoi în mesaj
+    let (severity, rest) = match text.split_once(": ") {
+        Some((prefix, rest)) if prefix == "ERROR" || prefix == "WARNING" => (Some(prefix), rest),
+        _ => (None, text),
+    };
+    let (_, rest) = split_number(rest)?;
+    let (line, column, rest) = if let Some(rest) = rest.strip_prefix(':') {
+        // Mesa sau AMD/Intel
+        let (line, rest) = split_number(rest)?;
+        match rest.strip_prefix('(') {
+            Some(inner) => {
+                let (column, rest) = split_number(inner)?;
+                (line, Some(column), rest.strip_prefix(')')?)
+            }
+            None => (line, None, rest),
+        }
+    } else {
+        // NVIDIA
+        let (line, rest) = split_number(rest.strip_prefix('(')?)?;
+        (line, None, rest.strip_prefix(')')?)
+    };
+    let rest = rest.trim_start_matches([':', ' ']);
+    let message = match severity {
+        Some(severity) => format!("{}: {}", severity, rest),
+        None => rest.to_string(),
+    };
+    Some((Some(line), column, message))
+}
+
+fn split_number(text: &str) -> Option<(usize, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+fn info_log(object: u32, is_program: bool) -> String {
+    let mut len: i32 = 0;
+    unsafe {
+        if is_program {
+            gl::GetProgramiv(object, gl::INFO_LOG_LENGTH, &mut len);
+        } else {
+            gl::GetShaderiv(object, gl::INFO_LOG_LENGTH, &mut len);
+        }
+    }
+    let mut buffer = vec![0u8; len.max(1) as usize];
+    let mut written: i32 = 0;
+    unsafe {
+        if is_program {
+            gl::GetProgramInfoLog(object, len, &mut written, buffer.as_mut_ptr() as *mut _);
+        } else {
+            gl::GetShaderInfoLog(object, len, &mut written, buffer.as_mut_ptr() as *mut _);
+        }
+    }
+    buffer.truncate(written.max(0) as usize);
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+fn compile_stage(stage: ShaderStage, file: &str, source: &str) -> Result<u32, ShaderError> {
     let mut success: i32 = 0;
     unsafe {
+        let shader = gl::CreateShader(stage.gl_type());
+        gl::ShaderSource(shader, 1, &source.c_str().as_ptr(), std::ptr::null());
+        gl::CompileShader(shader);
         gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
         if success == 0 {
-            let mut len: i32 = 0;
-            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-
-            let mut buffer: Vec<u8> = Vec::with_capacity(len as usize + 1);
-            buffer.set_len(len as usize);
-            gl::GetShaderInfoLog(
-                shader,
-                len,
-                std::ptr::null_mut(),
-                buffer.as_mut_ptr() as *mut _,
-            );
-
-            let error = String::from_utf8_lossy(&buffer);
-            println!("{} shader compilation failed:\n{}", shader_type, error);
+            let log = info_log(shader, false);
+            gl::DeleteShader(shader);
+            return Err(ShaderError::new(stage, file, &log, Some(source)));
         }
+        Ok(shader)
     }
 }
 
-fn check_program_link_status(program: u32) {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Program {
+    pub id: u32,
+}
+
+// `(stage, fișier, sursă)` pentru fiecare etapă; programul rămâne activ, ca înainte
+pub fn link_program(stages: &[(ShaderStage, &str, &str)]) -> Result<Program, ShaderError> {
+    let mut shaders = Vec::new();
+    for &(stage, file, source) in stages {
+        match compile_stage(stage, file, source) {
+            Ok(shader) => shaders.push(shader),
+            Err(e) => {
+                for shader in shaders {
+                    unsafe {
+                        gl::DeleteShader(shader);
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
     let mut success: i32 = 0;
     unsafe {
+        let program = gl::CreateProgram();
+        for &shader in &shaders {
+            gl::AttachShader(program, shader);
+        }
+        gl::LinkProgram(program);
+        for &shader in &shaders {
+            gl::DeleteShader(shader);
+        }
         gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
         if success == 0 {
-            let mut len: i32 = 0;
-            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
-
-            let mut buffer: Vec<u8> = Vec::with_capacity(len as usize + 1);
-            buffer.set_len(len as usize);
-            gl::GetProgramInfoLog(
-                program,
-                len,
-                std::ptr::null_mut(),
-                buffer.as_mut_ptr() as *mut _,
-            );
-
-            let error = String::from_utf8_lossy(&buffer);
-            println!("Shader program linking failed:\n{}", error);
+            let log = info_log(program, true);
+            gl::DeleteProgram(program);
+            let files: Vec<&str> = stages.iter().map(|&(_, file, _)| file).collect();
+            return Err(ShaderError::new(
+                ShaderStage::Link,
+                &files.join(" + "),
+                &log,
+                None,
+            ));
         }
+        uniforms::bind_blocks(program);
+        gl::UseProgram(program);
+        Ok(Program { id: program })
     }
 }
 
-pub fn create_shader_from(vshader: &str, fshader: &str) -> u32 {
-    let mut shader_program = 0;
+// pentru shaderele scrise inline, fișierul din eroare e locul apelului
+#[track_caller]
+pub fn create_shader_from(vshader: &str, fshader: &str) -> Result<Program, ShaderError> {
+    let caller = std::panic::Location::caller();
+    let file = format!("{}:{}", caller.file(), caller.line());
+    link_program(&[
+        (ShaderStage::Vertex, &file, vshader),
+        (ShaderStage::Fragment, &file, fshader),
+    ])
+}
+
+pub fn create_shader_from_files(
+    vshader_file: &str,
+    fshader_file: &str,
+) -> Result<Program, ShaderError> {
+    let read = |stage, file: &str| {
+        std::fs::read_to_string(file)
+            .map_err(|e| ShaderError::new(stage, file, &e.to_string(), None))
+    };
+    let vshader = read(ShaderStage::Vertex, vshader_file)?;
+    let fshader = read(ShaderStage::Fragment, fshader_file)?;
+    link_program(&[
+        (ShaderStage::Vertex, vshader_file, &vshader),
+        (ShaderStage::Fragment, fshader_file, &fshader),
+    ])
+}
+
+// `layout(local_size_x = …, local_size_y = …, local_size_z = …) in;` dintr-un program de compute
+pub fn work_group_size(program: u32) -> [u32; 3] {
+    let mut size = [0i32; 3];
     unsafe {
-        let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-        gl::ShaderSource(
-            vertex_shader,
-            1,
-            &vshader.c_str().as_ptr(),
-            std::ptr::null(),
-        );
-        gl::CompileShader(vertex_shader);
-        check_shader_compile_status(vertex_shader, "Vertex");
-
-        let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-        gl::ShaderSource(
-            fragment_shader,
-            1,
-            &fshader.c_str().as_ptr(),
-            std::ptr::null(),
-        );
-        gl::CompileShader(fragment_shader);
-        check_shader_compile_status(fragment_shader, "Fragment");
+        gl::GetProgramiv(program, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+    }
+    size.map(|s| s.max(0) as u32)
+}
 
-        shader_program = gl::CreateProgram();
-        gl::AttachShader(shader_program, vertex_shader);
-        gl::AttachShader(shader_program, fragment_shader);
-        gl::LinkProgram(shader_program);
-        check_program_link_status(shader_program);
-        gl::UseProgram(shader_program);
-        gl::DeleteShader(vertex_shader);
-        gl::DeleteShader(fragment_shader);
+// ca link_program cu o singură etapă de compute, dar refuză un grup de lucru gol
+pub fn link_compute(file: &str, source: &str) -> Result<Program, ShaderError> {
+    let program = link_program(&[(ShaderStage::Compute, file, source)])?;
+    let size = work_group_size(program.id);
+    if size.contains(&0) {
+        unsafe {
+            gl::DeleteProgram(program.id);
+        }
+        let log = format!("local_size {:?} nu poate avea 0", size);
+        return Err(ShaderError::new(ShaderStage::Link, file, &log, None));
     }
-    shader_program
+    Ok(program)
+}
+
+#[track_caller]
+pub fn create_compute_shader_from(cshader: &str) -> Result<Program, ShaderError> {
+    let caller = std::panic::Location::caller();
+    let file = format!("{}:{}", caller.file(), caller.line());
+    link_compute(&file, cshader)
+}
+
+pub fn create_compute_shader_from_file(cshader_file: &str) -> Result<Program, ShaderError> {
+    let cshader = std::fs::read_to_string(cshader_file)
+        .map_err(|e| ShaderError::new(ShaderStage::Compute, cshader_file, &e.to_string(), None))?;
+    link_compute(cshader_file, &cshader)
 }
 
 pub struct Shader {
     pub id: u32,
     pub cnt: u32,
+    pub uniforms: UniformCache,
 }
 impl Drop for Shader {
     fn drop(&mut self) {
@@ -105,51 +351,48 @@ impl Drop for Shader {
 }
 impl Shader {
     pub fn new() -> Self {
-        Self { id: 0, cnt: 0 }
+        Self {
+            id: 0,
+            cnt: 0,
+            uniforms: UniformCache::new(),
+        }
     }
-    pub fn from_str(&mut self, vshader: &str, fshader: &str) {
-        let mut shader_program = 0;
-        unsafe {
-            let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-            gl::ShaderSource(
-                vertex_shader,
-                1,
-                &vshader.c_str().as_ptr(),
-                std::ptr::null(),
-            );
-            gl::CompileShader(vertex_shader);
-            check_shader_compile_status(vertex_shader, "Vertex");
-
-            let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-            gl::ShaderSource(
-                fragment_shader,
-                1,
-                &fshader.c_str().as_ptr(),
-                std::ptr::null(),
-            );
-            gl::CompileShader(fragment_shader);
-            check_shader_compile_status(fragment_shader, "Fragment");
-
-            shader_program = gl::CreateProgram();
-            gl::AttachShader(shader_program, vertex_shader);
-            gl::AttachShader(shader_program, fragment_shader);
-            gl::LinkProgram(shader_program);
-            check_program_link_status(shader_program);
-            gl::UseProgram(shader_program);
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
-        }
-        self.id = shader_program;
-    }
-    pub fn from_files(&mut self, vshader_file: &str, fshader_file: &str) {
-        let vshader =
-            std::fs::read_to_string(vshader_file).expect("Eroare la citirea din vertex file!");
-        let vshader = vshader.as_str();
-        let fshader =
-            std::fs::read_to_string(fshader_file).expect("Eroare la citirea din fragment file!");
-        let fshader = fshader.as_str();
-
-        self.from_str(vshader, fshader);
+    fn replace(&mut self, program: Program) -> Program {
+        if self.id != 0 {
+            unsafe {
+                gl::DeleteProgram(self.id);
+            }
+        }
+        self.id = program.id;
+        program
+    }
+    // la eroare shaderul vechi rămâne cum era
+    #[track_caller]
+    pub fn load_str(&mut self, vshader: &str, fshader: &str) -> Result<Program, ShaderError> {
+        let program = create_shader_from(vshader, fshader)?;
+        Ok(self.replace(program))
+    }
+    pub fn load_files(
+        &mut self,
+        vshader_file: &str,
+        fshader_file: &str,
+    ) -> Result<Program, ShaderError> {
+        let program = create_shader_from_files(vshader_file, fshader_file)?;
+        Ok(self.replace(program))
+    }
+    // ca load_files, dar trecut prin preprocesor: #include și varianta dată de defines
+    pub fn load_preprocessed(
+        &mut self,
+        preprocessor: &Preprocessor,
+        vshader_file: &str,
+        fshader_file: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Program, ShaderError> {
+        let program = preprocessor.compile(vshader_file, fshader_file, defines)?;
+        Ok(self.replace(program))
+    }
+    pub fn location(&self, name: &str) -> i32 {
+        self.uniforms.location(self.id, name)
     }
     pub fn bind(&mut self) {
         unsafe {
@@ -161,78 +404,213 @@ impl Shader {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0 + self.cnt);
             gl::BindTexture(gl::TEXTURE_2D, *texture_id);
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.id, texture_name.c_str().as_ptr()),
-                self.cnt as i32,
-            );
+            gl::Uniform1i(self.location(texture_name), self.cnt as i32);
             self.cnt += 1;
         }
     }
     pub fn set_int(&self, name: &str, val: i32) {
         unsafe {
-            gl::Uniform1i(gl::GetUniformLocation(self.id, name.c_str().as_ptr()), val);
+            gl::Uniform1i(self.location(name), val);
         }
     }
     pub fn set_float(&self, name: &str, val: f32) {
         unsafe {
-            gl::Uniform1f(gl::GetUniformLocation(self.id, name.c_str().as_ptr()), val);
+            gl::Uniform1f(self.location(name), val);
         }
     }
     pub fn set_vec2(&self, name: &str, val: [f32; 2]) {
         unsafe {
-            gl::Uniform2fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                val.as_ptr(),
-            );
+            gl::Uniform2fv(self.location(name), 1, val.as_ptr());
         }
     }
     pub fn set_vec3(&self, name: &str, val: [f32; 3]) {
         unsafe {
-            gl::Uniform3fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                val.as_ptr(),
-            );
+            gl::Uniform3fv(self.location(name), 1, val.as_ptr());
         }
     }
     pub fn set_vec4(&self, name: &str, val: [f32; 4]) {
         unsafe {
-            gl::Uniform4fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                val.as_ptr(),
-            );
+            gl::Uniform4fv(self.location(name), 1, val.as_ptr());
         }
     }
     pub fn set_mat2(&self, name: &str, val: &[f32; 4]) {
         unsafe {
-            gl::UniformMatrix2fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                val.as_ptr(),
-            );
+            gl::UniformMatrix2fv(self.location(name), 1, gl::FALSE, val.as_ptr());
         }
     }
     pub fn set_mat3(&self, name: &str, val: &[f32; 9]) {
         unsafe {
-            gl::UniformMatrix3fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                val.as_ptr(),
-            );
+            gl::UniformMatrix3fv(self.location(name), 1, gl::FALSE, val.as_ptr());
         }
     }
     pub fn set_mat4(&self, name: &str, val: *const f32) {
         unsafe {
-            gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.id, name.c_str().as_ptr()),
-                1,
-                gl::FALSE,
-                val,
-            );
+            gl::UniformMatrix4fv(self.location(name), 1, gl::FALSE, val);
+        }
+    }
+}
+
+/*
+Program de compute: fără vertex și fragment, pornit cu dispatch. Se creează doar dintr-un
+program linkat, deci local_size e mereu cunoscut și nu se poate face dispatch cu un program
+care nu e de compute. Uniformele se setează ca la Shader.
+*/
+pub struct ComputeShader {
+    shader: Shader,
+    // work_group_size, fără zerouri (link_compute le refuză)
+    pub local_size: [u32; 3],
+}
+impl ComputeShader {
+    fn from_program(program: Program) -> Self {
+        let mut shader = Shader::new();
+        shader.replace(program);
+        Self {
+            shader,
+            local_size: work_group_size(program.id),
+        }
+    }
+    #[track_caller]
+    pub fn load_str(cshader: &str) -> Result<Self, ShaderError> {
+        Ok(Self::from_program(create_compute_shader_from(cshader)?))
+    }
+    pub fn load_file(cshader_file: &str) -> Result<Self, ShaderError> {
+        Ok(Self::from_program(create_compute_shader_from_file(
+            cshader_file,
+        )?))
+    }
+    pub fn load_preprocessed(
+        preprocessor: &Preprocessor,
+        cshader_file: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Self, ShaderError> {
+        let program = preprocessor.compile_compute(cshader_file, defines)?;
+        Ok(Self::from_program(program))
+    }
+    pub fn bind(&mut self) {
+        self.shader.bind();
+    }
+    // numărul de grupuri pe fiecare axă, nu de invocări
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        unsafe {
+            gl::UseProgram(self.shader.id);
+            gl::DispatchCompute(x, y, z);
         }
     }
+    // destule grupuri pentru `count` invocări pe axa x, după local_size_x din shader
+    pub fn dispatch_items(&self, count: u32) {
+        self.dispatch(count.div_ceil(self.local_size[0]), 1, 1);
+    }
+}
+// set_int, set_float, location… ca la Shader
+impl std::ops::Deref for ComputeShader {
+    type Target = Shader;
+    fn deref(&self) -> &Shader {
+        &self.shader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "#version 330 core\nout vec4 color;\nvoid main() {\n    color = vec4(x);\n}";
+
+    fn position(d: &ShaderDiagnostic) -> (Option<usize>, Option<usize>, &str) {
+        (d.line, d.column, d.message.as_str())
+    }
+
+    #[test]
+    fn parse_mesa_log() {
+        let error = ShaderError::new(
+            ShaderStage::Fragment,
+            "a.frag",
+            "0:4(18): error: `x' undeclared\n0:4(13): error: no matching function for call to `vec4(error)'\n",
+            Some(SOURCE),
+        );
+        assert_eq!(error.diagnostics.len(), 2);
+        assert_eq!(
+            position(&error.diagnostics[0]),
+            (Some(4), Some(18), "error: `x' undeclared")
+        );
+        assert_eq!(
+            error.diagnostics[0].source_line.as_deref(),
+            Some("    color = vec4(x);")
+        );
+        assert_eq!(error.diagnostics[1].column, Some(13));
+    }
+
+    #[test]
+    fn parse_nvidia_log() {
+        let error = ShaderError::new(
+            ShaderStage::Vertex,
+            "a.vert",
+            "0(4) : error C1008: undefined variable \"x\"",
+            Some(SOURCE),
+        );
+        assert_eq!(
+            position(&error.diagnostics[0]),
+            (Some(4), None, "error C1008: undefined variable \"x\"")
+        );
+        assert!(error.diagnostics[0].source_line.is_some());
+    }
+
+    #[test]
+    fn parse_amd_intel_log() {
+        let error = ShaderError::new(
+            ShaderStage::Fragment,
+            "a.frag",
+            "ERROR: 0:4: 'x' : undeclared identifier\nWARNING: 0:2: unused\nERROR: 2 compilation errors.  No code generated.",
+            Some(SOURCE),
+        );
+        let d = &error.diagnostics;
+        assert_eq!(d.len(), 3);
+        // prefixul rămâne în mesaj
+        assert_eq!(
+            position(&d[0]),
+            (Some(4), None, "ERROR: 'x' : undeclared identifier")
+        );
+        assert_eq!(position(&d[1]), (Some(2), None, "WARNING: unused"));
+        assert_eq!(
+            position(&d[2]),
+            (
+                None,
+                None,
+                "ERROR: 2 compilation errors.  No code generated."
+            )
+        );
+    }
+
+    #[test]
+    fn lines_outside_the_source_keep_no_context() {
+        let error = ShaderError::new(
+            ShaderStage::Link,
+            "a",
+            "0:99(1): error: x\n0:0(1): error: y\nerror: linking failed",
+            Some(SOURCE),
+        );
+        let d = &error.diagnostics;
+        assert_eq!((d[0].line, d[0].source_line.clone()), (Some(99), None));
+        assert_eq!((d[1].line, d[1].source_line.clone()), (Some(0), None));
+        assert_eq!(position(&d[2]), (None, None, "error: linking failed"));
+        let without_source = ShaderError::new(ShaderStage::Link, "a", "0:1(1): error: x", None);
+        assert!(without_source.diagnostics[0].source_line.is_none());
+    }
+
+    #[test]
+    fn display_points_at_the_column() {
+        let error = ShaderError::new(
+            ShaderStage::Fragment,
+            "a.frag",
+            "0:4(18): error: `x' undeclared",
+            Some(SOURCE),
+        );
+        assert_eq!(
+            error.to_string(),
+            "fragment shader a.frag:\n  4:18: error: `x' undeclared\n    |     color = vec4(x);\n    |                  ^"
+        );
+        // fără diagnostice se afișează logul întreg
+        let empty = ShaderError::new(ShaderStage::Link, "a", "  \n", None);
+        assert_eq!(empty.to_string(), "link program a: ");
+    }
 }
\ No newline at end of file
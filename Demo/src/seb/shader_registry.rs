@@ -0,0 +1,283 @@
+use crate::seb::preprocessor::{self, Preprocessor};
+use crate::seb::seb::{Program, ShaderError, ShaderStage};
+use std::time::{Instant, SystemTime};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShaderHandle(usize);
+
+struct Entry {
+    key: String,
+    vertex: String,
+    fragment: String,
+    defines: Vec<(String, String)>,
+    program: Program,
+    // fișierele principale și tot ce includ, cu data ultimei modificări
+    files: Vec<(String, Option<SystemTime>)>,
+    // eroarea ultimei recompilări, cât timp sursa de pe disc nu se compilează
+    error: Option<ShaderError>,
+}
+
+/*
+Programe încărcate din fișiere care se recompilează singure când se schimbă sursa sau
+ceva inclus de ea. `update` se apelează o dată pe frame; la o eroare de compilare rămâne
+programul vechi, iar eroarea e păstrată în `error` până la o versiune care merge și dată
+o singură dată de `take_errors`, ca s-o afișeze cine vrea. Cine desenează trebuie să ia
+id-ul cu `program` după fiecare `update`.
+
+    let mut shaders = ShaderRegistry::new();
+    let handle = shaders.load(VERTEX_SHADER, FRAGMENT_SHADER, &[("SHADOWS", "1")])?;
+    ...
+    if shaders.update() {
+        model.shader_id = shaders.program(handle);
+    }
+    for error in shaders.take_errors() {
+        println!("{}", error);
+    }
+*/
+pub struct ShaderRegistry {
+    entries: Vec<Entry>,
+    pub preprocessor: Preprocessor,
+    // cât de des ne uităm pe disc, în secunde
+    pub interval: f32,
+    last_check: Instant,
+    // erorile apărute de la ultimul take_errors
+    errors: Vec<ShaderError>,
+}
+
+impl Drop for ShaderRegistry {
+    fn drop(&mut self) {
+        for entry in &self.entries {
+            unsafe {
+                gl::DeleteProgram(entry.program.id);
+            }
+        }
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+type Compiled = (Program, Vec<(String, Option<SystemTime>)>);
+
+fn compile(
+    preprocessor: &Preprocessor,
+    vertex: &str,
+    fragment: &str,
+    defines: &[(&str, &str)],
+) -> Result<Option<Compiled>, ShaderError> {
+    let vsource = preprocessor.expand(ShaderStage::Vertex, vertex, defines)?;
+    let fsource = preprocessor.expand(ShaderStage::Fragment, fragment, defines)?;
+    // editoarele golesc uneori fișierul înainte să-l scrie, încercăm la următorul update
+    if vsource.code.trim().is_empty() || fsource.code.trim().is_empty() {
+        return Ok(None);
+    }
+    let program = preprocessor::compile_sources(&vsource, &fsource)?;
+    let files = vsource
+        .files
+        .iter()
+        .chain(&fsource.files)
+        .map(|file| (file.clone(), modified(file)))
+        .collect();
+    Ok(Some((program, files)))
+}
+
+impl ShaderRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            preprocessor: Preprocessor::new(preprocessor::ASSETS),
+            interval: 0.25,
+            last_check: Instant::now(),
+            errors: Vec::new(),
+        }
+    }
+    // aceleași fișiere cu aceleași defines dau același program
+    pub fn load(
+        &mut self,
+        vertex: &str,
+        fragment: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<ShaderHandle, ShaderError> {
+        let key = preprocessor::permutation_key(vertex, fragment, defines);
+        if let Some(index) = self.entries.iter().position(|e| e.key == key) {
+            return Ok(ShaderHandle(index));
+        }
+        let (program, files) =
+            compile(&self.preprocessor, vertex, fragment, defines)?.ok_or_else(|| {
+                let file = format!("{} + {}", vertex, fragment);
+                ShaderError::new(ShaderStage::Link, &file, "sursă goală", None)
+            })?;
+        self.entries.push(Entry {
+            key,
+            vertex: vertex.to_string(),
+            fragment: fragment.to_string(),
+            defines: defines
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            program,
+            files,
+            error: None,
+        });
+        Ok(ShaderHandle(self.entries.len() - 1))
+    }
+    pub fn program(&self, handle: ShaderHandle) -> u32 {
+        self.entries[handle.0].program.id
+    }
+    pub fn error(&self, handle: ShaderHandle) -> Option<&ShaderError> {
+        self.entries[handle.0].error.as_ref()
+    }
+    // erorile noi din update-urile de până acum, fiecare o singură dată
+    pub fn take_errors(&mut self) -> Vec<ShaderError> {
+        std::mem::take(&mut self.errors)
+    }
+    // recompilează ce s-a schimbat pe disc; true dacă vreun program a fost înlocuit
+    pub fn update(&mut self) -> bool {
+        if self.last_check.elapsed().as_secs_f32() < self.interval {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let mut swapped = false;
+        for entry in &mut self.entries {
+            let changed = entry
+                .files
+                .iter()
+                .any(|(file, time)| modified(file) != *time);
+            if !changed {
+                continue;
+            }
+            let defines: Vec<(&str, &str)> = entry
+                .defines
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            match compile(&self.preprocessor, &entry.vertex, &entry.fragment, &defines) {
+                Ok(None) => {}
+                Ok(Some((program, files))) => {
+                    unsafe {
+                        gl::DeleteProgram(entry.program.id);
+                    }
+                    entry.program = program;
+                    entry.files = files;
+                    entry.error = None;
+                    swapped = true;
+                }
+                Err(e) => {
+                    self.errors.push(e.clone());
+                    entry.error = Some(e);
+                    // nu mai încercăm până la următoarea salvare
+                    for (file, time) in &mut entry.files {
+                        *time = modified(file);
+                    }
+                }
+            }
+        }
+        swapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::window::HeadlessTest;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    const VERTEX: &str = "#version 330 core\nvoid main() { gl_Position = vec4(0.0); }\n";
+    const RED: &str =
+        "#version 330 core\nout vec4 color;\nvoid main() { color = vec4(1.0, 0.0, 0.0, 1.0); }\n";
+    const GREEN: &str =
+        "#version 330 core\nout vec4 color;\nvoid main() { color = vec4(0.0, 1.0, 0.0, 1.0); }\n";
+    const BROKEN: &str =
+        "#version 330 core\nout vec4 color;\nvoid main() { color = vec4(0.0, 1.0; }\n";
+
+    fn path(test: &str, name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "shader-registry-{}-{}-{}",
+            std::process::id(),
+            test,
+            name
+        ))
+    }
+
+    // scrie fișierul și îi mută data în viitor, altfel rescrierea în aceeași
+    // secundă poate păstra data veche pe unele sisteme de fișiere
+    fn rewrite(path: &Path, code: &str, seconds: u64) {
+        std::fs::write(path, code).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    fn registry(vertex: &Path, fragment: &Path) -> (ShaderRegistry, ShaderHandle) {
+        let mut shaders = ShaderRegistry::new();
+        shaders.interval = 0.0;
+        let handle = shaders
+            .load(&vertex.to_string_lossy(), &fragment.to_string_lossy(), &[])
+            .unwrap();
+        (shaders, handle)
+    }
+
+    #[test]
+    #[ignore = "cere un context OpenGL: cargo test -- --ignored"]
+    fn reloads_after_edit() {
+        let _gl = HeadlessTest::new(16, 16);
+        let vertex = path("reload", "v.glsl");
+        let fragment = path("reload", "f.glsl");
+        std::fs::write(&vertex, VERTEX).unwrap();
+        std::fs::write(&fragment, RED).unwrap();
+
+        let (mut shaders, handle) = registry(&vertex, &fragment);
+        let old = shaders.program(handle);
+        // nimic schimbat pe disc
+        assert!(!shaders.update());
+        assert_eq!(shaders.program(handle), old);
+
+        rewrite(&fragment, GREEN, 1);
+        assert!(shaders.update());
+        assert_ne!(shaders.program(handle), old);
+        assert!(shaders.error(handle).is_none());
+        assert!(shaders.take_errors().is_empty());
+
+        std::fs::remove_file(vertex).unwrap();
+        std::fs::remove_file(fragment).unwrap();
+    }
+
+    #[test]
+    #[ignore = "cere un context OpenGL: cargo test -- --ignored"]
+    fn keeps_old_program_after_broken_edit() {
+        let _gl = HeadlessTest::new(16, 16);
+        let vertex = path("broken", "v.glsl");
+        let fragment = path("broken", "f.glsl");
+        std::fs::write(&vertex, VERTEX).unwrap();
+        std::fs::write(&fragment, RED).unwrap();
+
+        let (mut shaders, handle) = registry(&vertex, &fragment);
+        let old = shaders.program(handle);
+
+        rewrite(&fragment, BROKEN, 1);
+        assert!(!shaders.update());
+        assert_eq!(shaders.program(handle), old);
+        assert!(shaders.error(handle).is_some());
+        let errors = shaders.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].stage, ShaderStage::Fragment);
+        // eroarea e dată o singură dată și nu se recompilează fără o nouă salvare
+        assert!(!shaders.update());
+        assert!(shaders.take_errors().is_empty());
+        assert!(shaders.error(handle).is_some());
+
+        // după reparare se schimbă programul și dispare eroarea
+        rewrite(&fragment, GREEN, 2);
+        assert!(shaders.update());
+        assert_ne!(shaders.program(handle), old);
+        assert!(shaders.error(handle).is_none());
+
+        std::fs::remove_file(vertex).unwrap();
+        std::fs::remove_file(fragment).unwrap();
+    }
+}
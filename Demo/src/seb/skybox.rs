@@ -2,20 +2,22 @@
 use core::f32;
 
 use crate::seb::{
-    seb::{ToCStr, create_shader_from},
+    seb::{ShaderError, create_shader_from},
+    uniforms::UniformCache,
 };
 use nalgebra_glm as glm;
 use stb_image;
 
 pub struct Skybox {
     shader_id: u32,
+    uniforms: UniformCache,
     texture_id: u32,
     vao: u32,
     vbo: u32,
 }
 
 impl Skybox {
-    pub fn new(faces: [&str; 6]) -> Self {
+    pub fn new(faces: [&str; 6]) -> Result<Self, ShaderError> {
         let vertex_shader = r#"
             #version 330 core
             layout (location = 0) in vec3 aPos;
@@ -43,7 +45,7 @@ impl Skybox {
             }
         "#;
 
-        let shader_id = create_shader_from(vertex_shader, fragment_shader);
+        let shader_id = create_shader_from(vertex_shader, fragment_shader)?.id;
 
         // Cube vertices
         let vertices: [f32; 108] = [
@@ -125,12 +127,13 @@ impl Skybox {
             );
         }
 
-        Self {
+        Ok(Self {
             shader_id,
+            uniforms: UniformCache::new(),
             texture_id,
             vao,
             vbo,
-        }
+        })
     }
 
     pub fn draw(&self, projection: glm::Mat4, view: glm::Mat4) {
@@ -142,13 +145,13 @@ impl Skybox {
             let view = glm::mat3_to_mat4(&view_no_translation);
 
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
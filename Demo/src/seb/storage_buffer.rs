@@ -0,0 +1,227 @@
+use std::marker::PhantomData;
+
+// ce trebuie să vadă comenzile de după un dispatch care a scris în buffer
+// alt dispatch care citește bufferul
+pub const STORAGE_BARRIER: u32 = gl::SHADER_STORAGE_BARRIER_BIT;
+// bufferul e folosit apoi ca vertex buffer (particule)
+pub const VERTEX_BARRIER: u32 = gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT;
+// bufferul dă argumentele pentru DrawArraysIndirect/DrawElementsIndirect (culling)
+pub const INDIRECT_BARRIER: u32 = gl::COMMAND_BARRIER_BIT;
+// citire sau scriere din CPU, cu read/update
+pub const READBACK_BARRIER: u32 = gl::BUFFER_UPDATE_BARRIER_BIT;
+
+// bițile se pot combina: barrier(STORAGE_BARRIER | VERTEX_BARRIER)
+pub fn barrier(bits: u32) {
+    unsafe {
+        gl::MemoryBarrier(bits);
+    }
+}
+
+/*
+Tipuri care pot fi citite înapoi din GPU. O structură #[repr(C)] făcută doar din câmpuri
+Pod se declară așa:
+
+    unsafe impl Pod for Particle {}
+
+# Safety
+Orice șir de octeți de mărimea lui T trebuie să fie o valoare validă: fără pointeri, fără
+padding și fără valori interzise. Nu e cazul pentru bool, char, enum-uri, referințe sau
+NonZero*. Regula stă aici și nu în rustdoc, de aici allow-ul de mai jos.
+*/
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for f32 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/*
+Un shader storage buffer cu `len` elemente T, legat la `binding`:
+
+    layout(std430, binding = 0) buffer Particles { Particle particles[]; };
+
+T trebuie să fie Pod și să aibă aceeași aranjare ca în std430: un vec3 se aliniază
+la 16 octeți, deci e mai simplu cu vec4 și [f32; 4].
+
+    let particles = StorageBuffer::new(0, &initial);
+    shader.dispatch_items(particles.len() as u32);
+    barrier(VERTEX_BARRIER);
+*/
+pub struct StorageBuffer<T: Pod> {
+    pub id: u32,
+    pub binding: u32,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T: Pod> Drop for StorageBuffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
+
+impl<T: Pod> StorageBuffer<T> {
+    fn size(count: usize) -> isize {
+        (count * std::mem::size_of::<T>()) as isize
+    }
+    fn allocate(binding: u32, len: usize, data: *const T) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, id);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                Self::size(len),
+                data as *const _,
+                gl::DYNAMIC_COPY,
+            );
+            if data.is_null() {
+                // fără date BufferData lasă conținutul nedefinit
+                gl::ClearBufferData(
+                    gl::SHADER_STORAGE_BUFFER,
+                    gl::R8UI,
+                    gl::RED_INTEGER,
+                    gl::UNSIGNED_BYTE,
+                    std::ptr::null(),
+                );
+            }
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+        let buffer = Self {
+            id,
+            binding,
+            len,
+            marker: PhantomData,
+        };
+        buffer.bind();
+        buffer
+    }
+    pub fn new(binding: u32, data: &[T]) -> Self {
+        Self::allocate(binding, data.len(), data.as_ptr())
+    }
+    // `len` elemente puse pe zero, pentru ce umple shaderul
+    pub fn zeroed(binding: u32, len: usize) -> Self {
+        Self::allocate(binding, len, std::ptr::null())
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    // când mai multe buffere își împart același punct
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, self.binding, self.id);
+        }
+    }
+    // fără depășire la adunare: offset + count poate trece de usize::MAX
+    fn check_range(&self, offset: usize, count: usize) {
+        assert!(
+            offset.checked_add(count).is_some_and(|end| end <= self.len),
+            "StorageBuffer: {} elemente de la {} în afara celor {}",
+            count,
+            offset,
+            self.len
+        );
+    }
+    // scrie `data` începând cu elementul `offset`
+    pub fn update(&mut self, offset: usize, data: &[T]) {
+        self.check_range(offset, data.len());
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+            gl::BufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                Self::size(offset),
+                Self::size(data.len()),
+                data.as_ptr() as *const _,
+            );
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+    }
+    pub fn read(&self) -> Vec<T> {
+        self.read_range(0, self.len)
+    }
+    // așteaptă scrierile shaderelor de dinainte, deci nu mai trebuie barrier
+    pub fn read_range(&self, offset: usize, count: usize) -> Vec<T> {
+        self.check_range(offset, count);
+        let mut data: Vec<T> = Vec::with_capacity(count);
+        unsafe {
+            gl::MemoryBarrier(READBACK_BARRIER);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                Self::size(offset),
+                Self::size(count),
+                data.as_mut_ptr() as *mut _,
+            );
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+            data.set_len(count);
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::seb::ComputeShader;
+    use crate::seb::window::HeadlessTest;
+
+    const SCALE: &str = r#"
+        #version 430
+        layout(local_size_x = 64) in;
+        layout(std430, binding = 3) buffer Values { float values[]; };
+        uniform float factor;
+        void main() {
+            uint i = gl_GlobalInvocationID.x;
+            if (i < uint(values.length())) {
+                values[i] *= factor;
+            }
+        }
+    "#;
+
+    #[test]
+    #[ignore = "cere un context OpenGL: cargo test -- --ignored"]
+    fn dispatch_and_read_back() {
+        let _gl = HeadlessTest::new(16, 16);
+        let mut shader = ComputeShader::load_str(SCALE).unwrap();
+        assert_eq!(shader.local_size, [64, 1, 1]);
+
+        // 1000 nu se împarte la 64, ultimul grup are invocări în plus
+        let data: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let mut values = StorageBuffer::new(3, &data);
+        shader.bind();
+        shader.set_float("factor", 2.0);
+        shader.dispatch_items(values.len() as u32);
+        let doubled = values.read();
+        assert_eq!(doubled.len(), 1000);
+        for (i, v) in doubled.iter().enumerate() {
+            assert_eq!(*v, i as f32 * 2.0);
+        }
+
+        values.update(10, &[-1.0, -2.0]);
+        shader.dispatch_items(values.len() as u32);
+        barrier(STORAGE_BARRIER);
+        assert_eq!(values.read_range(9, 4), vec![36.0, -2.0, -4.0, 48.0]);
+
+        let zeroed = StorageBuffer::<[f32; 4]>::zeroed(4, 8);
+        assert_eq!(zeroed.read(), vec![[0.0; 4]; 8]);
+
+        let empty =
+            ComputeShader::load_str("#version 430\nlayout(local_size_x = 0) in;\nvoid main() {}");
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    #[ignore = "cere un context OpenGL: cargo test -- --ignored"]
+    #[should_panic(expected = "în afara")]
+    fn read_past_the_end_panics() {
+        let _gl = HeadlessTest::new(16, 16);
+        let values = StorageBuffer::<u32>::zeroed(0, 4);
+        values.read_range(usize::MAX, 2);
+    }
+}
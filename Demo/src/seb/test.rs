@@ -1,22 +1,26 @@
-use crate::seb::seb::{ToCStr, create_shader_from};
+use crate::seb::seb::{ShaderError, create_shader_from_files};
+use crate::seb::uniforms::UniformCache;
 use nalgebra_glm as glm;
 
+pub const VERTEX_SHADER: &str = "./assets/blackhole/vertex.glsl";
+pub const FRAGMENT_SHADER: &str = "./assets/blackhole/fragment2.glsl";
+
 pub struct BlackHole {
     vao: u32,
     indices_len: i32,
-    ssbo: u32,
-    shader_id: u32,
+    // se poate înlocui cu un program din ShaderRegistry pentru reîncărcare din mers
+    pub shader_id: u32,
+    uniforms: UniformCache,
     pub texture_id_1: u32,
     pub texture_id_2: u32,
 }
 
 impl BlackHole {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self, ShaderError> {
         let mut vao = 0;
         let mut vbo = 0;
         let mut ebo = 0;
         let mut tvbo = 0;
-        let mut ssbo = 0;
         let mut shader_id = 0;
 
         let vert: [f32; 12] = [
@@ -27,12 +31,6 @@ impl BlackHole {
 
         let tcoords: [f32; 8] = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0];
 
-        let mut data: Vec<f32> = Vec::new();
-        for i in 0..1000 {
-            let v = i as f32 / 1000.0;
-            data.push(v);
-        }
-
         unsafe {
             gl::GenVertexArrays(1, &mut vao);
             gl::BindVertexArray(vao);
@@ -86,35 +84,20 @@ impl BlackHole {
             );
             gl::EnableVertexAttribArray(1);
 
-            // gl::GenBuffers(1, &mut ssbo);
-            // gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
-
-            // gl::BufferData(
-            //     gl::SHADER_STORAGE_BUFFER,
-            //     (data.len() * std::mem::size_of::<f32>()) as isize,
-            //     data.as_ptr() as *const _,
-            //     gl::DYNAMIC_DRAW,
-            // );
-
-            // gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 0, ssbo);
-            // gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
-
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl::BindVertexArray(0);
         }
 
-        let vshader = std::fs::read_to_string("./assets/blackhole/vertex.glsl").unwrap();
-        let fshader = std::fs::read_to_string("./assets/blackhole/fragment2.glsl").unwrap();
-        shader_id = create_shader_from(vshader.as_str(), fshader.as_str());
+        shader_id = create_shader_from_files(VERTEX_SHADER, FRAGMENT_SHADER)?.id;
 
-        Self {
+        Ok(Self {
             vao,
             indices_len: indices.len() as i32,
-            ssbo,
             shader_id,
+            uniforms: UniformCache::new(),
             texture_id_1: 0,
             texture_id_2: 0,
-        }
+        })
     }
     pub fn load_texture(&mut self, path: &str) -> Result<u32, String> {
         let mut texture_id = 0;
@@ -184,20 +167,20 @@ impl BlackHole {
             gl::UseProgram(self.shader_id);
 
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "projection".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "projection"),
                 1,
                 gl::FALSE,
                 projection.as_ptr(),
             );
 
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "view".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "view"),
                 1,
                 gl::FALSE,
                 view.as_ptr(),
             );
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self.shader_id, "model".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "model"),
                 1,
                 gl::FALSE,
                 model.as_ptr(),
@@ -209,42 +192,33 @@ impl BlackHole {
             let black_hole_y = 100.0;
             let black_hole_radius = 50.0;
 
-            gl::Uniform1f(
-                gl::GetUniformLocation(self.shader_id, "iTime".c_str().as_ptr()),
-                time,
-            );
+            gl::Uniform1f(self.uniforms.location(self.shader_id, "iTime"), time);
             gl::Uniform2f(
-                gl::GetUniformLocation(self.shader_id, "iResolution".c_str().as_ptr()),
+                self.uniforms.location(self.shader_id, "iResolution"),
                 size_x,
                 size_y,
             );
             // gl::Uniform2f(
-            //     gl::GetUniformLocation(self.shader_id, "iBlackHolePos".c_str().as_ptr()),
+            //     self.uniforms.location(self.shader_id, "iBlackHolePos"),
             //      black_hole_x / size_x,
             //      black_hole_y / size_y,
             // );
             // gl::Uniform1f(
-            //     gl::GetUniformLocation(self.shader_id, "iBlackHoleMass".c_str().as_ptr()),
+            //     self.uniforms.location(self.shader_id, "iBlackHoleMass"),
             //     10f32,
             // );
             // gl::Uniform1f(
-            //     gl::GetUniformLocation(self.shader_id, "iBlackHoleRadius".c_str().as_ptr()),
+            //     self.uniforms.location(self.shader_id, "iBlackHoleRadius"),
             //     black_hole_radius / size_x,
             // );
 
             gl::BindVertexArray(self.vao);
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.texture_id_1);
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.shader_id, "iChannel0".c_str().as_ptr()),
-                0,
-            );
+            gl::Uniform1i(self.uniforms.location(self.shader_id, "iChannel0"), 0);
             gl::ActiveTexture(gl::TEXTURE1);
             gl::BindTexture(gl::TEXTURE_2D, self.texture_id_2);
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.shader_id, "iChannel1".c_str().as_ptr()),
-                1,
-            );
+            gl::Uniform1i(self.uniforms.location(self.shader_id, "iChannel1"), 1);
 
             gl::DrawElements(
                 gl::TRIANGLES,
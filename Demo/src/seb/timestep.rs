@@ -0,0 +1,165 @@
+use crate::seb::collision::Collider;
+use nalgebra as na;
+use nalgebra_glm as glm;
+
+// un frame mai lung de atât (breakpoint, fereastră trasă) nu mai e recuperat
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/*
+Bucla cu pas fix: timpul real dintre frame-uri se adună într-un acumulator din care
+update-urile consumă pași de `step` secunde, așa că fizica și animațiile nu mai depind
+de rata de refresh. Ce rămâne în acumulator dă `alpha`, cât de departe suntem între
+ultimele două stări, pentru desenat. Timpul frame-ului vine de la fereastră, după poll_events.
+
+    window.poll_events();
+    for _ in 0..clock.advance(window.delta_time()) {
+        world.step(clock.step);
+    }
+    let alpha = clock.alpha();
+*/
+pub struct FixedTimestep {
+    pub step: f32,
+    // cel mult atâția pași pe frame, altfel un frame lent aduce și mai mulți pași
+    pub max_steps: usize,
+    accumulator: f32,
+    frame_time: f32,
+    time: f64,
+}
+
+impl FixedTimestep {
+    // `rate` e numărul de pași pe secundă
+    pub fn new(rate: f32) -> Self {
+        Self {
+            step: 1.0 / rate,
+            max_steps: 8,
+            accumulator: 0.0,
+            frame_time: 0.0,
+            time: 0.0,
+        }
+    }
+    // adaugă timpul trecut de la frame-ul trecut și întoarce câți pași trebuie rulați acum
+    pub fn advance(&mut self, dt: f32) -> usize {
+        self.frame_time = dt.clamp(0.0, MAX_FRAME_TIME);
+        self.accumulator += self.frame_time;
+        let steps = ((self.accumulator / self.step) as usize).min(self.max_steps);
+        self.accumulator -= steps as f32 * self.step;
+        if steps == self.max_steps {
+            // am rămas în urmă, renunțăm la restul în loc să alergăm după el
+            self.accumulator = self.accumulator.min(self.step);
+        }
+        self.time += steps as f64 * self.step as f64;
+        steps
+    }
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+    // timpul real al ultimului frame, pentru ce se mișcă o dată pe frame (camera, lumini)
+    pub fn frame_time(&self) -> f32 {
+        self.frame_time
+    }
+    // timpul simulat, adică numărul de pași înmulțit cu `step`
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+// colliderul între starea de la pasul trecut și cea de acum, pentru desenat
+pub fn interpolate(previous: &Collider, current: &Collider, alpha: f32) -> Collider {
+    let mut collider = current.clone();
+    collider.set_position(glm::lerp(&previous.position(), &current.position(), alpha));
+    let from = na::UnitQuaternion::from_matrix(&previous.rotation());
+    let to = na::UnitQuaternion::from_matrix(&current.rotation());
+    // la rotații opuse slerp nu are o direcție, rămânem pe starea nouă
+    if let Some(rotation) = from.try_slerp(&to, alpha, 1e-6) {
+        collider.set_rotation(rotation.to_rotation_matrix().matrix());
+    }
+    collider
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::collision::euler_to_matrix;
+    use crate::seb::primitives::Rectangle;
+
+    fn near(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn accumulator_carries_over_between_frames() {
+        let mut clock = FixedTimestep::new(100.0);
+        // 2.5 pași: 2 acum, jumătate rămâne
+        assert_eq!(clock.advance(0.025), 2);
+        assert!(near(clock.alpha(), 0.5));
+        // jumătatea rămasă plus încă 0.6 pași fac un pas
+        assert_eq!(clock.advance(0.006), 1);
+        assert!(near(clock.alpha(), 0.1));
+        assert_eq!(clock.advance(0.004), 0);
+        assert!(near(clock.alpha(), 0.5));
+        assert!((clock.time() - 0.03).abs() < 1e-6);
+        assert!(near(clock.frame_time(), 0.004));
+    }
+
+    #[test]
+    fn max_steps_drops_the_backlog() {
+        let mut clock = FixedTimestep::new(100.0);
+        clock.max_steps = 4;
+        // 20 de pași de recuperat, doar 4 rulați, restul e aruncat
+        assert_eq!(clock.advance(0.2), 4);
+        assert!(clock.alpha() <= 1.0);
+        assert!((clock.time() - 0.04).abs() < 1e-6);
+        // frame-ul următor nu mai aduce pașii pierduți
+        assert!(clock.advance(0.01) <= 2);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut clock = FixedTimestep::new(10.0);
+        clock.max_steps = 100;
+        // 5 secunde devin MAX_FRAME_TIME, adică 2 pași și jumătate
+        assert_eq!(clock.advance(5.0), 2);
+        assert!(near(clock.frame_time(), MAX_FRAME_TIME));
+        assert!(near(clock.alpha(), 0.5));
+        // timpul negativ nu dă înapoi acumulatorul
+        assert_eq!(clock.advance(-1.0), 0);
+        assert_eq!(clock.frame_time(), 0.0);
+        assert!(near(clock.alpha(), 0.5));
+    }
+
+    #[test]
+    fn alpha_stays_between_zero_and_one() {
+        let mut clock = FixedTimestep::new(60.0);
+        clock.max_steps = 2;
+        for i in 0..200 {
+            // frame-uri de lungimi variate, unele mult peste max_steps
+            let dt = (i % 13) as f32 * 0.013;
+            clock.advance(dt);
+            let alpha = clock.alpha();
+            assert!((0.0..=1.0).contains(&alpha), "alpha {} după {}", alpha, dt);
+        }
+    }
+
+    #[test]
+    fn interpolate_matches_the_ends() {
+        let mut previous = Rectangle::new();
+        previous.position = glm::vec3(0.0, 0.0, 0.0);
+        let mut current = Rectangle::new();
+        current.position = glm::vec3(2.0, 4.0, -6.0);
+        current.y_angle = 60.0;
+        let previous = Collider::Rectangle(previous);
+        let current = Collider::Rectangle(current);
+
+        let start = interpolate(&previous, &current, 0.0);
+        assert!(glm::distance(&start.position(), &previous.position()) < 1e-5);
+        assert!((start.rotation() - previous.rotation()).norm() < 1e-4);
+
+        let end = interpolate(&previous, &current, 1.0);
+        assert!(glm::distance(&end.position(), &current.position()) < 1e-5);
+        assert!((end.rotation() - current.rotation()).norm() < 1e-4);
+
+        let middle = interpolate(&previous, &current, 0.5);
+        assert!(glm::distance(&middle.position(), &glm::vec3(1.0, 2.0, -3.0)) < 1e-5);
+        assert!((middle.rotation() - euler_to_matrix(0.0, 30.0, 0.0)).norm() < 1e-3);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::seb::seb::ToCStr;
+use nalgebra_glm as glm;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// punctele fixe la care sunt legate blocurile comune, în toate programele
+pub const CAMERA_BINDING: u32 = 0;
+pub const LIGHT_BINDING: u32 = 1;
+// numele blocului din GLSL -> punctul lui; link_program le leagă singur
+pub const BLOCKS: &[(&str, u32)] = &[("Camera", CAMERA_BINDING), ("Light", LIGHT_BINDING)];
+
+/*
+layout(std140) uniform Camera, din assets/shaders/camera.glsl. În std140 un vec3 ocupă
+cât un vec4, așa că pozițiile sunt trimise ca vec4 și citite cu `.xyz`.
+*/
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CameraBlock {
+    pub projection: [f32; 16],
+    pub view: [f32; 16],
+    pub camera_pos: [f32; 4],
+}
+
+// layout(std140) uniform Light, din assets/shaders/light.glsl
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct LightBlock {
+    pub light_space_matrix: [f32; 16],
+    pub light_pos: [f32; 4],
+}
+
+// dacă cineva schimbă structurile, trebuie să rămână identice cu blocurile din GLSL
+const _: () = assert!(std::mem::size_of::<CameraBlock>() == 144);
+const _: () = assert!(std::mem::size_of::<LightBlock>() == 80);
+
+fn mat4(m: &glm::Mat4) -> [f32; 16] {
+    let mut out = [0.0; 16];
+    out.copy_from_slice(m.as_slice());
+    out
+}
+
+impl CameraBlock {
+    pub fn new(projection: &glm::Mat4, view: &glm::Mat4, camera_pos: &glm::Vec3) -> Self {
+        Self {
+            projection: mat4(projection),
+            view: mat4(view),
+            camera_pos: [camera_pos.x, camera_pos.y, camera_pos.z, 1.0],
+        }
+    }
+}
+
+impl LightBlock {
+    pub fn new(light_space_matrix: &glm::Mat4, light_pos: &glm::Vec3) -> Self {
+        Self {
+            light_space_matrix: mat4(light_space_matrix),
+            light_pos: [light_pos.x, light_pos.y, light_pos.z, 1.0],
+        }
+    }
+}
+
+/*
+Un uniform buffer cu exact un T. Bufferul se creează la primul `update`, deci `new` merge
+și fără context OpenGL; fiecare `update` îl leagă din nou la `binding`.
+
+    let mut camera = UniformBuffer::new(uniforms::CAMERA_BINDING);
+    ...
+    camera.update(&CameraBlock::new(&player.projection, &player.view, &player.cam_pos));
+*/
+#[derive(Debug)]
+pub struct UniformBuffer<T: Copy> {
+    pub id: u32,
+    pub binding: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T: Copy> Drop for UniformBuffer<T> {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            unsafe {
+                gl::DeleteBuffers(1, &self.id);
+            }
+        }
+    }
+}
+
+impl<T: Copy> UniformBuffer<T> {
+    pub fn new(binding: u32) -> Self {
+        Self {
+            id: 0,
+            binding,
+            marker: PhantomData,
+        }
+    }
+    pub fn update(&mut self, data: &T) {
+        upload(&mut self.id, self.binding, data);
+    }
+    // când mai multe buffere își împart același punct (mai multe lumini)
+    pub fn bind(&self) {
+        bind_buffer(self.binding, self.id);
+    }
+}
+
+/*
+Ce face UniformBuffer::update, pentru cine ține doar id-ul (ex: Light, care e Copy și
+deci nu poate avea Drop). Bufferul e creat când `id` e 0; cine îl ține trebuie să-l șteargă.
+*/
+pub fn upload<T: Copy>(id: &mut u32, binding: u32, data: &T) {
+    let size = std::mem::size_of::<T>() as isize;
+    unsafe {
+        if *id == 0 {
+            gl::GenBuffers(1, id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, *id);
+            gl::BufferData(gl::UNIFORM_BUFFER, size, std::ptr::null(), gl::DYNAMIC_DRAW);
+        } else {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, *id);
+        }
+        gl::BufferSubData(gl::UNIFORM_BUFFER, 0, size, data as *const T as *const _);
+        gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+    }
+    bind_buffer(binding, *id);
+}
+
+pub fn bind_buffer(binding: u32, id: u32) {
+    unsafe {
+        gl::BindBufferBase(gl::UNIFORM_BUFFER, binding, id);
+    }
+}
+
+// bufferul legat acum la `binding`, 0 dacă nu e niciunul
+pub fn bound_buffer(binding: u32) -> u32 {
+    let mut id = 0;
+    unsafe {
+        gl::GetIntegeri_v(gl::UNIFORM_BUFFER_BINDING, binding, &mut id);
+    }
+    id as u32
+}
+
+// leagă blocurile din BLOCKS pe care programul le folosește
+pub fn bind_blocks(program: u32) {
+    for &(name, binding) in BLOCKS {
+        unsafe {
+            let index = gl::GetUniformBlockIndex(program, name.c_str().as_ptr());
+            if index != gl::INVALID_INDEX {
+                gl::UniformBlockBinding(program, index, binding);
+            }
+        }
+    }
+}
+
+// locațiile tuturor uniformelor active; cele din blocuri nu au locație și lipsesc
+pub fn reflect(program: u32) -> HashMap<String, i32> {
+    let mut locations = HashMap::new();
+    unsafe {
+        let mut count = 0;
+        let mut max_length = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+        let mut buffer = vec![0u8; max_length.max(1) as usize];
+        for index in 0..count.max(0) as u32 {
+            let mut length = 0;
+            let mut size = 0;
+            let mut kind = 0;
+            gl::GetActiveUniform(
+                program,
+                index,
+                buffer.len() as i32,
+                &mut length,
+                &mut size,
+                &mut kind,
+                buffer.as_mut_ptr() as *mut _,
+            );
+            let name = String::from_utf8_lossy(&buffer[..length.max(0) as usize]).into_owned();
+            let location = gl::GetUniformLocation(program, name.c_str().as_ptr());
+            if location < 0 {
+                continue;
+            }
+            // un tablou apare ca "lights[0]", dar e cerut de obicei ca "lights"
+            if let Some(base) = name.strip_suffix("[0]") {
+                locations.insert(base.to_string(), location);
+            }
+            locations.insert(name, location);
+        }
+    }
+    locations
+}
+
+/*
+Locațiile uniformelor unui program, citite o singură dată. Programul e dat la fiecare
+cerere, așa că după o reîncărcare (alt id) cache-ul se reface singur. Ce nu apare în
+reflecție (elemente de tablou, câmpuri de struct) e cerut driverului o dată și păstrat;
+un nume inexistent dă -1, pe care OpenGL îl ignoră.
+*/
+#[derive(Debug, Default)]
+pub struct UniformCache {
+    program: Cell<u32>,
+    locations: RefCell<HashMap<String, i32>>,
+}
+
+impl UniformCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn location(&self, program: u32, name: &str) -> i32 {
+        let mut locations = self.locations.borrow_mut();
+        if self.program.get() != program {
+            *locations = reflect(program);
+            self.program.set(program);
+        }
+        if let Some(&location) = locations.get(name) {
+            return location;
+        }
+        let location = unsafe { gl::GetUniformLocation(program, name.c_str().as_ptr()) };
+        locations.insert(name.to_string(), location);
+        location
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seb::seb::{ShaderStage, link_program};
+    use crate::seb::window::HeadlessTest;
+
+    const VERTEX: &str = "#version 330 core\nvoid main() { gl_Position = vec4(0.0); }\n";
+    const FRAGMENT: &str = r#"
+        #version 330 core
+        layout(std140) uniform Camera { mat4 view; };
+        uniform vec4 lights[2];
+        uniform float scale;
+        out vec4 color;
+        void main() { color = (lights[0] + lights[1]) * scale + view[0]; }
+    "#;
+    const OTHER: &str = r#"
+        #version 330 core
+        uniform float scale;
+        out vec4 color;
+        void main() { color = vec4(scale); }
+    "#;
+
+    fn program(fragment: &str) -> u32 {
+        link_program(&[
+            (ShaderStage::Vertex, "test", VERTEX),
+            (ShaderStage::Fragment, "test", fragment),
+        ])
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    #[ignore = "cere un context OpenGL: cargo test -- --ignored"]
+    fn reflects_and_caches_locations() {
+        let _gl = HeadlessTest::new(16, 16);
+        let first = program(FRAGMENT);
+
+        let locations = reflect(first);
+        assert!(locations["lights[0]"] >= 0);
+        assert_eq!(locations["lights"], locations["lights[0]"]);
+        assert!(locations["scale"] >= 0);
+        // câmpurile din blocuri nu au locație
+        assert!(!locations.keys().any(|name| name.contains("view")));
+
+        let cache = UniformCache::new();
+        assert_eq!(cache.location(first, "lights"), locations["lights"]);
+        assert_eq!(cache.location(first, "missing"), -1);
+        assert_eq!(cache.locations.borrow().get("missing"), Some(&-1));
+        // lights[1] nu apare în reflecție, e cerut driverului și păstrat
+        let second_light = cache.location(first, "lights[1]");
+        assert!(second_light >= 0);
+        assert_eq!(
+            cache.locations.borrow().get("lights[1]"),
+            Some(&second_light)
+        );
+
+        // alt program (ca după o reîncărcare): cache-ul se reface de la zero
+        let second = program(OTHER);
+        assert!(cache.location(second, "scale") >= 0);
+        assert_eq!(cache.program.get(), second);
+        assert!(!cache.locations.borrow().contains_key("missing"));
+        assert!(!cache.locations.borrow().contains_key("lights"));
+
+        unsafe {
+            gl::DeleteProgram(first);
+            gl::DeleteProgram(second);
+        }
+    }
+}
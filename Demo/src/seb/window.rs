@@ -1,8 +1,10 @@
+use crate::seb::framebuffer::{self, FrameBuffer};
+use crate::seb::replay::{Frame, Recording, Replay};
 use glfw::Context;
 use glfw::{GlfwReceiver, fail_on_errors};
 
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum MouseScroll {
     Up,
     Down,
@@ -20,19 +22,35 @@ pub struct MouseButton {
 pub struct Mouse {
     pub x: f64,
     pub y: f64,
+    // cât s-a mișcat și cât s-a dat din rotiță în frame-ul ăsta, adunat din toate evenimentele
+    pub dx: f64,
+    pub dy: f64,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
     pub buttons: Vec<MouseButton>,
     pub scroll: Vec<MouseScroll>,
+    // prima poziție primită nu e o mișcare
+    has_position: bool,
 }
 impl Mouse {
     fn new() -> Self {
         Self {
             x: 0.0,
             y: 0.0,
+            dx: 0.0,
+            dy: 0.0,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
             buttons: Vec::new(),
             scroll: Vec::new(),
+            has_position: false,
         }
     }
     pub fn reset(&mut self) {
+        self.dx = 0.0;
+        self.dy = 0.0;
+        self.scroll_x = 0.0;
+        self.scroll_y = 0.0;
         self.buttons.clear();
         self.scroll.clear();
     }
@@ -74,6 +92,24 @@ pub struct Window {
     pub mouse: Mouse,
     pub keyboard: Keyboard,
     pub drag_and_drop_files: Vec<std::path::PathBuf>,
+    // secunde de la pornirea GLFW, luate la ultimul poll_events
+    pub time: f64,
+    delta_time: f32,
+    recording: Option<Recording>,
+    // unde se salvează înregistrarea la oprire
+    record_path: Option<String>,
+    replay: Option<Replay>,
+    // ținta desenării când nu avem fereastră vizibilă
+    offscreen: Option<FrameBuffer>,
+}
+
+impl Drop for Window {
+    // o înregistrare pornită cu record_to nu se pierde dacă demo-ul iese direct
+    fn drop(&mut self) {
+        self.stop_recording();
+        // framebuffer-ul trebuie șters cât contextul mai există
+        self.offscreen = None;
+    }
 }
 
 impl Window {
@@ -88,6 +124,12 @@ impl Window {
             mouse: Mouse::new(),
             keyboard: Keyboard::new(),
             drag_and_drop_files: Vec::new(),
+            time: 0.0,
+            delta_time: 0.0,
+            recording: None,
+            record_path: None,
+            replay: None,
+            offscreen: None,
         }
     }
     pub fn create(&mut self, width: u32, height: u32, title: &str) {
@@ -122,13 +164,102 @@ impl Window {
         window.set_char_polling(true);
         window.set_drag_and_drop_polling(true);
 
+        Self::load_gl(&mut window);
+        self.init_gl();
+
+        self.glfw = Some(glfw);
+        self.glfw_window = Some(window);
+        self.glfw_events = Some(events);
+    }
+    /*
+    Context GL 4.3 fără fereastră vizibilă, pentru mașini fără display sau GPU: platforma
+    null a GLFW cu EGL surfaceless, iar dacă nu merge, OSMesa (rasterizatorul software din
+    Mesa). Se desenează într-un FrameBuffer care ține locul ferestrei, așa că restul
+    codului nu știe diferența; imaginea se ia cu `read_pixels`.
+    */
+    pub fn create_headless(&mut self, width: u32, height: u32) -> Result<(), String> {
+        self.width = width;
+        self.height = height;
+
+        if glfw::Platform::Null.is_supported() {
+            glfw::init_hint(glfw::InitHint::Platform(glfw::Platform::Null));
+        }
+        let mut glfw = glfw::init(|_, description| println!("GLFW: {}", description))
+            .map_err(|e| format!("GLFW: {:?}", e))?;
+
+        glfw.window_hint(glfw::WindowHint::ContextVersion(4, 3));
+        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+            glfw::OpenGlProfileHint::Core,
+        ));
+        glfw.window_hint(glfw::WindowHint::Visible(false));
+        glfw.window_hint(glfw::WindowHint::ContextCreationApi(
+            glfw::ContextCreationApi::Egl,
+        ));
+        let mut created = glfw.create_window(width, height, "", glfw::WindowMode::Windowed);
+        if created.is_none() {
+            glfw.window_hint(glfw::WindowHint::ContextCreationApi(
+                glfw::ContextCreationApi::OsMesa,
+            ));
+            created = glfw.create_window(width, height, "", glfw::WindowMode::Windowed);
+        }
+        let (mut window, events) =
+            created.ok_or("nu s-a putut crea un context OpenGL 4.3 headless")?;
+
+        window.make_current();
+        Self::load_gl(&mut window);
+
+        let offscreen = FrameBuffer::new(width as i32, height as i32)?;
+        framebuffer::set_default_framebuffer(offscreen.framebuffer);
+        offscreen.bind();
+        self.init_gl();
+
+        self.glfw = Some(glfw);
+        self.glfw_window = Some(window);
+        self.glfw_events = Some(events);
+        self.offscreen = Some(offscreen);
+        Ok(())
+    }
+    /*
+    Pentru demo-uri: --headless desenează fără fereastră, iar --record/--replay merg
+    ca la `record_or_replay_from_args`.
+    */
+    pub fn create_from_args(&mut self, width: u32, height: u32, title: &str) {
+        if std::env::args().any(|arg| arg == "--headless") {
+            if let Err(e) = self.create_headless(width, height) {
+                panic!("{}", e);
+            }
+        } else {
+            self.create(width, height, title);
+        }
+        self.record_or_replay_from_args();
+    }
+    pub fn is_headless(&self) -> bool {
+        self.offscreen.is_some()
+    }
+    // dimensiunea în care se desenează: a FrameBuffer-ului headless, altfel a ferestrei
+    pub fn render_size(&self) -> (i32, i32) {
+        match self.offscreen {
+            Some(ref offscreen) => (offscreen.width, offscreen.height),
+            None => self.get_framebuffer_size(),
+        }
+    }
+    // ce s-a desenat până acum, RGBA de sus în jos, de dimensiunea render_size
+    pub fn read_pixels(&self) -> Vec<u8> {
+        if let Some(ref offscreen) = self.offscreen {
+            return offscreen.read_pixels();
+        }
+        let (width, height) = self.get_framebuffer_size();
+        framebuffer::read_pixels(0, width, height)
+    }
+    fn load_gl(window: &mut glfw::PWindow) {
         gl::load_with(|s| {
             window
                 .get_proc_address(s)
                 .map(|p| p as *const _)
                 .unwrap_or(std::ptr::null())
         });
-
+    }
+    fn init_gl(&self) {
         unsafe {
             gl::Viewport(0, 0, self.width as i32, self.height as i32);
 
@@ -139,12 +270,7 @@ impl Window {
             gl::DepthFunc(gl::LESS);
             gl::Enable(gl::BLEND);
             gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
-            gl::Viewport(0, 0, width as i32, height as i32);
         }
-
-        self.glfw = Some(glfw);
-        self.glfw_window = Some(window);
-        self.glfw_events = Some(events);
     }
     pub fn is_open(&self) -> bool {
         if let Some(ref window) = self.glfw_window {
@@ -160,88 +286,190 @@ impl Window {
     }
     
     pub fn get_key(&self, key: glfw::Key) -> Option<glfw::Action> {
-        if let Some(ref window) = self.glfw_window {
-            Some(window.get_key(key))
-        }
-        else {
-            None
+        if let Some(ref replay) = self.replay {
+            return Some(replay.key(key));
         }
+        self.glfw_window.as_ref().map(|w| w.get_key(key))
     }
     pub fn get_mouse_button(&self, button: glfw::MouseButton) -> Option<glfw::Action> {
-        if let Some(ref window) = self.glfw_window {
-            Some(window.get_mouse_button(button))
-        }
-        else {
-            None
+        if let Some(ref replay) = self.replay {
+            return Some(replay.mouse_button(button));
         }
+        self.glfw_window.as_ref().map(|w| w.get_mouse_button(button))
     }
     pub fn is_resized(&self) -> bool {
         self.resize
     }
+    // secunde între ultimele două poll_events, din înregistrare când redăm
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
     pub fn poll_events(&mut self) {
-        if let Some(ref mut glfw) = self.glfw {
-            glfw.poll_events();
-        } else {
-            return;
+        let live = self.poll_glfw();
+        // cât timp redăm, ce vine de la GLFW e ignorat
+        let frame = match self.replay.as_mut() {
+            Some(replay) => {
+                let frame = replay.next_frame();
+                if replay.is_finished() {
+                    self.replay = None;
+                }
+                frame
+            }
+            None => live,
+        };
+        if let Some(frame) = frame {
+            self.apply_frame(frame);
         }
+    }
+    // evenimentele unui frame, ca și cum ar fi venit de la poll_events
+    pub fn apply_frame(&mut self, frame: Frame) {
         self.resize = false;
         self.mouse.reset();
         self.keyboard.reset();
         self.drag_and_drop_files.clear();
-        if let Some(ref events) = self.glfw_events {
-            for (_, event) in glfw::flush_messages(events) {
-                match event {
-                    glfw::WindowEvent::FramebufferSize(w, h) => {
-                        self.width = w as u32;
-                        self.height = h as u32;
-                        self.resize = true;
-                        unsafe {
-                            gl::Viewport(0, 0, w, h);
-                        }
-                    }
-                    glfw::WindowEvent::CursorPos(x, y) => {
-                        self.mouse.x = x;
-                        self.mouse.y = y;
-                    }
-                    glfw::WindowEvent::MouseButton(button, action, modifiers) => {
-                        self.mouse.buttons.push(MouseButton {
-                            button,
-                            action,
-                            modifiers,
-                        });
-                    }
-                    glfw::WindowEvent::Scroll(x, y) => {
-                        if y > 0.0 {
-                            self.mouse.scroll.push(MouseScroll::Up);
-                        } else if y < 0.0 {
-                            self.mouse.scroll.push(MouseScroll::Down);
-                        }
+        if self.time > 0.0 {
+            self.delta_time = (frame.time - self.time).max(0.0) as f32;
+        }
+        self.time = frame.time;
+        for (_, event) in &frame.events {
+            self.handle_event(event.clone());
+        }
+        if let Some(ref mut recording) = self.recording {
+            recording.frames.push(frame);
+        }
+    }
 
-                        if x > 0.0 {
-                            self.mouse.scroll.push(MouseScroll::Right);
-                        } else if x < 0.0 {
-                            self.mouse.scroll.push(MouseScroll::Left);
-                        }
-                    }
-                    glfw::WindowEvent::Char(x) => {
-                        self.keyboard.char_keys.push(x);
-                    }
-                    glfw::WindowEvent::FileDrop(v) => {
-                        self.drag_and_drop_files = v;
-                    }
-                    glfw::WindowEvent::Key(key, scancode, action, modifiers) => {
-                        self.keyboard.keys.push(KeyButton {
-                            key,
-                            action,
-                            modifiers,
-                            scancode,
-                        });
+    /*
+    Înregistrează evenimentele primite de acum încolo. Cu `record_to` înregistrarea e
+    salvată în fișier la `stop_recording` sau când fereastra se închide.
+    */
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new(self.width, self.height));
+    }
+    pub fn record_to(&mut self, path: &str) {
+        self.start_recording();
+        self.record_path = Some(path.to_string());
+    }
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        let recording = self.recording.take()?;
+        if let Some(path) = self.record_path.take() {
+            match recording.save(&path) {
+                Ok(()) => println!("input înregistrat în {}", path),
+                Err(e) => println!("{}", e),
+            }
+        }
+        Some(recording)
+    }
+    // poll_events ia de acum evenimentele din înregistrare în loc de GLFW, până la capăt
+    pub fn replay(&mut self, recording: Recording) {
+        if recording.width > 0 && recording.height > 0 {
+            self.width = recording.width;
+            self.height = recording.height;
+        }
+        self.mouse.has_position = false;
+        self.time = 0.0;
+        self.delta_time = 0.0;
+        self.replay = Some(Replay::new(recording));
+    }
+    pub fn replay_file(&mut self, path: &str) -> Result<(), String> {
+        self.replay(Recording::load(path)?);
+        Ok(())
+    }
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+    /*
+    --record <fișier> înregistrează inputul demo-ului, --replay <fișier> îl redă.
+    Se apelează după `create`.
+    */
+    pub fn record_or_replay_from_args(&mut self) {
+        let args: Vec<String> = std::env::args().collect();
+        for pair in args.windows(2) {
+            match pair[0].as_str() {
+                "--record" => self.record_to(&pair[1]),
+                "--replay" => {
+                    if let Err(e) = self.replay_file(&pair[1]) {
+                        println!("{}", e);
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
     }
+
+    fn poll_glfw(&mut self) -> Option<Frame> {
+        let glfw = self.glfw.as_mut()?;
+        glfw.poll_events();
+        let time = glfw.get_time();
+        let events = match self.glfw_events {
+            Some(ref receiver) => glfw::flush_messages(receiver)
+                .filter(|(_, event)| Recording::is_recorded(event))
+                .collect(),
+            None => Vec::new(),
+        };
+        Some(Frame { time, events })
+    }
+    fn handle_event(&mut self, event: glfw::WindowEvent) {
+        match event {
+            glfw::WindowEvent::FramebufferSize(w, h) => {
+                self.width = w as u32;
+                self.height = h as u32;
+                self.resize = true;
+                unsafe {
+                    gl::Viewport(0, 0, w, h);
+                }
+            }
+            glfw::WindowEvent::CursorPos(x, y) => {
+                if self.mouse.has_position {
+                    self.mouse.dx += x - self.mouse.x;
+                    self.mouse.dy += y - self.mouse.y;
+                }
+                self.mouse.x = x;
+                self.mouse.y = y;
+                self.mouse.has_position = true;
+            }
+            glfw::WindowEvent::MouseButton(button, action, modifiers) => {
+                self.mouse.buttons.push(MouseButton {
+                    button,
+                    action,
+                    modifiers,
+                });
+            }
+            glfw::WindowEvent::Scroll(x, y) => {
+                self.mouse.scroll_x += x;
+                self.mouse.scroll_y += y;
+                if y > 0.0 {
+                    self.mouse.scroll.push(MouseScroll::Up);
+                } else if y < 0.0 {
+                    self.mouse.scroll.push(MouseScroll::Down);
+                }
+
+                if x > 0.0 {
+                    self.mouse.scroll.push(MouseScroll::Right);
+                } else if x < 0.0 {
+                    self.mouse.scroll.push(MouseScroll::Left);
+                }
+            }
+            glfw::WindowEvent::Char(x) => {
+                self.keyboard.char_keys.push(x);
+            }
+            glfw::WindowEvent::FileDrop(v) => {
+                self.drag_and_drop_files = v;
+            }
+            glfw::WindowEvent::Key(key, scancode, action, modifiers) => {
+                self.keyboard.keys.push(KeyButton {
+                    key,
+                    action,
+                    modifiers,
+                    scancode,
+                });
+            }
+            _ => {}
+        }
+    }
     pub fn get_framebuffer_size(&self) -> (i32, i32) {
         if let Some(win) = &self.glfw_window {
             win.get_framebuffer_size()
@@ -262,3 +490,32 @@ impl Window {
         }
     }
 }
+
+/*
+Context headless pentru teste. glfw::init nu e thread-safe și testele rulează în paralel,
+așa că toate testele cu OpenGL țin același lacăt cât trăiește fereastra. Testele astea sunt
+#[ignore], ca lipsa unui context să nu treacă drept reușită; `cargo test -- --ignored`
+le rulează și pică dacă nu se poate crea contextul.
+*/
+#[cfg(test)]
+pub(crate) struct HeadlessTest {
+    // câmpurile se distrug în ordine: întâi contextul, apoi lacătul
+    pub window: Window,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl HeadlessTest {
+    pub fn new(width: u32, height: u32) -> Self {
+        static GL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let lock = GL.lock().unwrap_or_else(|e| e.into_inner());
+        let mut window = Window::new();
+        if let Err(e) = window.create_headless(width, height) {
+            panic!("fără context OpenGL: {}", e);
+        }
+        Self {
+            window,
+            _lock: lock,
+        }
+    }
+}